@@ -0,0 +1,96 @@
+//! Packing of samples with fewer bits than the integer types holding them.
+//!
+//! Rows of packed samples start at a byte boundary, the samples are packed most significant bit
+//! first as in the image data of TIFF files.
+use std::io;
+
+/// Number of bytes of a row of `samples` samples with `bits` bits each, packed without padding
+pub(crate) fn packed_row_bytes(samples: usize, bits: u8) -> usize {
+    (samples * usize::from(bits))
+        .checked_sub(1)
+        .map_or(0, |last| last / 8 + 1)
+}
+
+/// Copies `len` bits starting at bit `src_bit` of `src` to bit `dst_bit` of `dst`.
+///
+/// Bits are counted from the most significant bit of each byte, like packed samples.
+pub(crate) fn copy_bits(dst: &mut [u8], dst_bit: usize, src: &[u8], src_bit: usize, len: usize) {
+    // Whole bytes are copied at once if both ranges start at a byte boundary.
+    let copied = if (dst_bit | src_bit) & 7 == 0 {
+        let bytes = len / 8;
+        dst[dst_bit / 8..][..bytes].copy_from_slice(&src[src_bit / 8..][..bytes]);
+        bytes * 8
+    } else {
+        0
+    };
+
+    for i in copied..len {
+        let (from, to) = (src_bit + i, dst_bit + i);
+        let mask = 0x80 >> (to % 8);
+        if src[from / 8] & (0x80 >> (from % 8)) != 0 {
+            dst[to / 8] |= mask;
+        } else {
+            dst[to / 8] &= !mask;
+        }
+    }
+}
+
+/// Calls `f` with the index and value of each of `len` samples packed with `bits <= 32` bits.
+pub(crate) fn for_each_packed_sample(
+    src: &[u8],
+    bits: u8,
+    len: usize,
+    mut f: impl FnMut(usize, u32),
+) {
+    let bits = usize::from(bits);
+    for i in 0..len {
+        let (byte, shift) = (i * bits / 8, i * bits % 8);
+        // A sample spans at most 5 bytes.
+        let window = (0..5).fold(0u64, |window, n| {
+            window << 8 | u64::from(src.get(byte + n).copied().unwrap_or(0))
+        });
+        f(i, (window << (24 + shift) >> (64 - bits)) as u32);
+    }
+}
+
+/// Packs rows of `row_samples` samples with `bits` bits each.
+///
+/// Each row is padded to a full byte, like the rows of the image data in the file. Returns an
+/// error if a sample does not fit into `bits` bits, signed samples have to be truncated to their
+/// two's complement in `bits` bits beforehand.
+pub(crate) fn pack_rows(
+    samples: impl Iterator<Item = u64>,
+    row_samples: usize,
+    bits: u8,
+) -> io::Result<Vec<u8>> {
+    let mut packed = Vec::new();
+    let (mut acc, mut acc_bits) = (0u64, 0);
+    let mut column = 0;
+
+    for sample in samples {
+        if sample >> (bits - 1) >> 1 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Sample value {} does not fit into {} bits", sample, bits),
+            ));
+        }
+
+        acc = acc << bits | sample;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            acc_bits -= 8;
+            packed.push((acc >> acc_bits) as u8);
+        }
+
+        column += 1;
+        if column == row_samples {
+            if acc_bits > 0 {
+                packed.push((acc << (8 - acc_bits)) as u8);
+                acc_bits = 0;
+            }
+            column = 0;
+        }
+    }
+
+    Ok(packed)
+}
@@ -0,0 +1,532 @@
+//! Decoding of the bilevel CCITT compressions
+//!
+//! This covers the modified Huffman run length encoding (compression 2), the one and two
+//! dimensional coding of ITU-T T.4 (Group 3, compression 3) and the coding of ITU-T T.6 (Group 4,
+//! compression 4). The decoded rows are packed with one bit per pixel, each row padded to a full
+//! byte. White pixels are stored as 0 and black ones as 1.
+use std::io::{self, Read};
+
+use crate::tags::{CompressionMethod, FillOrder};
+
+/// The T4Options bit selecting the two dimensional coding
+const OPTION_2D: u32 = 1;
+
+/// Settings of the fax coding of an image
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FaxOptions {
+    /// Width of the strip or tile in pixels
+    pub width: usize,
+    /// The value of the T4Options or T6Options tag
+    pub options: u32,
+    /// The bit order within the bytes of the data
+    pub fill_order: FillOrder,
+}
+
+/// Codes of the white terminating run lengths 0 to 63, as (code, length in bits)
+const WHITE_TERMINATING: [(u16, u8); 64] = [
+    (0b0011_0101, 8),
+    (0b00_0111, 6),
+    (0b0111, 4),
+    (0b1000, 4),
+    (0b1011, 4),
+    (0b1100, 4),
+    (0b1110, 4),
+    (0b1111, 4),
+    (0b1_0011, 5),
+    (0b1_0100, 5),
+    (0b0_0111, 5),
+    (0b0_1000, 5),
+    (0b00_1000, 6),
+    (0b00_0011, 6),
+    (0b11_0100, 6),
+    (0b11_0101, 6),
+    (0b10_1010, 6),
+    (0b10_1011, 6),
+    (0b010_0111, 7),
+    (0b000_1100, 7),
+    (0b000_1000, 7),
+    (0b001_0111, 7),
+    (0b000_0011, 7),
+    (0b000_0100, 7),
+    (0b010_1000, 7),
+    (0b010_1011, 7),
+    (0b001_0011, 7),
+    (0b010_0100, 7),
+    (0b001_1000, 7),
+    (0b0000_0010, 8),
+    (0b0000_0011, 8),
+    (0b0001_1010, 8),
+    (0b0001_1011, 8),
+    (0b0001_0010, 8),
+    (0b0001_0011, 8),
+    (0b0001_0100, 8),
+    (0b0001_0101, 8),
+    (0b0001_0110, 8),
+    (0b0001_0111, 8),
+    (0b0010_1000, 8),
+    (0b0010_1001, 8),
+    (0b0010_1010, 8),
+    (0b0010_1011, 8),
+    (0b0010_1100, 8),
+    (0b0010_1101, 8),
+    (0b0000_0100, 8),
+    (0b0000_0101, 8),
+    (0b0000_1010, 8),
+    (0b0000_1011, 8),
+    (0b0101_0010, 8),
+    (0b0101_0011, 8),
+    (0b0101_0100, 8),
+    (0b0101_0101, 8),
+    (0b0010_0100, 8),
+    (0b0010_0101, 8),
+    (0b0101_1000, 8),
+    (0b0101_1001, 8),
+    (0b0101_1010, 8),
+    (0b0101_1011, 8),
+    (0b0100_1010, 8),
+    (0b0100_1011, 8),
+    (0b0011_0010, 8),
+    (0b0011_0011, 8),
+    (0b0011_0100, 8),
+];
+
+/// Codes of the white make-up run lengths 64 to 1728, in steps of 64
+const WHITE_MAKEUP: [(u16, u8); 27] = [
+    (0b1_1011, 5),
+    (0b1_0010, 5),
+    (0b01_0111, 6),
+    (0b011_0111, 7),
+    (0b0011_0110, 8),
+    (0b0011_0111, 8),
+    (0b0110_0100, 8),
+    (0b0110_0101, 8),
+    (0b0110_1000, 8),
+    (0b0110_0111, 8),
+    (0b0_1100_1100, 9),
+    (0b0_1100_1101, 9),
+    (0b0_1101_0010, 9),
+    (0b0_1101_0011, 9),
+    (0b0_1101_0100, 9),
+    (0b0_1101_0101, 9),
+    (0b0_1101_0110, 9),
+    (0b0_1101_0111, 9),
+    (0b0_1101_1000, 9),
+    (0b0_1101_1001, 9),
+    (0b0_1101_1010, 9),
+    (0b0_1101_1011, 9),
+    (0b0_1001_1000, 9),
+    (0b0_1001_1001, 9),
+    (0b0_1001_1010, 9),
+    (0b01_1000, 6),
+    (0b0_1001_1011, 9),
+];
+
+/// Codes of the black terminating run lengths 0 to 63
+const BLACK_TERMINATING: [(u16, u8); 64] = [
+    (0b00_0011_0111, 10),
+    (0b010, 3),
+    (0b11, 2),
+    (0b10, 2),
+    (0b011, 3),
+    (0b0011, 4),
+    (0b0010, 4),
+    (0b0_0011, 5),
+    (0b00_0101, 6),
+    (0b00_0100, 6),
+    (0b000_0100, 7),
+    (0b000_0101, 7),
+    (0b000_0111, 7),
+    (0b0000_0100, 8),
+    (0b0000_0111, 8),
+    (0b0_0001_1000, 9),
+    (0b00_0001_0111, 10),
+    (0b00_0001_1000, 10),
+    (0b00_0000_1000, 10),
+    (0b000_0110_0111, 11),
+    (0b000_0110_1000, 11),
+    (0b000_0110_1100, 11),
+    (0b000_0011_0111, 11),
+    (0b000_0010_1000, 11),
+    (0b000_0001_0111, 11),
+    (0b000_0001_1000, 11),
+    (0b0000_1100_1010, 12),
+    (0b0000_1100_1011, 12),
+    (0b0000_1100_1100, 12),
+    (0b0000_1100_1101, 12),
+    (0b0000_0110_1000, 12),
+    (0b0000_0110_1001, 12),
+    (0b0000_0110_1010, 12),
+    (0b0000_0110_1011, 12),
+    (0b0000_1101_0010, 12),
+    (0b0000_1101_0011, 12),
+    (0b0000_1101_0100, 12),
+    (0b0000_1101_0101, 12),
+    (0b0000_1101_0110, 12),
+    (0b0000_1101_0111, 12),
+    (0b0000_0110_1100, 12),
+    (0b0000_0110_1101, 12),
+    (0b0000_1101_1010, 12),
+    (0b0000_1101_1011, 12),
+    (0b0000_0101_0100, 12),
+    (0b0000_0101_0101, 12),
+    (0b0000_0101_0110, 12),
+    (0b0000_0101_0111, 12),
+    (0b0000_0110_0100, 12),
+    (0b0000_0110_0101, 12),
+    (0b0000_0101_0010, 12),
+    (0b0000_0101_0011, 12),
+    (0b0000_0010_0100, 12),
+    (0b0000_0011_0111, 12),
+    (0b0000_0011_1000, 12),
+    (0b0000_0010_0111, 12),
+    (0b0000_0010_1000, 12),
+    (0b0000_0101_1000, 12),
+    (0b0000_0101_1001, 12),
+    (0b0000_0010_1011, 12),
+    (0b0000_0010_1100, 12),
+    (0b0000_0101_1010, 12),
+    (0b0000_0110_0110, 12),
+    (0b0000_0110_0111, 12),
+];
+
+/// Codes of the black make-up run lengths 64 to 1728, in steps of 64
+const BLACK_MAKEUP: [(u16, u8); 27] = [
+    (0b00_0000_1111, 10),
+    (0b0000_1100_1000, 12),
+    (0b0000_1100_1001, 12),
+    (0b0000_0101_1011, 12),
+    (0b0000_0011_0011, 12),
+    (0b0000_0011_0100, 12),
+    (0b0000_0011_0101, 12),
+    (0b0_0000_0110_1100, 13),
+    (0b0_0000_0110_1101, 13),
+    (0b0_0000_0100_1010, 13),
+    (0b0_0000_0100_1011, 13),
+    (0b0_0000_0100_1100, 13),
+    (0b0_0000_0100_1101, 13),
+    (0b0_0000_0111_0010, 13),
+    (0b0_0000_0111_0011, 13),
+    (0b0_0000_0111_0100, 13),
+    (0b0_0000_0111_0101, 13),
+    (0b0_0000_0111_0110, 13),
+    (0b0_0000_0111_0111, 13),
+    (0b0_0000_0101_0010, 13),
+    (0b0_0000_0101_0011, 13),
+    (0b0_0000_0101_0100, 13),
+    (0b0_0000_0101_0101, 13),
+    (0b0_0000_0101_1010, 13),
+    (0b0_0000_0101_1011, 13),
+    (0b0_0000_0110_0100, 13),
+    (0b0_0000_0110_0101, 13),
+];
+
+/// Codes of the make-up run lengths 1792 to 2560 shared by both colors, in steps of 64
+const EXTENDED_MAKEUP: [(u16, u8); 13] = [
+    (0b000_0000_1000, 11),
+    (0b000_0000_1100, 11),
+    (0b000_0000_1101, 11),
+    (0b0000_0001_0010, 12),
+    (0b0000_0001_0011, 12),
+    (0b0000_0001_0100, 12),
+    (0b0000_0001_0101, 12),
+    (0b0000_0001_0110, 12),
+    (0b0000_0001_0111, 12),
+    (0b0000_0001_1100, 12),
+    (0b0000_0001_1101, 12),
+    (0b0000_0001_1110, 12),
+    (0b0000_0001_1111, 12),
+];
+
+/// Length of the longest run length code
+const MAX_CODE_LEN: u8 = 13;
+
+/// Lookup table from the next `MAX_CODE_LEN` bits to the (length, run) of the code they start with
+struct RunTable(Vec<(u8, u16)>);
+
+impl RunTable {
+    fn new(terminating: &[(u16, u8)], makeup: &[(u16, u8)]) -> RunTable {
+        let mut table = vec![(0, 0); 1 << MAX_CODE_LEN];
+
+        let terminating = terminating.iter().zip(0..);
+        let makeup = makeup.iter().chain(EXTENDED_MAKEUP.iter()).zip(1..);
+        let codes = terminating.chain(makeup.map(|(code, n)| (code, n * 64)));
+        for (&(code, len), run) in codes {
+            let shift = MAX_CODE_LEN - len;
+            let start = usize::from(code) << shift;
+            for entry in &mut table[start..start + (1 << shift)] {
+                *entry = (len, run);
+            }
+        }
+
+        RunTable(table)
+    }
+}
+
+/// Coding modes of the two dimensional coding
+enum Mode {
+    Pass,
+    Horizontal,
+    Vertical(isize),
+    /// An end of line code, only valid to end the data in two dimensional coding
+    EndOfLine,
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads single bits, starting with the most significant one of each byte.
+struct BitReader {
+    data: Vec<u8>,
+    /// Position in bits
+    position: usize,
+}
+
+impl BitReader {
+    /// Returns the next `n <= 16` bits, padded with zeros at the end of the data.
+    fn peek(&self, n: u8) -> u16 {
+        let byte = self.position / 8;
+        let mut bits = 0u32;
+        for i in 0..3 {
+            bits = bits << 8 | u32::from(self.data.get(byte + i).copied().unwrap_or(0));
+        }
+        let bits = bits << (self.position % 8) >> (24 - n);
+        bits as u16 & (0xffff >> (16 - n))
+    }
+
+    fn consume(&mut self, n: u8) {
+        self.position += usize::from(n);
+    }
+
+    fn align(&mut self) {
+        self.position = self
+            .position
+            .checked_sub(1)
+            .map_or(0, |last| (last / 8 + 1) * 8);
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.data.len() * 8
+    }
+
+    /// Skips fill bits and an end of line code if the next bits contain one.
+    fn skip_end_of_line(&mut self) {
+        // No code other than the end of line starts with 11 zero bits.
+        if self.peek(12) > 1 {
+            return;
+        }
+        while !self.at_end() && self.peek(1) == 0 {
+            self.consume(1);
+        }
+        self.consume(1);
+    }
+
+    fn read_mode(&mut self) -> io::Result<Mode> {
+        let bits = self.peek(7);
+        let (len, mode) = match bits {
+            _ if bits >> 6 == 0b1 => (1, Mode::Vertical(0)),
+            _ if bits >> 4 == 0b011 => (3, Mode::Vertical(1)),
+            _ if bits >> 4 == 0b010 => (3, Mode::Vertical(-1)),
+            _ if bits >> 4 == 0b001 => (3, Mode::Horizontal),
+            _ if bits >> 3 == 0b0001 => (4, Mode::Pass),
+            _ if bits >> 1 == 0b00_0011 => (6, Mode::Vertical(2)),
+            _ if bits >> 1 == 0b00_0010 => (6, Mode::Vertical(-2)),
+            0b000_0011 => (7, Mode::Vertical(3)),
+            0b000_0010 => (7, Mode::Vertical(-3)),
+            0b000_0000 => (0, Mode::EndOfLine),
+            _ => return Err(invalid_data("CCITT uncompressed mode is unsupported")),
+        };
+        self.consume(len);
+        Ok(mode)
+    }
+}
+
+/// Decoder of a single strip or tile
+struct FaxDecoder {
+    bits: BitReader,
+    white: RunTable,
+    black: RunTable,
+    width: usize,
+    /// Positions at which the color of the previous row changes, starting with white
+    reference: Vec<usize>,
+    /// Positions at which the color of the current row changes, starting with white
+    current: Vec<usize>,
+}
+
+impl FaxDecoder {
+    /// Reads the make-up and terminating codes of a run of the color
+    fn read_run(&mut self, black: bool) -> io::Result<usize> {
+        let table = if black { &self.black } else { &self.white };
+        let mut run = 0;
+        loop {
+            let (len, part) = table.0[usize::from(self.bits.peek(MAX_CODE_LEN))];
+            if len == 0 {
+                return Err(invalid_data("invalid CCITT run length code"));
+            }
+            self.bits.consume(len);
+            run += usize::from(part);
+            if part < 64 {
+                return Ok(run);
+            }
+        }
+    }
+
+    /// Decodes a row with one dimensional coding
+    fn decode_1d(&mut self) -> io::Result<bool> {
+        self.current.clear();
+        let mut position = 0;
+        let mut black = false;
+        while position < self.width {
+            let run = self.read_run(black)?;
+            position += run;
+            self.current.push(position);
+            black = !black;
+        }
+        Ok(true)
+    }
+
+    /// Decodes a row with two dimensional coding, relative to the previous row
+    ///
+    /// Returns `false` if the data ended before the row.
+    fn decode_2d(&mut self) -> io::Result<bool> {
+        self.current.clear();
+        let width = self.width;
+        // The position of a0, None for the imaginary pixel in front of the row.
+        let mut a0: Option<usize> = None;
+        let mut black = false;
+        // Index of the first changing element of the reference row right of a0.
+        let mut next = 0;
+
+        while a0.unwrap_or(0) < width {
+            let reference = &self.reference;
+            while next < reference.len() && matches!(a0, Some(a0) if reference[next] <= a0) {
+                next += 1;
+            }
+            // b1 has to change to the opposite color of a0, changes to black have even indices.
+            let b1_index = next + usize::from(next % 2 != usize::from(black));
+            let b1 = self.reference.get(b1_index).copied().unwrap_or(width);
+            let b2 = self.reference.get(b1_index + 1).copied().unwrap_or(width);
+
+            match self.bits.read_mode()? {
+                // The color of a0 continues below b2, without a change on this row.
+                Mode::Pass => a0 = Some(b2),
+                Mode::Horizontal => {
+                    let start = a0.unwrap_or(0);
+                    let a1 = start + self.read_run(black)?;
+                    let a2 = a1 + self.read_run(!black)?;
+                    self.current.push(a1);
+                    self.current.push(a2);
+                    a0 = Some(a2);
+                }
+                Mode::Vertical(delta) => {
+                    let a1 = b1 as isize + delta;
+                    let a1 = match a0 {
+                        Some(a0) if a1 < a0 as isize => None,
+                        _ if a1 < 0 => None,
+                        _ => Some(a1 as usize),
+                    }
+                    .ok_or_else(|| invalid_data("invalid CCITT vertical mode"))?;
+                    self.current.push(a1);
+                    a0 = Some(a1);
+                    black = !black;
+                }
+                Mode::EndOfLine if a0.is_none() => return Ok(false),
+                Mode::EndOfLine => return Err(invalid_data("unexpected CCITT end of line")),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Writes the current row as packed bits and makes it the reference of the next row.
+    fn write_row(&mut self, row: &mut [u8]) {
+        for pair in self.current.chunks(2) {
+            let start = pair[0].min(self.width);
+            let end = pair.get(1).copied().unwrap_or(self.width).min(self.width);
+            for x in start..end {
+                row[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        std::mem::swap(&mut self.reference, &mut self.current);
+    }
+}
+
+/// Reader that decompresses the CCITT modified Huffman, T.4 and T.6 codings
+pub struct FaxReader {
+    buffer: io::Cursor<Vec<u8>>,
+}
+
+impl FaxReader {
+    /// Wraps a reader, decoding `rows` rows of the strip or tile.
+    pub(crate) fn new<R: Read>(
+        reader: R,
+        compressed_length: usize,
+        compression_method: CompressionMethod,
+        options: FaxOptions,
+        rows: usize,
+    ) -> io::Result<FaxReader> {
+        let mut data = Vec::new();
+        reader
+            .take(compressed_length as u64)
+            .read_to_end(&mut data)?;
+        if options.fill_order == FillOrder::LsbToMsb {
+            data.iter_mut().for_each(|byte| *byte = byte.reverse_bits());
+        }
+
+        let mut decoder = FaxDecoder {
+            bits: BitReader { data, position: 0 },
+            white: RunTable::new(&WHITE_TERMINATING, &WHITE_MAKEUP),
+            black: RunTable::new(&BLACK_TERMINATING, &BLACK_MAKEUP),
+            width: options.width,
+            reference: Vec::new(),
+            current: Vec::new(),
+        };
+
+        let row_bytes = options.width.checked_sub(1).map_or(0, |last| last / 8 + 1);
+        let mut buffer = vec![0; row_bytes * rows];
+        for row in buffer.chunks_mut(row_bytes.max(1)) {
+            let decoded = match compression_method {
+                CompressionMethod::Huffman => {
+                    // Each row begins at a byte boundary.
+                    decoder.bits.align();
+                    !decoder.bits.at_end() && decoder.decode_1d()?
+                }
+                CompressionMethod::Fax3 => {
+                    decoder.bits.skip_end_of_line();
+                    if decoder.bits.at_end() {
+                        false
+                    } else if options.options & OPTION_2D == 0 {
+                        decoder.decode_1d()?
+                    } else {
+                        // A tag bit selects the coding of each row.
+                        let one_dimensional = decoder.bits.peek(1) == 1;
+                        decoder.bits.consume(1);
+                        if one_dimensional {
+                            decoder.decode_1d()?
+                        } else {
+                            decoder.decode_2d()?
+                        }
+                    }
+                }
+                CompressionMethod::Fax4 => decoder.decode_2d()?,
+                _ => unreachable!(),
+            };
+
+            // Rows missing from the data are left white.
+            if !decoded {
+                break;
+            }
+            decoder.write_row(row);
+        }
+
+        Ok(FaxReader {
+            buffer: io::Cursor::new(buffer),
+        })
+    }
+}
+
+impl Read for FaxReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.buffer.read(buf)
+    }
+}
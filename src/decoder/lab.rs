@@ -0,0 +1,139 @@
+//! CIE L*a*b* samples and their conversion to sRGB
+//!
+//! See section 23 of the TIFF 6.0 specification and the ICCLab encoding of the TIFF Technical
+//! Note #1 by Adobe.
+use crate::tags::Tag;
+use crate::{TiffFormatError, TiffResult};
+
+/// The chromaticity of the D50 white point, the default white of L*a*b* images.
+const D50: [f32; 2] = [0.3457, 0.3585];
+
+/// The XYZ tristimulus values of the D65 white point of sRGB.
+const D65_XYZ: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// The Bradford transform of XYZ values to cone responses.
+const BRADFORD: [[f32; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// The inverse of the Bradford transform.
+const BRADFORD_INVERSE: [[f32; 3]; 3] = [
+    [0.986_993, -0.147_054, 0.159_963],
+    [0.432_305, 0.518_360, 0.049_291],
+    [-0.008_529, 0.040_043, 0.968_487],
+];
+
+/// The transform of XYZ values relative to D65 to linear sRGB.
+const XYZ_TO_SRGB: [[f32; 3]; 3] = [
+    [3.240_454, -1.537_138_5, -0.498_531_4],
+    [-0.969_266, 1.876_010_8, 0.041_556],
+    [0.055_643_4, -0.204_025_9, 1.057_225_2],
+];
+
+/// Converts a sample of an L*a*b* image to its L*, a* or b* value.
+///
+/// L* ranges from 0 to 100. The a* and b* samples are signed integers in CIELab images and are
+/// offset by half of their range in ICCLab images, they are scaled to the range of 8 bit
+/// samples, so 16 bit samples have 8 fractional bits.
+pub(crate) fn lab_value(sample: u16, bits: u8, channel: usize, signed: bool) -> f32 {
+    let full = 1u32 << bits;
+    let half = full / 2;
+    let sample = u32::from(sample);
+    if channel == 0 {
+        return sample as f32 * 100.0 / (full - 1) as f32;
+    }
+
+    let value = if !signed {
+        sample as f32 - half as f32
+    } else if sample >= half {
+        sample as f32 - full as f32
+    } else {
+        sample as f32
+    };
+    value * 256.0 / full as f32
+}
+
+/// The conversion of L*a*b* values to sRGB.
+pub(crate) struct LabConversion {
+    /// The XYZ tristimulus values of the white point of the L*a*b* values.
+    white: [f32; 3],
+    /// The transform of XYZ values relative to the white point to linear sRGB.
+    matrix: [[f32; 3]; 3],
+}
+
+impl LabConversion {
+    /// Creates the conversion from the value of the `WhitePoint` tag.
+    pub fn new(white_point: Option<Vec<f32>>) -> TiffResult<Self> {
+        let (x, y) = match white_point.as_deref() {
+            None => (D50[0], D50[1]),
+            Some(&[x, y]) if y > 0.0 => (x, y),
+            Some(_) => return Err(TiffFormatError::InvalidTagValueType(Tag::WhitePoint).into()),
+        };
+        let white = [x / y, 1.0, (1.0 - x - y) / y];
+
+        // Adapt the colors from the white point to D65 with the Bradford transform.
+        let source = multiply_vector(&BRADFORD, white);
+        let target = multiply_vector(&BRADFORD, D65_XYZ);
+        let mut scaled = BRADFORD;
+        for (row, (source, target)) in scaled.iter_mut().zip(source.iter().zip(target.iter())) {
+            for value in row.iter_mut() {
+                *value *= target / source;
+            }
+        }
+        let adaptation = multiply(&BRADFORD_INVERSE, &scaled);
+
+        Ok(LabConversion {
+            white,
+            matrix: multiply(&XYZ_TO_SRGB, &adaptation),
+        })
+    }
+
+    /// Converts the L*a*b* values of a pixel to sRGB samples with 8 bits each.
+    pub fn to_rgb(&self, [l, a, b]: [f32; 3]) -> [u8; 3] {
+        let inverse = |t: f32| {
+            if t > 6.0 / 29.0 {
+                t * t * t
+            } else {
+                3.0 * (6.0 / 29.0) * (6.0 / 29.0) * (t - 4.0 / 29.0)
+            }
+        };
+        let fy = (l + 16.0) / 116.0;
+        let xyz = [
+            self.white[0] * inverse(fy + a / 500.0),
+            self.white[1] * inverse(fy),
+            self.white[2] * inverse(fy - b / 200.0),
+        ];
+
+        let gamma = |linear: f32| {
+            let encoded = if linear <= 0.003_130_8 {
+                12.92 * linear
+            } else {
+                1.055 * linear.powf(1.0 / 2.4) - 0.055
+            };
+            // Casting saturates values outside of the range of `u8`.
+            (encoded * 255.0).round() as u8
+        };
+        let [r, g, b] = multiply_vector(&self.matrix, xyz);
+        [gamma(r), gamma(g), gamma(b)]
+    }
+}
+
+fn multiply_vector(matrix: &[[f32; 3]; 3], vector: [f32; 3]) -> [f32; 3] {
+    let mut result = [0.0; 3];
+    for (result, row) in result.iter_mut().zip(matrix.iter()) {
+        *result = row.iter().zip(vector.iter()).map(|(m, v)| m * v).sum();
+    }
+    result
+}
+
+fn multiply(left: &[[f32; 3]; 3], right: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut result = [[0.0; 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| left[i][k] * right[k][j]).sum();
+        }
+    }
+    result
+}
@@ -3,21 +3,35 @@ use std::convert::TryFrom;
 use std::io::{self, Read, Seek};
 use std::{cmp, ops::Range};
 
+use crate::bitpack::{copy_bits, for_each_packed_sample, pack_rows, packed_row_bytes};
+use crate::exif::{Exif, Gps};
+use crate::gdal::{GdalMetadata, NoData};
+use crate::geotiff::GeoTiff;
 use crate::{
     bytecast, ColorType, TiffError, TiffFormatError, TiffResult, TiffUnsupportedError, UsageError,
 };
 
 use self::ifd::Directory;
 use crate::tags::{
-    CompressionMethod, PhotometricInterpretation, Predictor, SampleFormat, Tag, Type,
+    CompressionMethod, ExtraSamples, FillOrder, GpsTag, Orientation, PhotometricInterpretation,
+    PlanarConfiguration, Predictor, SampleFormat, SubfileType, Tag, Type,
 };
 
+use self::fax::{FaxOptions, FaxReader};
+use self::lab::LabConversion;
+use self::ycbcr::YCbCrConversion;
+
 use self::stream::{
-    ByteOrder, DeflateReader, EndianReader, JpegReader, LZWReader, PackBitsReader, SmartReader,
+    ByteOrder, DeflateReader, EndianReader, JpegReader, LZWReader, PackBitsReader,
+    ReverseBitsReader, SmartReader,
 };
 
+mod fax;
 pub mod ifd;
+mod lab;
+mod sgilog;
 mod stream;
+mod ycbcr;
 
 /// Result of a decoding process
 #[derive(Debug)]
@@ -139,6 +153,298 @@ impl DecodingResult {
             DecodingResult::I64(ref mut buf) => DecodingBuffer::I64(&mut buf[start..]),
         }
     }
+
+    /// Transposes the samples, read as a matrix with `rows` rows.
+    ///
+    /// This converts between chunky and planar sample layouts: the planes of a planar image are
+    /// the rows of a matrix whose columns are the pixels, and vice versa.
+    fn transposed(self, rows: usize, limits: &Limits) -> TiffResult<DecodingResult> {
+        Ok(match self {
+            DecodingResult::U8(buf) => DecodingResult::U8(transpose(&buf, rows, limits)?),
+            DecodingResult::U16(buf) => DecodingResult::U16(transpose(&buf, rows, limits)?),
+            DecodingResult::U32(buf) => DecodingResult::U32(transpose(&buf, rows, limits)?),
+            DecodingResult::U64(buf) => DecodingResult::U64(transpose(&buf, rows, limits)?),
+            DecodingResult::F32(buf) => DecodingResult::F32(transpose(&buf, rows, limits)?),
+            DecodingResult::F64(buf) => DecodingResult::F64(transpose(&buf, rows, limits)?),
+            DecodingResult::I8(buf) => DecodingResult::I8(transpose(&buf, rows, limits)?),
+            DecodingResult::I16(buf) => DecodingResult::I16(transpose(&buf, rows, limits)?),
+            DecodingResult::I32(buf) => DecodingResult::I32(transpose(&buf, rows, limits)?),
+            DecodingResult::I64(buf) => DecodingResult::I64(transpose(&buf, rows, limits)?),
+        })
+    }
+
+    /// Rearranges the pixels of consecutive planes stored with `orientation` to top-left order.
+    ///
+    /// Each plane has the given stored width and height and `samples` samples per pixel.
+    fn oriented(
+        self,
+        planes: &[(usize, usize)],
+        samples: usize,
+        orientation: Orientation,
+    ) -> DecodingResult {
+        match self {
+            DecodingResult::U8(buf) => {
+                DecodingResult::U8(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::U16(buf) => {
+                DecodingResult::U16(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::U32(buf) => {
+                DecodingResult::U32(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::U64(buf) => {
+                DecodingResult::U64(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::F32(buf) => {
+                DecodingResult::F32(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::F64(buf) => {
+                DecodingResult::F64(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::I8(buf) => {
+                DecodingResult::I8(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::I16(buf) => {
+                DecodingResult::I16(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::I32(buf) => {
+                DecodingResult::I32(orient(&buf, planes, samples, orientation))
+            }
+            DecodingResult::I64(buf) => {
+                DecodingResult::I64(orient(&buf, planes, samples, orientation))
+            }
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match *self {
+            DecodingResult::U8(ref buf) => buf.len(),
+            DecodingResult::U16(ref buf) => buf.len(),
+            DecodingResult::U32(ref buf) => buf.len(),
+            DecodingResult::U64(ref buf) => buf.len(),
+            DecodingResult::F32(ref buf) => buf.len(),
+            DecodingResult::F64(ref buf) => buf.len(),
+            DecodingResult::I8(ref buf) => buf.len(),
+            DecodingResult::I16(ref buf) => buf.len(),
+            DecodingResult::I32(ref buf) => buf.len(),
+            DecodingResult::I64(ref buf) => buf.len(),
+        }
+    }
+
+    /// Splits the samples into consecutive results with the given lengths.
+    fn split(self, lengths: &[usize]) -> Vec<DecodingResult> {
+        match self {
+            DecodingResult::U8(buf) => split(buf, lengths, DecodingResult::U8),
+            DecodingResult::U16(buf) => split(buf, lengths, DecodingResult::U16),
+            DecodingResult::U32(buf) => split(buf, lengths, DecodingResult::U32),
+            DecodingResult::U64(buf) => split(buf, lengths, DecodingResult::U64),
+            DecodingResult::F32(buf) => split(buf, lengths, DecodingResult::F32),
+            DecodingResult::F64(buf) => split(buf, lengths, DecodingResult::F64),
+            DecodingResult::I8(buf) => split(buf, lengths, DecodingResult::I8),
+            DecodingResult::I16(buf) => split(buf, lengths, DecodingResult::I16),
+            DecodingResult::I32(buf) => split(buf, lengths, DecodingResult::I32),
+            DecodingResult::I64(buf) => split(buf, lengths, DecodingResult::I64),
+        }
+    }
+
+    /// Stores the sample with the raw `bits` bits `value` at `index`.
+    ///
+    /// Signed samples are sign extended, floating point samples are reinterpreted.
+    fn set_sample(&mut self, index: usize, value: u64, bits: u8) {
+        // Moves the sign bit of a sample to the sign bit of an `i64`.
+        let shift = 64 - u32::from(bits);
+        let signed = (value << shift) as i64 >> shift;
+        match *self {
+            DecodingResult::U8(ref mut buf) => buf[index] = value as u8,
+            DecodingResult::U16(ref mut buf) => buf[index] = value as u16,
+            DecodingResult::U32(ref mut buf) => buf[index] = value as u32,
+            DecodingResult::U64(ref mut buf) => buf[index] = value,
+            DecodingResult::F32(ref mut buf) => buf[index] = f32::from_bits(value as u32),
+            DecodingResult::F64(ref mut buf) => buf[index] = f64::from_bits(value),
+            DecodingResult::I8(ref mut buf) => buf[index] = signed as i8,
+            DecodingResult::I16(ref mut buf) => buf[index] = signed as i16,
+            DecodingResult::I32(ref mut buf) => buf[index] = signed as i32,
+            DecodingResult::I64(ref mut buf) => buf[index] = signed,
+        }
+    }
+}
+
+fn split<T>(
+    mut buf: Vec<T>,
+    lengths: &[usize],
+    result: fn(Vec<T>) -> DecodingResult,
+) -> Vec<DecodingResult> {
+    let mut parts = Vec::with_capacity(lengths.len());
+    for &length in lengths.iter().rev() {
+        let start = buf.len().saturating_sub(length);
+        parts.push(result(buf.split_off(start)));
+    }
+    parts.reverse();
+    parts
+}
+
+/// Reads the raw value of the sample with `bits` bits starting at bit `start` of `data`.
+///
+/// Samples of 8, 16, 32 or 64 bits starting at a byte are stored in the byte order of the file,
+/// all others are packed with their most significant bit first.
+fn read_sample(data: &[u8], start: usize, bits: u8, byte_order: ByteOrder) -> u64 {
+    let bits = usize::from(bits);
+    let (byte, shift) = (start / 8, start % 8);
+    match (shift, bits) {
+        (0, 8) | (0, 16) | (0, 32) | (0, 64) => {
+            let bytes = data[byte..byte + bits / 8].iter();
+            let add = |value: u64, &byte: &u8| value << 8 | u64::from(byte);
+            match byte_order {
+                ByteOrder::LittleEndian => bytes.rev().fold(0, add),
+                ByteOrder::BigEndian => bytes.fold(0, add),
+            }
+        }
+        _ => (start..start + bits).fold(0, |value, bit| {
+            value << 1 | u64::from(data[bit / 8] >> (7 - bit % 8) & 1)
+        }),
+    }
+}
+
+/// Whether images with the given orientation are displayed with their rows as columns
+fn swaps_dimensions(orientation: Orientation) -> bool {
+    matches!(
+        orientation,
+        Orientation::LeftTop
+            | Orientation::RightTop
+            | Orientation::RightBottom
+            | Orientation::LeftBottom
+    )
+}
+
+fn orient<T: Copy>(
+    buf: &[T],
+    planes: &[(usize, usize)],
+    samples: usize,
+    orientation: Orientation,
+) -> Vec<T> {
+    let mut oriented = Vec::with_capacity(buf.len());
+    let mut start = 0;
+    for &(width, height) in planes {
+        let plane = &buf[start..start + width * height * samples];
+        start += plane.len();
+
+        // The column and row of the stored pixel displayed at column `x` and row `y`.
+        let source = |x: usize, y: usize| match orientation {
+            Orientation::TopRight => (width - 1 - x, y),
+            Orientation::BottomRight => (width - 1 - x, height - 1 - y),
+            Orientation::BottomLeft => (x, height - 1 - y),
+            Orientation::LeftTop => (y, x),
+            Orientation::RightTop => (y, height - 1 - x),
+            Orientation::RightBottom => (width - 1 - y, height - 1 - x),
+            Orientation::LeftBottom => (width - 1 - y, x),
+            _ => (x, y),
+        };
+        let (columns, rows) = if swaps_dimensions(orientation) {
+            (height, width)
+        } else {
+            (width, height)
+        };
+
+        for y in 0..rows {
+            for x in 0..columns {
+                let (column, row) = source(x, y);
+                oriented.extend_from_slice(&plane[(row * width + column) * samples..][..samples]);
+            }
+        }
+    }
+    oriented
+}
+
+fn transpose<T: Copy>(buf: &[T], rows: usize, limits: &Limits) -> TiffResult<Vec<T>> {
+    // The transposed samples are collected in a second buffer of the same size, both buffers
+    // together must not exceed the limit.
+    let bytes = std::mem::size_of_val(buf);
+    if bytes > limits.decoding_buffer_size.saturating_sub(bytes) {
+        return Err(TiffError::LimitsExceeded);
+    }
+
+    let columns = buf.len() / rows;
+    let mut transposed = Vec::with_capacity(buf.len());
+    for column in 0..columns {
+        transposed.extend((0..rows).map(|row| buf[row * columns + column]));
+    }
+    Ok(transposed)
+}
+
+fn copy_strided<T: Copy>(
+    dst: &mut [T],
+    dst_start: usize,
+    dst_step: usize,
+    src: &[T],
+    src_start: usize,
+    src_step: usize,
+    len: usize,
+) {
+    if dst_step == 1 && src_step == 1 {
+        dst[dst_start..dst_start + len].copy_from_slice(&src[src_start..src_start + len]);
+    } else {
+        for i in 0..len {
+            dst[dst_start + i * dst_step] = src[src_start + i * src_step];
+        }
+    }
+}
+
+/// Expands a row of packed samples to one integer of the buffer's type per sample.
+///
+/// Signed samples are sign extended.
+fn unpack_row(src: &[u8], dst: DecodingBuffer, bits: u8) {
+    // Moves the sign bit of a sample to the sign bit of an `i32`.
+    let shift = 32 - u32::from(bits);
+    let signed = |value: u32| (value << shift) as i32 >> shift;
+    match dst {
+        DecodingBuffer::U8(buf) => {
+            for_each_packed_sample(src, bits, buf.len(), |i, v| buf[i] = v as u8)
+        }
+        DecodingBuffer::U16(buf) => {
+            for_each_packed_sample(src, bits, buf.len(), |i, v| buf[i] = v as u16)
+        }
+        DecodingBuffer::U32(buf) => for_each_packed_sample(src, bits, buf.len(), |i, v| buf[i] = v),
+        DecodingBuffer::I8(buf) => {
+            for_each_packed_sample(src, bits, buf.len(), |i, v| buf[i] = signed(v) as i8)
+        }
+        DecodingBuffer::I16(buf) => {
+            for_each_packed_sample(src, bits, buf.len(), |i, v| buf[i] = signed(v) as i16)
+        }
+        DecodingBuffer::I32(buf) => {
+            for_each_packed_sample(src, bits, buf.len(), |i, v| buf[i] = signed(v))
+        }
+        DecodingBuffer::U64(_)
+        | DecodingBuffer::I64(_)
+        | DecodingBuffer::F32(_)
+        | DecodingBuffer::F64(_) => unreachable!(),
+    }
+}
+
+/// Divides the first `alpha` samples of each pixel by the sample at index `alpha`, scaled to
+/// `max` and rounded to the nearest integer.
+fn unassociate_integers<T>(
+    buf: &mut [T],
+    max: u64,
+    alpha: usize,
+    pixels: usize,
+    index: impl Fn(usize, usize) -> usize,
+) where
+    T: Copy + Into<u64> + TryFrom<u64>,
+{
+    let max = u128::from(max);
+    for pixel in 0..pixels {
+        let alpha_value = u128::from(buf[index(pixel, alpha)].into());
+        if alpha_value == 0 {
+            continue;
+        }
+        for sample in 0..alpha {
+            let color = u128::from(buf[index(pixel, sample)].into());
+            let straight = ((color * max + alpha_value / 2) / alpha_value).min(max);
+            if let Ok(straight) = T::try_from(straight as u64) {
+                buf[index(pixel, sample)] = straight;
+            }
+        }
+    }
 }
 
 // A buffer for image decoding
@@ -214,6 +520,60 @@ impl<'a> DecodingBuffer<'a> {
         }
     }
 
+    /// Copies `len` samples from `src` into this buffer.
+    ///
+    /// The samples are taken every `src_step` samples starting at `src_start` and stored every
+    /// `dst_step` samples starting at `dst_start`. Fails if the sample types of the source and
+    /// this buffer differ.
+    fn copy_from(
+        &mut self,
+        dst_start: usize,
+        dst_step: usize,
+        src: &DecodingResult,
+        src_start: usize,
+        src_step: usize,
+        len: usize,
+    ) -> TiffResult<()> {
+        match (self, src) {
+            (DecodingBuffer::U8(buf), DecodingResult::U8(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::U16(buf), DecodingResult::U16(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::U32(buf), DecodingResult::U32(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::U64(buf), DecodingResult::U64(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::F32(buf), DecodingResult::F32(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::F64(buf), DecodingResult::F64(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::I8(buf), DecodingResult::I8(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::I16(buf), DecodingResult::I16(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::I32(buf), DecodingResult::I32(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            (DecodingBuffer::I64(buf), DecodingResult::I64(src)) => {
+                copy_strided(buf, dst_start, dst_step, src, src_start, src_step, len)
+            }
+            _ => {
+                return Err(TiffError::UnsupportedError(
+                    TiffUnsupportedError::UnsupportedDataType,
+                ))
+            }
+        }
+        Ok(())
+    }
+
     fn subrange<'b>(&'b mut self, range: Range<usize>) -> DecodingBuffer<'b>
     where
         'a: 'b,
@@ -259,8 +619,14 @@ struct TileAttributes {
 }
 
 impl TileAttributes {
-    /// Returns the tile offset in the result buffer, counted in samples
+    /// Returns the number of tiles in each plane of the image
+    fn tiles_per_plane(&self) -> usize {
+        self.tiles_across * self.tiles_down
+    }
+
+    /// Returns the tile offset in the plane of the tile in the result buffer, counted in samples
     fn get_offset(&self, tile: usize) -> usize {
+        let tile = tile % self.tiles_per_plane();
         let row = tile / self.tiles_across;
         let column = tile % self.tiles_across;
 
@@ -268,6 +634,7 @@ impl TileAttributes {
     }
 
     fn get_padding(&self, tile: usize) -> (usize, usize) {
+        let tile = tile % self.tiles_per_plane();
         let row = tile / self.tiles_across;
         let column = tile % self.tiles_across;
 
@@ -302,6 +669,68 @@ pub enum ChunkType {
     Tile,
 }
 
+/// An image of the file, as listed by [`images`][Decoder::images] and
+/// [`sub_images`][Decoder::sub_images].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    /// The offset of the IFD of the image, to select it with
+    /// [`seek_to_ifd`][Decoder::seek_to_ifd].
+    pub offset: u64,
+    /// The number of `SubIFDs` tags leading to the image, 0 for the images of the main chain
+    /// of IFDs.
+    pub depth: usize,
+    pub width: u32,
+    pub height: u32,
+    pub subfile_type: SubfileType,
+}
+
+/// The samples returned for palette images by [`read_image`][Decoder::read_image].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PaletteOutput {
+    /// The indices into the color map, as stored in the file.
+    Indices,
+    /// RGB samples with 8 bits each, the high bytes of the color map entries.
+    RGB8,
+    /// RGB samples with 16 bits each, the color map entries themselves.
+    RGB16,
+}
+
+/// The samples returned for YCbCr images by [`read_image`][Decoder::read_image].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum YCbCrOutput {
+    /// RGB samples converted from the YCbCr samples.
+    RGB,
+    /// The YCbCr samples of each pixel, the chroma samples of subsampled images are repeated for
+    /// all pixels sharing them.
+    YCbCr,
+    /// The plane of all luma samples, followed by the planes of the blue and the red chroma
+    /// samples, each with the [`ycbcr_subsampling`][Decoder::ycbcr_subsampling] of the image.
+    Planes,
+}
+
+/// The samples returned for CIELab and ICCLab images by [`read_image`][Decoder::read_image].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LabOutput {
+    /// The samples as stored in the file. The a* and b* samples are signed integers in CIELab
+    /// images and are offset by half of their range in ICCLab images.
+    Samples,
+    /// The L*, a* and b* values as `f32`, with L* ranging from 0 to 100.
+    Lab,
+    /// sRGB samples with 8 bits each, converted from the L*a*b* values.
+    RGB8,
+}
+
+/// The samples returned for SGILog compressed LogLuv images by
+/// [`read_image`][Decoder::read_image].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LogLuvOutput {
+    /// Linear CIE XYZ values, as decoded from the file.
+    XYZ,
+    /// Linear RGB values with the primaries of CCIR 709, converted from the XYZ values as libtiff
+    /// does.
+    RGB,
+}
+
 /// Decoding limits
 #[derive(Clone, Debug)]
 pub struct Limits {
@@ -364,7 +793,9 @@ where
     bigtiff: bool,
     limits: Limits,
     next_ifd: Option<u64>,
+    ifd_offsets: Vec<u64>,
     ifd: Option<Directory>,
+    subfile_type: SubfileType,
     width: u32,
     height: u32,
     bits_per_sample: Vec<u8>,
@@ -372,6 +803,19 @@ where
     sample_format: Vec<SampleFormat>,
     photometric_interpretation: PhotometricInterpretation,
     compression_method: CompressionMethod,
+    planar_config: PlanarConfiguration,
+    planar_output: PlanarConfiguration,
+    packed_output: bool,
+    unassociated_alpha_output: bool,
+    orientation: Orientation,
+    oriented_output: bool,
+    extra_samples: Vec<ExtraSamples>,
+    color_map: Option<Vec<u16>>,
+    palette_output: PaletteOutput,
+    ycbcr_subsampling: Option<(u16, u16)>,
+    ycbcr_output: YCbCrOutput,
+    lab_output: LabOutput,
+    log_luv_output: LogLuvOutput,
     chunk_type: ChunkType,
     strip_decoder: Option<StripDecodeState>,
     tile_decoder: Option<TileDecodeState>,
@@ -456,20 +900,38 @@ fn rev_hpredict(
     image: DecodingBuffer,
     size: (u32, u32),
     img_width: usize,
+    samples: usize,
     color_type: ColorType,
 ) -> TiffResult<()> {
-    // TODO: use bits_per_sample.len() after implementing type 3 predictor
-    let samples = match color_type {
-        ColorType::Gray(8) | ColorType::Gray(16) | ColorType::Gray(32) | ColorType::Gray(64) => 1,
-        ColorType::RGB(8) | ColorType::RGB(16) | ColorType::RGB(32) | ColorType::RGB(64) => 3,
-        ColorType::RGBA(8)
+    match color_type {
+        ColorType::Gray(8)
+        | ColorType::Gray(16)
+        | ColorType::Gray(32)
+        | ColorType::Gray(64)
+        | ColorType::RGB(8)
+        | ColorType::RGB(16)
+        | ColorType::RGB(32)
+        | ColorType::RGB(64)
+        | ColorType::RGBA(8)
         | ColorType::RGBA(16)
         | ColorType::RGBA(32)
         | ColorType::RGBA(64)
         | ColorType::CMYK(8)
         | ColorType::CMYK(16)
         | ColorType::CMYK(32)
-        | ColorType::CMYK(64) => 4,
+        | ColorType::CMYK(64)
+        | ColorType::Palette(8)
+        | ColorType::Palette(16)
+        | ColorType::Lab(8)
+        | ColorType::Lab(16)
+        | ColorType::GrayA(8)
+        | ColorType::GrayA(16)
+        | ColorType::GrayA(32)
+        | ColorType::GrayA(64)
+        | ColorType::Multiband { bit_depth: 8, .. }
+        | ColorType::Multiband { bit_depth: 16, .. }
+        | ColorType::Multiband { bit_depth: 32, .. }
+        | ColorType::Multiband { bit_depth: 64, .. } => {}
         _ => {
             return Err(TiffError::UnsupportedError(
                 TiffUnsupportedError::HorizontalPredictor(color_type),
@@ -490,18 +952,8 @@ fn rev_hpredict(
         DecodingBuffer::U64(buf) => {
             rev_hpredict_nsamp(buf, size, img_width, samples)?;
         }
-        DecodingBuffer::F32(_buf) => {
-            // FIXME: check how this is defined.
-            // See issue #89.
-            // rev_hpredict_nsamp(buf, size, img_width,samples)?;
-            return Err(TiffError::UnsupportedError(
-                TiffUnsupportedError::HorizontalPredictor(color_type),
-            ));
-        }
-        DecodingBuffer::F64(_buf) => {
-            //FIXME: check how this is defined.
-            // See issue #89.
-            // rev_hpredict_nsamp(buf, size, img_width,samples)?;
+        // Floating point samples are differenced with `Predictor::FloatingPoint` instead.
+        DecodingBuffer::F32(_) | DecodingBuffer::F64(_) => {
             return Err(TiffError::UnsupportedError(
                 TiffUnsupportedError::HorizontalPredictor(color_type),
             ));
@@ -522,6 +974,30 @@ fn rev_hpredict(
     Ok(())
 }
 
+/// Reverses the floating point predictor of Adobe's Technical Note 3.
+///
+/// The data holds rows of `row_bytes` bytes each, as stored in the file. Afterwards the rows
+/// hold the samples in big endian byte order.
+fn rev_fpredict(data: &mut [u8], row_bytes: usize, samples: usize, byte_len: usize) {
+    let row_samples = row_bytes / byte_len;
+    let mut shuffled = vec![0; row_bytes];
+
+    for row in data.chunks_mut(row_bytes) {
+        for i in samples..row.len() {
+            row[i] = row[i].wrapping_add(row[i - samples]);
+        }
+
+        // The row holds the most significant bytes of all samples first, then the next ones and
+        // so on.
+        shuffled[..row.len()].copy_from_slice(row);
+        for (i, sample) in row.chunks_mut(byte_len).enumerate() {
+            for (n, byte) in sample.iter_mut().enumerate() {
+                *byte = shuffled[n * row_samples + i];
+            }
+        }
+    }
+}
+
 impl<R: Read + Seek> Decoder<R> {
     /// Create a new decoder that decodes from the stream ```r```
     pub fn new(r: R) -> TiffResult<Decoder<R>> {
@@ -531,7 +1007,9 @@ impl<R: Read + Seek> Decoder<R> {
             bigtiff: false,
             limits: Default::default(),
             next_ifd: None,
+            ifd_offsets: Vec::new(),
             ifd: None,
+            subfile_type: SubfileType::default(),
             width: 0,
             height: 0,
             bits_per_sample: vec![1],
@@ -539,6 +1017,19 @@ impl<R: Read + Seek> Decoder<R> {
             sample_format: vec![SampleFormat::Uint],
             photometric_interpretation: PhotometricInterpretation::BlackIsZero,
             compression_method: CompressionMethod::None,
+            planar_config: PlanarConfiguration::Chunky,
+            planar_output: PlanarConfiguration::Chunky,
+            packed_output: false,
+            unassociated_alpha_output: false,
+            orientation: Orientation::TopLeft,
+            oriented_output: false,
+            extra_samples: Vec::new(),
+            color_map: None,
+            palette_output: PaletteOutput::Indices,
+            ycbcr_subsampling: None,
+            ycbcr_output: YCbCrOutput::YCbCr,
+            lab_output: LabOutput::Samples,
+            log_luv_output: LogLuvOutput::XYZ,
             chunk_type: ChunkType::Strip,
             strip_decoder: None,
             tile_decoder: None,
@@ -553,20 +1044,328 @@ impl<R: Read + Seek> Decoder<R> {
         self
     }
 
+    /// Sets the sample layout of the results of [`read_image`][Decoder::read_image] and
+    /// [`read_region`][Decoder::read_region].
+    ///
+    /// With the default `PlanarConfiguration::Chunky` the samples of each pixel are stored next
+    /// to each other. With `PlanarConfiguration::Planar` the result holds one complete plane per
+    /// sample, one after the other. This is independent of the layout of the file, see
+    /// [`planar_configuration`][Decoder::planar_configuration].
+    pub fn with_planar_output(mut self, planar_output: PlanarConfiguration) -> Decoder<R> {
+        self.planar_output = planar_output;
+        self
+    }
+
+    /// Keeps samples with a bit depth other than 8, 16, 32 or 64 packed in the decoded results.
+    ///
+    /// By default each such sample is expanded to the next native integer size, for example
+    /// bilevel images decode to one byte per pixel with the values 0 and 1 and 12 bit samples
+    /// decode to `u16`. With packed output the rows are returned as stored in the file instead,
+    /// as [`DecodingResult::U8`] with each row padded to a full byte. This applies to all results
+    /// and buffers, including those of [`read_chunk`][Decoder::read_chunk] and
+    /// [`read_region`][Decoder::read_region]. The samples are not inverted for
+    /// `PhotometricInterpretation::WhiteIsZero`, so 1 stands for black in bilevel images.
+    ///
+    /// [`read_image`][Decoder::read_image] unpacks and repacks the samples only if they are
+    /// converted or rearranged, for example with
+    /// [`with_planar_output`][Decoder::with_planar_output].
+    pub fn with_packed_output(mut self, packed: bool) -> Decoder<R> {
+        self.packed_output = packed;
+        self
+    }
+
+    /// Sets whether [`read_image`][Decoder::read_image] returns unassociated alpha for images
+    /// with associated alpha.
+    ///
+    /// By default the samples are returned as stored. With unassociated alpha output the color
+    /// samples of images whose [`alpha`][Decoder::alpha] is `ExtraSamples::AssociatedAlpha` are
+    /// divided by their alpha, the color samples of fully transparent pixels are kept.
+    pub fn with_unassociated_alpha_output(mut self, unassociated: bool) -> Decoder<R> {
+        self.unassociated_alpha_output = unassociated;
+        self
+    }
+
+    /// Sets whether [`read_image`][Decoder::read_image] returns the pixels in the order they are
+    /// displayed.
+    ///
+    /// By default the pixels are returned as stored. With oriented output the pixels of images
+    /// with another [`orientation`][Decoder::orientation] than `Orientation::TopLeft` are rotated
+    /// or flipped, such that the first row is the top and the first column the left side of the
+    /// image, with the [`oriented_dimensions`][Decoder::oriented_dimensions]. The other functions
+    /// reading image data always return the pixels as stored, and chunks and regions are
+    /// addressed within the stored image.
+    pub fn with_oriented_output(mut self, oriented: bool) -> Decoder<R> {
+        self.oriented_output = oriented;
+        self
+    }
+
+    /// Sets the samples returned by [`read_image`][Decoder::read_image] for palette images.
+    ///
+    /// By default the indices into the [`color_map`][Decoder::color_map] are returned. With
+    /// `PaletteOutput::RGB8` or `PaletteOutput::RGB16` they are replaced by the colors they refer
+    /// to, with three samples per pixel arranged as configured with
+    /// [`with_planar_output`][Decoder::with_planar_output]. Regions and chunks read with
+    /// [`read_region`][Decoder::read_region] and [`read_chunk`][Decoder::read_chunk] are expanded
+    /// the same way.
+    pub fn with_palette_output(mut self, palette_output: PaletteOutput) -> Decoder<R> {
+        self.palette_output = palette_output;
+        self
+    }
+
+    /// Sets the samples returned by [`read_image`][Decoder::read_image] for YCbCr images.
+    ///
+    /// By default the YCbCr samples of each pixel are returned as with `YCbCrOutput::YCbCr`, like
+    /// the samples of [`read_strip`][Decoder::read_strip] and [`read_tile`][Decoder::read_tile].
+    /// With `YCbCrOutput::RGB` they are converted to RGB.
+    ///
+    /// Regions and chunks read with [`read_region`][Decoder::read_region] and
+    /// [`read_chunk`][Decoder::read_chunk] are converted the same way. With `YCbCrOutput::Planes`
+    /// their chroma planes are subsampled from their top left pixel, so they only match the
+    /// stored chroma samples for regions starting at a multiple of the subsampling.
+    pub fn with_ycbcr_output(mut self, ycbcr_output: YCbCrOutput) -> Decoder<R> {
+        self.ycbcr_output = ycbcr_output;
+        self
+    }
+
+    /// Sets the samples returned by [`read_image`][Decoder::read_image] for CIELab and ICCLab
+    /// images.
+    ///
+    /// By default the samples are returned as stored, as with `LabOutput::Samples`. With
+    /// `LabOutput::Lab` the L*, a* and b* values are returned as `f32`, independent of their
+    /// encoding in the file. Only samples with 8 or 16 bits can be converted. The conversion to
+    /// sRGB uses the white point of the image, which defaults to D50. Regions and chunks read with
+    /// [`read_region`][Decoder::read_region] and [`read_chunk`][Decoder::read_chunk] are
+    /// converted the same way.
+    pub fn with_lab_output(mut self, lab_output: LabOutput) -> Decoder<R> {
+        self.lab_output = lab_output;
+        self
+    }
+
+    /// Sets the samples returned by [`read_image`][Decoder::read_image] for SGILog compressed
+    /// LogLuv images.
+    ///
+    /// By default the linear XYZ values the encoded samples stand for are returned, matching the
+    /// `ColorType::XYZ` reported by [`colortype`][Decoder::colortype]. Regions and chunks read
+    /// with [`read_region`][Decoder::read_region] and [`read_chunk`][Decoder::read_chunk] are
+    /// converted the same way, the samples of [`read_strip`][Decoder::read_strip] and
+    /// [`read_tile`][Decoder::read_tile] are always returned as with `LogLuvOutput::XYZ`.
+    pub fn with_log_luv_output(mut self, log_luv_output: LogLuvOutput) -> Decoder<R> {
+        self.log_luv_output = log_luv_output;
+        self
+    }
+
+    /// The width and height of the stored image.
+    ///
+    /// The positions of chunks and regions refer to the stored image, independent of its
+    /// [`orientation`][Decoder::orientation].
     pub fn dimensions(&mut self) -> TiffResult<(u32, u32)> {
         Ok((self.width, self.height))
     }
 
+    /// The width and height of the image as it is displayed.
+    ///
+    /// These are the dimensions of the image returned by [`read_image`][Decoder::read_image] with
+    /// [`with_oriented_output`][Decoder::with_oriented_output], the stored dimensions swapped for
+    /// orientations that turn the rows into columns.
+    pub fn oriented_dimensions(&self) -> (u32, u32) {
+        if swaps_dimensions(self.orientation) {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// The position of the first row and column of the stored image when it is displayed.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// The kind of data of the image, like an overview of another image.
+    pub fn subfile_type(&self) -> SubfileType {
+        self.subfile_type
+    }
+
+    /// The color map of a palette image.
+    ///
+    /// Holds the red values of all `2^bits` colors, followed by all green and then all blue
+    /// values, each ranging from 0 to 65535.
+    pub fn color_map(&self) -> Option<&[u16]> {
+        self.color_map.as_deref()
+    }
+
+    /// The horizontal and vertical subsampling of the chroma samples of a YCbCr image.
+    ///
+    /// Each chroma sample is shared by a block of that many pixels. This is not available for
+    /// JPEG compressed images, whose samples are subsampled and converted by the JPEG decoder.
+    pub fn ycbcr_subsampling(&self) -> Option<(u16, u16)> {
+        self.ycbcr_subsampling
+    }
+
+    /// The meaning of the extra samples of each pixel, following the samples of its color space.
+    ///
+    /// Without an `ExtraSamples` tag the meaning of any extra samples is unspecified.
+    pub fn extra_samples(&self) -> &[ExtraSamples] {
+        &self.extra_samples
+    }
+
+    /// The kind of the alpha samples of the image, if the first extra sample is alpha.
+    ///
+    /// Returns `ExtraSamples::AssociatedAlpha` if the color samples are premultiplied with the
+    /// alpha samples and `ExtraSamples::UnassociatedAlpha` otherwise. Extra samples of
+    /// unspecified meaning are not reported, even though the fourth sample of RGBA images is
+    /// commonly treated as unassociated alpha.
+    pub fn alpha(&self) -> Option<ExtraSamples> {
+        match self.extra_samples.first() {
+            Some(&alpha @ ExtraSamples::AssociatedAlpha)
+            | Some(&alpha @ ExtraSamples::UnassociatedAlpha) => Some(alpha),
+            _ => None,
+        }
+    }
+
+    /// The GeoTIFF georeferencing of the image.
+    ///
+    /// Returns `None` if the image has neither a `GeoKeyDirectoryTag` nor any of the model tags.
+    pub fn geotiff(&mut self) -> TiffResult<Option<GeoTiff>> {
+        let directory = self.find_tag_unsigned_vec(Tag::GeoKeyDirectoryTag)?;
+        let tiepoints = self.find_tag_f64_vec(Tag::ModelTiepointTag)?;
+        let pixel_scale = self.find_tag_f64_vec(Tag::ModelPixelScaleTag)?;
+        let transformation = self.find_tag_f64_vec(Tag::ModelTransformationTag)?;
+        if directory.is_none()
+            && tiepoints.is_none()
+            && pixel_scale.is_none()
+            && transformation.is_none()
+        {
+            return Ok(None);
+        }
+
+        let doubles = self.find_tag_f64_vec(Tag::GeoDoubleParamsTag)?;
+        let ascii = self
+            .find_tag(Tag::GeoAsciiParamsTag)?
+            .map(ifd::Value::into_string)
+            .transpose()?;
+        GeoTiff::from_tags(
+            directory,
+            doubles,
+            ascii,
+            tiepoints,
+            pixel_scale,
+            transformation,
+        )
+        .map(Some)
+    }
+
+    /// The value of the samples of pixels without data, from the `GDAL_NODATA` tag.
+    ///
+    /// The value is parsed into the type of the first sample of the stored image. Values outside
+    /// of its range are ignored, as by GDAL.
+    pub fn gdal_nodata(&mut self) -> TiffResult<Option<NoData>> {
+        let value = match self.find_tag(Tag::GdalNodata)? {
+            Some(value) => value.into_string()?,
+            None => return Ok(None),
+        };
+        let format = self.sample_format.first().copied();
+        let bits = self.bits_per_sample.first().copied();
+        NoData::parse(
+            &value,
+            format.unwrap_or(SampleFormat::Uint),
+            bits.unwrap_or(8),
+        )
+    }
+
+    /// The metadata items of the `GDAL_METADATA` tag, like the scale and offset of each band.
+    pub fn gdal_metadata(&mut self) -> TiffResult<Option<GdalMetadata>> {
+        match self.find_tag(Tag::GdalMetadata)? {
+            Some(value) => GdalMetadata::from_xml(&value.into_string()?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The EXIF tags of the image, from the IFD of its `ExifIFD` tag.
+    pub fn exif(&mut self) -> TiffResult<Option<Exif>> {
+        Ok(self
+            .read_private_ifd(Tag::ExifIFD)?
+            .map(|entries| Exif { entries }))
+    }
+
+    /// The GPS tags of the image, from the IFD of its `GPSInfo` tag.
+    pub fn gps(&mut self) -> TiffResult<Option<Gps>> {
+        let entries = match self.read_private_ifd(Tag::GPSInfo)? {
+            Some(entries) => entries,
+            None => return Ok(None),
+        };
+        // The tags of the IFD have their own numbers.
+        let entries = entries
+            .into_iter()
+            .map(|(tag, value)| (GpsTag::from_u16_exhaustive(tag.to_u16()), value))
+            .collect();
+        Ok(Some(Gps { entries }))
+    }
+
+    /// The bits of each sample of a pixel.
+    pub fn bits_per_sample(&self) -> &[u8] {
+        &self.bits_per_sample
+    }
+
+    /// The format of each sample of a pixel.
+    pub fn sample_formats(&self) -> &[SampleFormat] {
+        &self.sample_format
+    }
+
+    /// Whether the samples of a pixel differ in bit depth or sample format
+    fn is_mixed(&self) -> bool {
+        self.bits_per_sample
+            .windows(2)
+            .any(|bits| bits[0] != bits[1])
+            || self
+                .sample_format
+                .windows(2)
+                .any(|formats| formats[0] != formats[1])
+    }
+
+    /// The layout of the samples in the strips or tiles of the image
+    pub fn planar_configuration(&self) -> PlanarConfiguration {
+        self.planar_config
+    }
+
+    /// The color type of the samples stored in the image.
+    ///
+    /// [`read_image`][Decoder::read_image] returns these samples unless a conversion to other
+    /// samples is requested, for example with
+    /// [`with_palette_output`][Decoder::with_palette_output]. JPEG compressed YCbCr images are
+    /// the exception, they can only be decoded to RGB.
     pub fn colortype(&mut self) -> TiffResult<ColorType> {
+        if self.is_mixed() {
+            return Ok(ColorType::Mixed {
+                num_samples: u16::try_from(self.bits_per_sample.len())?,
+            });
+        }
+
         match self.photometric_interpretation {
             PhotometricInterpretation::RGB => match self.bits_per_sample[..] {
                 [r, g, b] if [r, r] == [g, b] => Ok(ColorType::RGB(r)),
                 [r, g, b, a] if [r, r, r] == [g, b, a] => Ok(ColorType::RGBA(r)),
-                // FIXME: We should _ignore_ other components. In particular:
-                // > Beware of extra components. Some TIFF files may have more components per pixel
-                // than you think. A Baseline TIFF reader must skip over them gracefully,using the
-                // values of the SamplesPerPixel and BitsPerSample fields.
-                // > -- TIFF 6.0 Specification, Section 7, Additional Baseline requirements.
+                _ => self.multiband(),
+            },
+            PhotometricInterpretation::CMYK => match self.bits_per_sample[..] {
+                [c, m, y, k] if [c, c, c] == [m, y, k] => Ok(ColorType::CMYK(c)),
+                _ => self.multiband(),
+            },
+            PhotometricInterpretation::BlackIsZero | PhotometricInterpretation::WhiteIsZero => {
+                match self.bits_per_sample[..] {
+                    [v] => Ok(ColorType::Gray(v)),
+                    [v, a] if v == a => Ok(ColorType::GrayA(v)),
+                    _ => self.multiband(),
+                }
+            }
+            PhotometricInterpretation::RGBPalette if self.color_map.is_some() => {
+                Ok(ColorType::Palette(self.bits_per_sample[0]))
+            }
+            PhotometricInterpretation::YCbCr => match self.bits_per_sample[..] {
+                // The JPEG decoder converts the samples to RGB.
+                [8, 8, 8] if self.compression_method == CompressionMethod::ModernJPEG => {
+                    Ok(ColorType::RGB(8))
+                }
+                [y, cb, cr] if [y, y] == [cb, cr] => Ok(ColorType::YCbCr(y)),
                 _ => Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::InterpretationWithBits(
                         self.photometric_interpretation,
@@ -574,8 +1373,11 @@ impl<R: Read + Seek> Decoder<R> {
                     ),
                 )),
             },
-            PhotometricInterpretation::CMYK => match self.bits_per_sample[..] {
-                [c, m, y, k] if [c, c, c] == [m, y, k] => Ok(ColorType::CMYK(c)),
+            PhotometricInterpretation::LogL if self.bits_per_sample.len() == 1 => {
+                Ok(ColorType::Gray(self.bits_per_sample[0]))
+            }
+            PhotometricInterpretation::LogLuv => match self.bits_per_sample[..] {
+                [x, y, z] if [x, x] == [y, z] => Ok(ColorType::XYZ(x)),
                 _ => Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::InterpretationWithBits(
                         self.photometric_interpretation,
@@ -583,10 +1385,16 @@ impl<R: Read + Seek> Decoder<R> {
                     ),
                 )),
             },
-            PhotometricInterpretation::BlackIsZero | PhotometricInterpretation::WhiteIsZero
-                if self.bits_per_sample.len() == 1 =>
-            {
-                Ok(ColorType::Gray(self.bits_per_sample[0]))
+            PhotometricInterpretation::CIELab | PhotometricInterpretation::ICCLab => {
+                match self.bits_per_sample[..] {
+                    [l, a, b] if [l, l] == [a, b] => Ok(ColorType::Lab(l)),
+                    _ => Err(TiffError::UnsupportedError(
+                        TiffUnsupportedError::InterpretationWithBits(
+                            self.photometric_interpretation,
+                            self.bits_per_sample.clone(),
+                        ),
+                    )),
+                }
             }
 
             // TODO: this is bad we should not fail at this point
@@ -599,6 +1407,27 @@ impl<R: Read + Seek> Decoder<R> {
         }
     }
 
+    /// The color type of images with extra samples beyond those of their color space.
+    ///
+    /// A Baseline TIFF reader must skip over extra components gracefully, see section 7 of the
+    /// TIFF 6.0 specification.
+    fn multiband(&self) -> TiffResult<ColorType> {
+        match self.bits_per_sample[..] {
+            [bits, ref rest @ ..] if rest.iter().all(|&other| other == bits) => {
+                Ok(ColorType::Multiband {
+                    bit_depth: bits,
+                    num_samples: u16::try_from(self.bits_per_sample.len())?,
+                })
+            }
+            _ => Err(TiffError::UnsupportedError(
+                TiffUnsupportedError::InterpretationWithBits(
+                    self.photometric_interpretation,
+                    self.bits_per_sample.clone(),
+                ),
+            )),
+        }
+    }
+
     fn read_header(&mut self) -> TiffResult<()> {
         let mut endianess = Vec::with_capacity(2);
         self.reader.by_ref().take(2).read_to_end(&mut endianess)?;
@@ -644,6 +1473,7 @@ impl<R: Read + Seek> Decoder<R> {
             0 => None,
             n => Some(n),
         };
+        self.ifd_offsets.extend(self.next_ifd);
         Ok(())
     }
 
@@ -661,6 +1491,7 @@ impl<R: Read + Seek> Decoder<R> {
         self.ifd = Some(self.read_ifd()?);
         self.width = self.get_tag_u32(Tag::ImageWidth)?;
         self.height = self.get_tag_u32(Tag::ImageLength)?;
+        self.subfile_type = self.find_subfile_type()?;
         self.strip_decoder = None;
         self.tile_decoder = None;
         self.tile_attributes = None;
@@ -680,31 +1511,101 @@ impl<R: Read + Seek> Decoder<R> {
 
         self.samples = self.find_tag_unsigned(Tag::SamplesPerPixel)?.unwrap_or(1);
 
+        if self.samples == 0 {
+            return Err(TiffUnsupportedError::UnsupportedSampleDepth(self.samples).into());
+        }
+
         self.sample_format = match self.find_tag_unsigned_vec(Tag::SampleFormat)? {
-            Some(vals) => {
-                let sample_format: Vec<_> = vals
-                    .into_iter()
-                    .map(SampleFormat::from_u16_exhaustive)
-                    .collect();
-
-                // TODO: for now, only homogenous formats across samples are supported.
-                if !sample_format.windows(2).all(|s| s[0] == s[1]) {
-                    return Err(TiffUnsupportedError::UnsupportedSampleFormat(
-                        self.sample_format.clone(),
-                    )
-                    .into());
-                }
+            // As with the bits, the format of all samples may be stored only once.
+            Some(formats) if formats.len() == 1 => {
+                let format = SampleFormat::from_u16_exhaustive(formats[0]);
+                vec![format; usize::from(self.samples)]
+            }
+            Some(formats) => formats
+                .into_iter()
+                .map(SampleFormat::from_u16_exhaustive)
+                .collect(),
+            None => vec![SampleFormat::Uint; usize::from(self.samples)],
+        };
 
-                sample_format
+        self.bits_per_sample = match self.find_tag_unsigned_vec(Tag::BitsPerSample)? {
+            // Some writers store the bits of all samples only once.
+            Some(bits) if bits.len() == 1 => vec![bits[0]; usize::from(self.samples)],
+            Some(bits) => bits,
+            None => vec![1; usize::from(self.samples)],
+        };
+
+        // SGILog compressed pixels are decoded to floating point samples, as with the
+        // `SGILOGDATAFMT_FLOAT` data format of libtiff. The tags describe the data format the
+        // pixels were encoded from.
+        if let Some(encoding) = self.sgilog_encoding() {
+            let samples = encoding.samples();
+            self.bits_per_sample = vec![32; samples];
+            self.sample_format = vec![SampleFormat::IEEEFP; samples];
+        }
+
+        let color_samples = match self.photometric_interpretation {
+            PhotometricInterpretation::WhiteIsZero
+            | PhotometricInterpretation::BlackIsZero
+            | PhotometricInterpretation::RGBPalette
+            | PhotometricInterpretation::TransparencyMask
+            | PhotometricInterpretation::LogL => 1,
+            PhotometricInterpretation::CMYK => 4,
+            _ => 3,
+        };
+        self.extra_samples = match self.find_tag_unsigned_vec(Tag::ExtraSamples)? {
+            Some(values) => values
+                .into_iter()
+                .map(ExtraSamples::from_u16_exhaustive)
+                .collect(),
+            None => {
+                let extra = self.bits_per_sample.len().saturating_sub(color_samples);
+                vec![ExtraSamples::Unspecified; extra]
             }
-            None => vec![SampleFormat::Uint],
         };
 
-        self.bits_per_sample = match self.samples {
-            1 | 3 | 4 => self
-                .find_tag_unsigned_vec(Tag::BitsPerSample)?
-                .unwrap_or_else(|| vec![1]),
-            _ => return Err(TiffUnsupportedError::UnsupportedSampleDepth(self.samples).into()),
+        // An invalid orientation does not prevent decoding the image, it is ignored.
+        self.orientation = self
+            .find_tag_unsigned(Tag::Orientation)?
+            .and_then(Orientation::from_u16)
+            .unwrap_or(Orientation::TopLeft);
+
+        self.color_map = match (self.photometric_interpretation, &self.bits_per_sample[..]) {
+            (PhotometricInterpretation::RGBPalette, &[bits]) if bits <= 16 => {
+                let color_map = self.get_tag_u16_vec(Tag::ColorMap)?;
+                if color_map.len() != 3 << bits {
+                    return Err(TiffFormatError::InconsistentSizesEncountered.into());
+                }
+                Some(color_map)
+            }
+            _ => None,
+        };
+
+        self.ycbcr_subsampling = match (self.photometric_interpretation, self.compression_method) {
+            (_, CompressionMethod::JPEG) | (_, CompressionMethod::ModernJPEG) => None,
+            (PhotometricInterpretation::YCbCr, _) => {
+                match self
+                    .find_tag_unsigned_vec(Tag::YCbCrSubSampling)?
+                    .as_deref()
+                {
+                    None => Some((2, 2)),
+                    Some(&[h, v]) if matches!(h, 1 | 2 | 4) && matches!(v, 1 | 2 | 4) => {
+                        Some((h, v))
+                    }
+                    Some(_) => {
+                        return Err(
+                            TiffFormatError::InvalidTagValueType(Tag::YCbCrSubSampling).into()
+                        )
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        self.planar_config = match self.find_tag_unsigned(Tag::PlanarConfiguration)? {
+            Some(val) => PlanarConfiguration::from_u16(val)
+                .ok_or(TiffUnsupportedError::UnsupportedPlanarConfig(None))?,
+            None => PlanarConfiguration::Chunky,
         };
 
         let ifd = self.ifd.as_ref().unwrap();
@@ -740,7 +1641,7 @@ impl<R: Read + Seek> Decoder<R> {
                 let tiles_across = (usize::try_from(self.width)? + tile_width - 1) / tile_width;
                 let tiles_down = (usize::try_from(self.height)? + tile_length - 1) / tile_length;
 
-                let samples_per_pixel = self.bits_per_sample.len();
+                let samples_per_pixel = self.chunk_samples();
 
                 let tile_samples = tile_length * tile_width * samples_per_pixel;
                 let padding_right = (tiles_across * tile_width) - usize::try_from(self.width)?;
@@ -779,6 +1680,134 @@ impl<R: Read + Seek> Decoder<R> {
         self.next_ifd.is_some()
     }
 
+    /// Reads in the image with the given index in the main chain of IFDs.
+    ///
+    /// The first image has index 0. Images of `SubIFDs` tags are not counted, they are selected
+    /// with [`seek_to_ifd`][Decoder::seek_to_ifd].
+    pub fn seek_to_image(&mut self, index: usize) -> TiffResult<()> {
+        while self.ifd_offsets.len() <= index {
+            let last = self.ifd_offsets[self.ifd_offsets.len() - 1];
+            let next = match self.read_directory(last)?.1 {
+                0 => return Err(TiffFormatError::ImageFileDirectoryNotFound.into()),
+                next if self.ifd_offsets.contains(&next) => {
+                    return Err(TiffFormatError::CycleInOffsets.into())
+                }
+                next => next,
+            };
+            self.ifd_offsets.push(next);
+        }
+
+        self.seen_ifds = self.ifd_offsets[..=index].iter().copied().collect();
+        self.next_ifd = Some(self.ifd_offsets[index]);
+        self.next_image()
+    }
+
+    /// Reads in the image with the IFD at the given offset.
+    ///
+    /// The offsets of all images are listed by [`images`][Decoder::images]. Following images are
+    /// read from the chain of IFDs of that image.
+    pub fn seek_to_ifd(&mut self, offset: u64) -> TiffResult<()> {
+        self.seen_ifds = Some(offset).into_iter().collect();
+        self.next_ifd = Some(offset);
+        self.next_image()
+    }
+
+    /// Lists all images of the file.
+    ///
+    /// The images of the main chain of IFDs are listed in order, each followed by the images of
+    /// its `SubIFDs` tag and their own sub images. Reduced resolution images, like the overviews
+    /// of a pyramid, are marked by their [`subfile_type`][ImageInfo::subfile_type].
+    pub fn images(&mut self) -> TiffResult<Vec<ImageInfo>> {
+        let mut images = Vec::new();
+        let mut seen = HashSet::new();
+        let mut next = self.ifd_offsets[0];
+        while next != 0 {
+            if !seen.insert(next) {
+                return Err(TiffFormatError::CycleInOffsets.into());
+            }
+            next = self.list_image(next, 0, &mut seen, &mut images)?;
+        }
+        Ok(images)
+    }
+
+    /// Lists the images of the `SubIFDs` tag of the current image and their own sub images.
+    pub fn sub_images(&mut self) -> TiffResult<Vec<ImageInfo>> {
+        let mut images = Vec::new();
+        let mut seen = HashSet::new();
+        for offset in self
+            .find_tag_unsigned_vec(Tag::SubIFDs)?
+            .unwrap_or_default()
+        {
+            if !seen.insert(offset) {
+                return Err(TiffFormatError::CycleInOffsets.into());
+            }
+            self.list_image(offset, 1, &mut seen, &mut images)?;
+        }
+        Ok(images)
+    }
+
+    /// Adds the image with the IFD at `offset` and its sub images to `images`.
+    ///
+    /// Returns the offset of the next IFD in the chain of the image.
+    fn list_image(
+        &mut self,
+        offset: u64,
+        depth: usize,
+        seen: &mut HashSet<u64>,
+        images: &mut Vec<ImageInfo>,
+    ) -> TiffResult<u64> {
+        // The sub images are visited depth first with an explicit stack instead of recursion, so
+        // deeply nested `SubIFDs` do not overflow the call stack.
+        let mut stack = vec![(offset, depth)];
+        let mut chain_next = None;
+        while let Some((offset, depth)) = stack.pop() {
+            let (dir, next) = self.read_directory(offset)?;
+            chain_next.get_or_insert(next);
+
+            // The tags are read with the IFD temporarily taking the place of the current one.
+            let current = self.ifd.replace(dir);
+            let info = self.image_info(offset, depth);
+            self.ifd = current;
+            let (info, sub_ifds) = info?;
+
+            images.push(info);
+            for &offset in sub_ifds.iter().rev() {
+                if !seen.insert(offset) {
+                    return Err(TiffFormatError::CycleInOffsets.into());
+                }
+                stack.push((offset, depth + 1));
+            }
+        }
+        Ok(chain_next.unwrap_or(0))
+    }
+
+    /// Reads the description of the current IFD and the offsets of its sub IFDs.
+    fn image_info(&mut self, offset: u64, depth: usize) -> TiffResult<(ImageInfo, Vec<u64>)> {
+        let info = ImageInfo {
+            offset,
+            depth,
+            width: self.get_tag_u32(Tag::ImageWidth)?,
+            height: self.get_tag_u32(Tag::ImageLength)?,
+            subfile_type: self.find_subfile_type()?,
+        };
+        let sub_ifds = self
+            .find_tag_unsigned_vec(Tag::SubIFDs)?
+            .unwrap_or_default();
+        Ok((info, sub_ifds))
+    }
+
+    /// Parses the `NewSubfileType` tag, or the deprecated `SubfileType` tag in its absence.
+    fn find_subfile_type(&mut self) -> TiffResult<SubfileType> {
+        if let Some(value) = self.find_tag_unsigned(Tag::NewSubfileType)? {
+            return Ok(SubfileType::from_u32(value));
+        }
+        // An unknown deprecated subfile type does not prevent decoding the image.
+        Ok(self
+            .find_tag_unsigned(Tag::SubfileType)?
+            .and_then(SubfileType::from_subfile_type)
+            .unwrap_or_default())
+    }
+
     /// Returns the byte_order
     pub fn byte_order(&self) -> ByteOrder {
         self.byte_order
@@ -928,15 +1957,34 @@ impl<R: Read + Seek> Decoder<R> {
 
     /// Reads the next IFD
     fn read_ifd(&mut self) -> TiffResult<Directory> {
-        let mut dir: Directory = HashMap::new();
-        match self.next_ifd {
+        let offset = match self.next_ifd {
             None => {
                 return Err(TiffError::FormatError(
                     TiffFormatError::ImageFileDirectoryNotFound,
                 ))
             }
-            Some(offset) => self.goto_offset_u64(offset)?,
+            Some(offset) => offset,
+        };
+        let (dir, next) = self.read_directory(offset)?;
+        if next != 0 && !self.seen_ifds.insert(next) {
+            return Err(TiffError::FormatError(TiffFormatError::CycleInOffsets));
+        }
+        // Remember the offsets of the main chain of IFDs when reaching its end.
+        if next != 0 && self.ifd_offsets.last() == Some(&offset) {
+            self.ifd_offsets.push(next);
         }
+        self.next_ifd = match next {
+            0 => None,
+            n => Some(n),
+        };
+        Ok(dir)
+    }
+
+    /// Reads the IFD at the given offset and the offset of the IFD following it, 0 at the end of
+    /// the chain.
+    fn read_directory(&mut self, offset: u64) -> TiffResult<(Directory, u64)> {
+        let mut dir: Directory = HashMap::new();
+        self.goto_offset_u64(offset)?;
         let num_tags = if self.bigtiff {
             self.read_long8()?
         } else {
@@ -951,11 +1999,28 @@ impl<R: Read + Seek> Decoder<R> {
             };
             dir.insert(tag, entry);
         }
-        self.next_ifd = match self.read_ifd_offset_nonrepeating()? {
-            0 => None,
-            n => Some(n),
+        let next = self.read_ifd_offset()?;
+        Ok((dir, next))
+    }
+
+    /// Reads the values of all entries of the private IFD that a tag of the current image points
+    /// to, like the EXIF IFD.
+    fn read_private_ifd(&mut self, tag: Tag) -> TiffResult<Option<HashMap<Tag, ifd::Value>>> {
+        let offset = match self.find_tag_unsigned(tag)? {
+            Some(offset) => offset,
+            None => return Ok(None),
         };
-        Ok(dir)
+        // A private IFD is never one of the IFDs of the images.
+        if self.seen_ifds.contains(&offset) || self.ifd_offsets.contains(&offset) {
+            return Err(TiffError::FormatError(TiffFormatError::CycleInOffsets));
+        }
+
+        let (dir, _) = self.read_directory(offset)?;
+        let limits = self.limits.clone();
+        dir.into_iter()
+            .map(|(tag, entry)| Ok((tag, entry.val(&limits, self)?)))
+            .collect::<TiffResult<_>>()
+            .map(Some)
     }
 
     /// Tries to retrieve a tag.
@@ -982,6 +2047,32 @@ impl<R: Read + Seek> Decoder<R> {
             .transpose()
     }
 
+    /// Tries to retrieve the values of a tag of rationals as floating point numbers.
+    fn find_tag_rationals(&mut self, tag: Tag) -> TiffResult<Option<Vec<f32>>> {
+        let values = match self.find_tag(tag)? {
+            Some(ifd::Value::List(values)) => values,
+            Some(value) => vec![value],
+            None => return Ok(None),
+        };
+
+        values
+            .into_iter()
+            .map(|value| match value {
+                ifd::Value::Rational(n, d) => Ok(n as f32 / d as f32),
+                ifd::Value::RationalBig(n, d) => Ok(n as f32 / d as f32),
+                _ => Err(TiffFormatError::InvalidTagValueType(tag).into()),
+            })
+            .collect::<TiffResult<_>>()
+            .map(Some)
+    }
+
+    /// Tries to retrieve the values of a tag as floating point numbers.
+    fn find_tag_f64_vec(&mut self, tag: Tag) -> TiffResult<Option<Vec<f64>>> {
+        self.find_tag(tag)?
+            .map(ifd::Value::into_f64_vec)
+            .transpose()
+    }
+
     /// Tries to retrieve a vector of all a tag's values and convert them to
     /// the desired unsigned type.
     pub fn find_tag_unsigned_vec<T: TryFrom<u64>>(
@@ -1093,14 +2184,14 @@ impl<R: Read + Seek> Decoder<R> {
             (ColorType::Gray(64), DecodingBuffer::U64(ref mut buffer)) => {
                 Self::invert_colors_unsigned(buffer, 0xffff_ffff_ffff_ffff);
             }
-            (ColorType::Gray(32), DecodingBuffer::U32(ref mut buffer)) => {
-                Self::invert_colors_unsigned(buffer, 0xffff_ffff);
+            (ColorType::Gray(n), DecodingBuffer::U32(ref mut buffer)) if n <= 32 => {
+                Self::invert_colors_unsigned(buffer, 0xffff_ffff >> (32 - n));
             }
-            (ColorType::Gray(16), DecodingBuffer::U16(ref mut buffer)) => {
-                Self::invert_colors_unsigned(buffer, 0xffff);
+            (ColorType::Gray(n), DecodingBuffer::U16(ref mut buffer)) if n <= 16 => {
+                Self::invert_colors_unsigned(buffer, 0xffff >> (16 - n));
             }
             (ColorType::Gray(n), DecodingBuffer::U8(ref mut buffer)) if n <= 8 => {
-                Self::invert_colors_unsigned(buffer, 0xff);
+                Self::invert_colors_unsigned(buffer, 0xff >> (8 - n));
             }
             (ColorType::Gray(32), DecodingBuffer::F32(ref mut buffer)) => {
                 Self::invert_colors_fp(buffer, 1.0);
@@ -1157,13 +2248,32 @@ impl<R: Read + Seek> Decoder<R> {
     ) -> TiffResult<()> {
         // Validate that the provided buffer is of the expected type.
         let color_type = self.colortype()?;
+        let packed_output = self.packed_output_bits().is_some();
         match (color_type, &buffer) {
+            // Packed rows are copied as stored.
+            (_, DecodingBuffer::U8(_)) if packed_output => {}
             (ColorType::RGB(n), _)
             | (ColorType::RGBA(n), _)
             | (ColorType::CMYK(n), _)
             | (ColorType::Gray(n), _)
+            | (ColorType::Palette(n), _)
+            | (ColorType::YCbCr(n), _)
+            | (ColorType::Lab(n), _)
+            | (ColorType::XYZ(n), _)
+            | (ColorType::GrayA(n), _)
+            | (ColorType::Multiband { bit_depth: n, .. }, _)
                 if usize::from(n) == buffer.byte_len() * 8 => {}
-            (ColorType::Gray(n), DecodingBuffer::U8(_)) if n <= 8 => {}
+            // Samples of other sizes are expanded to the next native integer.
+            (ColorType::RGB(n), _)
+            | (ColorType::RGBA(n), _)
+            | (ColorType::CMYK(n), _)
+            | (ColorType::Gray(n), _)
+            | (ColorType::Palette(n), _)
+            | (ColorType::Lab(n), _)
+            | (ColorType::GrayA(n), _)
+            | (ColorType::Multiband { bit_depth: n, .. }, _)
+                if self.packed_bits() == Some(n)
+                    && usize::from(n).next_power_of_two().max(8) == buffer.byte_len() * 8 => {}
             (type_, _) => {
                 return Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::UnsupportedColorType(type_),
@@ -1171,6 +2281,26 @@ impl<R: Read + Seek> Decoder<R> {
             }
         }
 
+        let floating_point_predictor = self.floating_point_predictor(&buffer)?;
+        let width = usize::try_from(self.width)?;
+        let row_samples = width * self.chunk_samples();
+        // Bits of the samples that are expanded to one integer each
+        let packed_bits = self.packed_bits().filter(|_| !packed_output);
+        let samples = match self.packed_output_bits() {
+            Some(bits) => buffer.len() / packed_row_bytes(row_samples, bits).max(1) * row_samples,
+            None => buffer.len(),
+        };
+        let fax_options = self.fax_options(width)?;
+        let data_units = self.data_units()?;
+        let sgilog_encoding = self.sgilog_encoding();
+        if sgilog_encoding.is_some() && self.planar_config == PlanarConfiguration::Planar {
+            return Err(
+                TiffUnsupportedError::UnsupportedPlanarConfig(Some(self.planar_config)).into(),
+            );
+        }
+
+        self.check_compression()?;
+
         // Construct necessary reader to perform decompression.
         self.goto_offset_u64(offset)?;
         let byte_order = self.reader.byte_order;
@@ -1179,13 +2309,35 @@ impl<R: Read + Seek> Decoder<R> {
             &mut self.reader,
             self.compression_method,
             length,
-            buffer.len(),
+            samples,
             buffer.byte_len(),
             self.limits.intermediate_buffer_size,
+            fax_options,
         )?;
 
         // Read into output buffer.
-        {
+        if let (Some(encoding), DecodingBuffer::F32(buf)) = (sgilog_encoding, &mut buffer) {
+            let rows = buf.len() / row_samples.max(1);
+            sgilog::read_rows(reader, buf, encoding, width, width, rows, row_samples)?;
+            return Ok(());
+        } else if let (Some(subsampling), DecodingBuffer::U8(buf)) = (data_units, &mut buffer) {
+            let rows = buf.len() / row_samples.max(1);
+            ycbcr::read_data_units(reader, buf, subsampling, width, width, rows, row_samples)?;
+        } else if let Some(bits) = packed_bits {
+            let rows = buffer.len() / row_samples.max(1);
+            let row_bytes = packed_row_bytes(row_samples, bits);
+            let mut packed = Vec::with_capacity(rows * row_bytes);
+            reader
+                .take(u64::try_from(rows * row_bytes)?)
+                .read_to_end(&mut packed)?;
+            packed.resize(rows * row_bytes, 0);
+
+            for row in 0..rows {
+                let packed_row = &packed[row * row_bytes..][..row_bytes];
+                let row = buffer.subrange(row * row_samples..(row + 1) * row_samples);
+                unpack_row(packed_row, row, bits);
+            }
+        } else {
             let mut buffer = match &mut buffer {
                 DecodingBuffer::U8(buf) => &mut *buf,
                 DecodingBuffer::I8(buf) => bytecast::i8_as_ne_mut_bytes(buf),
@@ -1208,9 +2360,28 @@ impl<R: Read + Seek> Decoder<R> {
             }
         }
 
-        Self::fix_endianness(&mut buffer, byte_order);
+        let byte_order = if floating_point_predictor {
+            let byte_len = buffer.byte_len();
+            let row_bytes = usize::try_from(self.width)? * self.chunk_samples() * byte_len;
+            let bytes = match &mut buffer {
+                DecodingBuffer::F32(buf) => bytecast::f32_as_ne_mut_bytes(buf),
+                DecodingBuffer::F64(buf) => bytecast::f64_as_ne_mut_bytes(buf),
+                _ => unreachable!(),
+            };
+            rev_fpredict(bytes, row_bytes, self.chunk_samples(), byte_len);
+            ByteOrder::BigEndian
+        } else {
+            byte_order
+        };
+
+        // Unpacked samples are already in native byte order.
+        if packed_bits.is_none() {
+            Self::fix_endianness(&mut buffer, byte_order);
+        }
 
-        if self.photometric_interpretation == PhotometricInterpretation::WhiteIsZero {
+        if self.photometric_interpretation == PhotometricInterpretation::WhiteIsZero
+            && !packed_output
+        {
             Self::invert_colors(&mut buffer, color_type);
         }
 
@@ -1232,13 +2403,36 @@ impl<R: Read + Seek> Decoder<R> {
         let tile_attrs = self.tile_attributes.as_mut().unwrap();
         let (padding_right, padding_down) = tile_attrs.get_padding(tile);
         let tile_samples = tile_attrs.tile_samples;
+        let tile_width = tile_attrs.tile_width;
         let tile_length = tile_attrs.tile_length;
         let row_samples = tile_attrs.row_samples;
-        let padding_right_samples = padding_right * self.bits_per_sample.len();
+        let padding_right_samples = padding_right * self.chunk_samples();
+
+        let packed_bits = self.packed_bits();
+        let packed_output = self.packed_output_bits().is_some();
+        let mut packed_row = packed_bits.map(|bits| vec![0; packed_row_bytes(row_samples, bits)]);
+        let fax_options = self.fax_options(tile_width)?;
+        let data_units = self.data_units()?;
+        let sgilog_encoding = self.sgilog_encoding();
+        if sgilog_encoding.is_some() && self.planar_config == PlanarConfiguration::Planar {
+            return Err(
+                TiffUnsupportedError::UnsupportedPlanarConfig(Some(self.planar_config)).into(),
+            );
+        }
+        self.check_compression()?;
 
         self.goto_offset_u64(offset)?;
 
-        let line_samples = output_width * self.bits_per_sample.len();
+        let line_samples = output_width * self.chunk_samples();
+
+        // The floating point predictor needs whole rows of the tile, including the padding, and
+        // leaves the samples in big endian byte order.
+        let (mut predictor_row, byte_order) = if self.floating_point_predictor(&buffer)? {
+            (Some(vec![0; row_samples * byte_len]), ByteOrder::BigEndian)
+        } else {
+            (None, self.byte_order)
+        };
+        let samples = self.chunk_samples();
 
         let mut reader = Self::create_reader(
             &mut self.reader,
@@ -1247,37 +2441,83 @@ impl<R: Read + Seek> Decoder<R> {
             tile_samples,
             byte_len,
             self.limits.intermediate_buffer_size,
+            fax_options,
         )?;
 
-        for row in 0..(tile_length - padding_down) {
-            let buf = match &mut buffer {
-                DecodingBuffer::U8(buf) => &mut *buf,
-                DecodingBuffer::I8(buf) => bytecast::i8_as_ne_mut_bytes(buf),
-                DecodingBuffer::U16(buf) => bytecast::u16_as_ne_mut_bytes(buf),
-                DecodingBuffer::I16(buf) => bytecast::i16_as_ne_mut_bytes(buf),
-                DecodingBuffer::U32(buf) => bytecast::u32_as_ne_mut_bytes(buf),
-                DecodingBuffer::I32(buf) => bytecast::i32_as_ne_mut_bytes(buf),
-                DecodingBuffer::U64(buf) => bytecast::u64_as_ne_mut_bytes(buf),
-                DecodingBuffer::I64(buf) => bytecast::i64_as_ne_mut_bytes(buf),
-                DecodingBuffer::F32(buf) => bytecast::f32_as_ne_mut_bytes(buf),
-                DecodingBuffer::F64(buf) => bytecast::f64_as_ne_mut_bytes(buf),
-            };
+        if let (Some(encoding), DecodingBuffer::F32(buf)) = (sgilog_encoding, &mut buffer) {
+            let (width, rows) = (tile_width - padding_right, tile_length - padding_down);
+            sgilog::read_rows(reader, buf, encoding, tile_width, width, rows, line_samples)?;
+            return Ok(());
+        }
 
+        if let (Some(subsampling), DecodingBuffer::U8(buf)) = (data_units, &mut buffer) {
+            let (width, rows) = (tile_width - padding_right, tile_length - padding_down);
+            ycbcr::read_data_units(
+                reader,
+                buf,
+                subsampling,
+                tile_width,
+                width,
+                rows,
+                line_samples,
+            )?;
+            return Ok(());
+        }
+
+        for row in 0..(tile_length - padding_down) {
             let row_start = row * line_samples;
             let row_end = row_start + row_samples - padding_right_samples;
 
-            let row = &mut buf[(row_start * byte_len)..(row_end * byte_len)];
-            reader.read_exact(row)?;
+            if let (Some(bits), Some(packed_row)) = (packed_bits, packed_row.as_mut()) {
+                reader.read_exact(packed_row)?;
+                if !packed_output {
+                    unpack_row(packed_row, buffer.subrange(row_start..row_end), bits);
+                } else if let DecodingBuffer::U8(buf) = &mut buffer {
+                    // Tiles are a multiple of 16 pixels wide, their rows start at a byte boundary.
+                    let line_bytes = packed_row_bytes(line_samples, bits);
+                    let data_bytes = packed_row_bytes(row_samples - padding_right_samples, bits);
+                    buf[row * line_bytes..][..data_bytes]
+                        .copy_from_slice(&packed_row[..data_bytes]);
+                } else {
+                    return Err(TiffError::UnsupportedError(
+                        TiffUnsupportedError::UnsupportedColorType(color_type),
+                    ));
+                }
+            } else {
+                let buf = match &mut buffer {
+                    DecodingBuffer::U8(buf) => &mut *buf,
+                    DecodingBuffer::I8(buf) => bytecast::i8_as_ne_mut_bytes(buf),
+                    DecodingBuffer::U16(buf) => bytecast::u16_as_ne_mut_bytes(buf),
+                    DecodingBuffer::I16(buf) => bytecast::i16_as_ne_mut_bytes(buf),
+                    DecodingBuffer::U32(buf) => bytecast::u32_as_ne_mut_bytes(buf),
+                    DecodingBuffer::I32(buf) => bytecast::i32_as_ne_mut_bytes(buf),
+                    DecodingBuffer::U64(buf) => bytecast::u64_as_ne_mut_bytes(buf),
+                    DecodingBuffer::I64(buf) => bytecast::i64_as_ne_mut_bytes(buf),
+                    DecodingBuffer::F32(buf) => bytecast::f32_as_ne_mut_bytes(buf),
+                    DecodingBuffer::F64(buf) => bytecast::f64_as_ne_mut_bytes(buf),
+                };
+
+                let row = &mut buf[(row_start * byte_len)..(row_end * byte_len)];
+                if let Some(predictor_row) = predictor_row.as_mut() {
+                    reader.read_exact(predictor_row)?;
+                    rev_fpredict(predictor_row, row_samples * byte_len, samples, byte_len);
+                    row.copy_from_slice(&predictor_row[..row.len()]);
+                } else {
+                    reader.read_exact(row)?;
+
+                    // Skip horizontal padding
+                    if padding_right > 0 {
+                        let len = u64::try_from(padding_right_samples * byte_len)?;
+                        io::copy(&mut reader.by_ref().take(len), &mut io::sink())?;
+                    }
+                }
 
-            // Skip horizontal padding
-            if padding_right > 0 {
-                let len = u64::try_from(padding_right_samples * byte_len)?;
-                io::copy(&mut reader.by_ref().take(len), &mut io::sink())?;
+                Self::fix_endianness(&mut buffer.subrange(row_start..row_end), byte_order);
             }
 
-            Self::fix_endianness(&mut buffer.subrange(row_start..row_end), self.byte_order);
-
-            if self.photometric_interpretation == PhotometricInterpretation::WhiteIsZero {
+            if self.photometric_interpretation == PhotometricInterpretation::WhiteIsZero
+                && !packed_output
+            {
                 Self::invert_colors(&mut buffer.subrange(row_start..row_end), color_type);
             }
         }
@@ -1285,6 +2525,227 @@ impl<R: Read + Seek> Decoder<R> {
         Ok(())
     }
 
+    /// Whether the samples are differenced with the floating point predictor
+    ///
+    /// Returns an error if the predictor is used for samples other than floating point ones.
+    fn floating_point_predictor(&mut self, buffer: &DecodingBuffer) -> TiffResult<bool> {
+        let predictor = self.get_tag_unsigned(Tag::Predictor).ok();
+        if predictor.and_then(Predictor::from_u16) != Some(Predictor::FloatingPoint) {
+            return Ok(false);
+        }
+
+        match buffer {
+            DecodingBuffer::F32(_) | DecodingBuffer::F64(_) => Ok(true),
+            _ => Err(TiffError::UnsupportedError(
+                TiffUnsupportedError::FloatingPointPredictor(self.colortype()?),
+            )),
+        }
+    }
+
+    /// The subsampling of YCbCr images whose samples are stored in data units
+    ///
+    /// Returns an error if the data units cannot be decoded.
+    fn data_units(&self) -> TiffResult<Option<(usize, usize)>> {
+        match self.ycbcr_subsampling {
+            None | Some((1, 1)) => Ok(None),
+            Some(_) if self.planar_config == PlanarConfiguration::Planar => {
+                Err(TiffUnsupportedError::UnsupportedPlanarConfig(Some(self.planar_config)).into())
+            }
+            Some(_) if self.bits_per_sample != [8, 8, 8] => {
+                Err(TiffUnsupportedError::InterpretationWithBits(
+                    self.photometric_interpretation,
+                    self.bits_per_sample.clone(),
+                )
+                .into())
+            }
+            Some((h, v)) => Ok(Some((usize::from(h), usize::from(v)))),
+        }
+    }
+
+    /// The encoding of the pixels of SGILog compressed LogL and LogLuv images
+    fn sgilog_encoding(&self) -> Option<sgilog::Encoding> {
+        match (self.compression_method, self.photometric_interpretation) {
+            (CompressionMethod::SGILog, PhotometricInterpretation::LogL)
+            | (CompressionMethod::SGILog24, PhotometricInterpretation::LogL) => {
+                Some(sgilog::Encoding::LogL16)
+            }
+            (CompressionMethod::SGILog, PhotometricInterpretation::LogLuv) => {
+                Some(sgilog::Encoding::LogLuv32)
+            }
+            (CompressionMethod::SGILog24, PhotometricInterpretation::LogLuv) => {
+                Some(sgilog::Encoding::LogLuv24)
+            }
+            _ => None,
+        }
+    }
+
+    /// Bits of the integer samples if they are packed with other sizes than 8, 16, 32 or 64 bits
+    fn packed_bits(&self) -> Option<u8> {
+        let bits = *self.bits_per_sample.first()?;
+        let integer = self.sample_format.first() != Some(&SampleFormat::IEEEFP);
+        let uniform = self.bits_per_sample.iter().all(|&b| b == bits);
+        match bits {
+            8 | 16 | 32 => None,
+            1..=31 if integer && uniform => Some(bits),
+            _ => None,
+        }
+    }
+
+    /// Bits of the samples if they are returned packed as requested with
+    /// [`with_packed_output`][Decoder::with_packed_output]
+    fn packed_output_bits(&self) -> Option<u8> {
+        self.packed_bits().filter(|_| self.packed_output)
+    }
+
+    /// Number of elements of a buffer for `height` rows of `width` pixels with `samples` samples
+    /// each, counted in bytes if the samples are returned packed
+    fn buffer_len(&self, width: usize, height: usize, samples: usize) -> Option<usize> {
+        let row_samples = width.checked_mul(samples)?;
+        let row_len = match self.packed_output_bits() {
+            Some(bits) => {
+                row_samples.checked_mul(usize::from(bits))?;
+                packed_row_bytes(row_samples, bits)
+            }
+            None => row_samples,
+        };
+        row_len.checked_mul(height)
+    }
+
+    /// Expands `rows` packed rows of `row_samples` samples to one integer per sample.
+    fn unpack_rows(
+        &self,
+        packed: &[u8],
+        rows: usize,
+        row_samples: usize,
+        bits: u8,
+    ) -> TiffResult<DecodingResult> {
+        let size = rows
+            .checked_mul(row_samples)
+            .ok_or(TiffError::LimitsExceeded)?;
+        let format = self.sample_format.first().unwrap_or(&SampleFormat::Uint);
+        let mut result = Self::sample_buffer(size, bits, format, &self.limits)?;
+
+        let row_bytes = packed_row_bytes(row_samples, bits);
+        for (row, packed_row) in packed.chunks(row_bytes.max(1)).take(rows).enumerate() {
+            let start = row * row_samples;
+            unpack_row(
+                packed_row,
+                result.as_buffer(0).subrange(start..start + row_samples),
+                bits,
+            );
+        }
+        Ok(result)
+    }
+
+    /// Packs the samples of a result that were unpacked to be converted or rearranged, if
+    /// requested with [`with_packed_output`][Decoder::with_packed_output].
+    fn packed_result(
+        &self,
+        result: DecodingResult,
+        row_samples: usize,
+    ) -> TiffResult<DecodingResult> {
+        let bits = match self.packed_output_bits() {
+            Some(bits) => bits,
+            None => return Ok(result),
+        };
+        // Signed samples are packed as their two's complement.
+        let mask = u64::MAX >> (64 - bits);
+
+        let packed = match result {
+            DecodingResult::U8(buf) => {
+                pack_rows(buf.into_iter().map(u64::from), row_samples, bits)?
+            }
+            DecodingResult::U16(buf) => {
+                pack_rows(buf.into_iter().map(u64::from), row_samples, bits)?
+            }
+            DecodingResult::U32(buf) => {
+                pack_rows(buf.into_iter().map(u64::from), row_samples, bits)?
+            }
+            DecodingResult::I8(buf) => {
+                pack_rows(buf.into_iter().map(|v| v as u64 & mask), row_samples, bits)?
+            }
+            DecodingResult::I16(buf) => {
+                pack_rows(buf.into_iter().map(|v| v as u64 & mask), row_samples, bits)?
+            }
+            DecodingResult::I32(buf) => {
+                pack_rows(buf.into_iter().map(|v| v as u64 & mask), row_samples, bits)?
+            }
+            result => return Ok(result),
+        };
+        Ok(DecodingResult::U8(packed))
+    }
+
+    /// The order of the bits within the bytes of the image data
+    ///
+    /// Unknown values are an error for the CCITT compressions only, the other compressions
+    /// ignore them like libtiff and use the default `FillOrder::MsbToLsb`.
+    fn fill_order(&mut self) -> TiffResult<FillOrder> {
+        let fill_order = self.find_tag_unsigned(Tag::FillOrder)?.unwrap_or(1);
+        match FillOrder::from_u16(fill_order) {
+            Some(fill_order) => Ok(fill_order),
+            None => match self.compression_method {
+                CompressionMethod::Huffman | CompressionMethod::Fax3 | CompressionMethod::Fax4 => {
+                    Err(TiffFormatError::UnknownFillOrder(fill_order).into())
+                }
+                _ => Ok(FillOrder::MsbToLsb),
+            },
+        }
+    }
+
+    /// The settings of the CCITT compressions for chunks `width` pixels wide
+    ///
+    /// The fill order is also used by the other compressions.
+    fn fax_options(&mut self, width: usize) -> TiffResult<FaxOptions> {
+        let fill_order = self.fill_order()?;
+        let options_tag = match self.compression_method {
+            CompressionMethod::Huffman => None,
+            CompressionMethod::Fax3 => Some(Tag::T4Options),
+            CompressionMethod::Fax4 => Some(Tag::T6Options),
+            _ => {
+                return Ok(FaxOptions {
+                    width,
+                    options: 0,
+                    fill_order,
+                })
+            }
+        };
+
+        if self.bits_per_sample[..] != [1] {
+            return Err(TiffError::UnsupportedError(
+                TiffUnsupportedError::InterpretationWithBits(
+                    self.photometric_interpretation,
+                    self.bits_per_sample.clone(),
+                ),
+            ));
+        }
+
+        let options = match options_tag {
+            Some(tag) => self.find_tag_unsigned(tag)?.unwrap_or(0),
+            None => 0,
+        };
+
+        Ok(FaxOptions {
+            width,
+            options,
+            fill_order,
+        })
+    }
+
+    /// Checks that the image data can be decompressed by the reader of
+    /// [`create_reader`][Decoder::create_reader].
+    ///
+    /// SGILog compressed pixels can only be decoded for LogL and LogLuv images.
+    fn check_compression(&self) -> TiffResult<()> {
+        match self.compression_method {
+            method @ CompressionMethod::SGILog | method @ CompressionMethod::SGILog24
+                if self.sgilog_encoding().is_none() =>
+            {
+                Err(TiffUnsupportedError::UnsupportedCompressionMethod(method).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn create_reader<'r>(
         reader: &'r mut SmartReader<R>,
         compression_method: CompressionMethod,
@@ -1292,9 +2753,21 @@ impl<R: Read + Seek> Decoder<R> {
         samples: usize,  // Expected chunk length in samples
         byte_len: usize, // Byte length of the samples in result buffer
         intermediate_buffer_size: usize,
+        fax_options: FaxOptions,
     ) -> TiffResult<Box<dyn Read + 'r>> {
+        let fax = matches!(
+            compression_method,
+            CompressionMethod::Huffman | CompressionMethod::Fax3 | CompressionMethod::Fax4
+        );
+        // The bits of the compressed data are reversed before decompression, the fax decoder
+        // does so itself.
+        let reader: Box<dyn Read + 'r> = match fax_options.fill_order {
+            FillOrder::LsbToMsb if !fax => Box::new(ReverseBitsReader::new(reader)),
+            _ => Box::new(reader),
+        };
+
         Ok(match compression_method {
-            CompressionMethod::None => Box::new(reader),
+            CompressionMethod::None => reader,
             CompressionMethod::LZW => {
                 let clen = usize::try_from(compressed_length)?;
 
@@ -1311,6 +2784,27 @@ impl<R: Read + Seek> Decoder<R> {
             CompressionMethod::Deflate | CompressionMethod::OldDeflate => {
                 Box::new(DeflateReader::new(reader))
             }
+            CompressionMethod::SGILog | CompressionMethod::SGILog24 => {
+                if compressed_length > intermediate_buffer_size as u64 {
+                    return Err(TiffError::LimitsExceeded);
+                }
+
+                // The pixels are decompressed when they are converted to samples, the
+                // photometric interpretation is checked by `check_compression`.
+                Box::new(reader.take(compressed_length))
+            }
+            method @ CompressionMethod::Huffman
+            | method @ CompressionMethod::Fax3
+            | method @ CompressionMethod::Fax4 => {
+                let clen = usize::try_from(compressed_length)?;
+                let rows = samples / fax_options.width.max(1);
+
+                if samples / 8 > intermediate_buffer_size || clen > intermediate_buffer_size {
+                    return Err(TiffError::LimitsExceeded);
+                }
+
+                Box::new(FaxReader::new(reader, clen, method, fax_options, rows)?)
+            }
             method => {
                 return Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::UnsupportedCompressionMethod(method),
@@ -1335,51 +2829,529 @@ impl<R: Read + Seek> Decoder<R> {
         self.chunk_type
     }
 
-    /// Number of strips in image
-    pub fn strip_count(&mut self) -> TiffResult<u32> {
-        self.check_chunk_type(ChunkType::Strip)?;
-        let rows_per_strip = self.get_tag_u32(Tag::RowsPerStrip).unwrap_or(self.height);
+    /// Number of samples per pixel within a single strip or tile
+    fn chunk_samples(&self) -> usize {
+        match self.planar_config {
+            PlanarConfiguration::Planar => 1,
+            _ => self.bits_per_sample.len(),
+        }
+    }
 
-        if rows_per_strip == 0 {
-            return Ok(0);
+    /// Number of samples per pixel within a row of the results of `read_image`
+    fn output_samples(&self) -> usize {
+        match self.planar_output {
+            PlanarConfiguration::Planar => 1,
+            _ => self.bits_per_sample.len(),
         }
+    }
 
-        // rows_per_strip - 1 can never fail since we know it's at least 1
-        let height = match self.height.checked_add(rows_per_strip - 1) {
-            Some(h) => h,
-            None => return Err(TiffError::IntSizeError),
-        };
+    /// Number of planes the chunks of the image are divided into
+    fn planes(&self) -> usize {
+        match self.planar_config {
+            PlanarConfiguration::Planar => self.bits_per_sample.len(),
+            _ => 1,
+        }
+    }
+
+    /// Number of strips or tiles needed to cover the image once
+    fn chunks_per_plane(&mut self) -> TiffResult<u32> {
+        match self.chunk_type {
+            ChunkType::Strip => {
+                let rows_per_strip = self.get_tag_u32(Tag::RowsPerStrip).unwrap_or(self.height);
+
+                if rows_per_strip == 0 {
+                    return Ok(0);
+                }
+
+                // rows_per_strip - 1 can never fail since we know it's at least 1
+                let height = match self.height.checked_add(rows_per_strip - 1) {
+                    Some(h) => h,
+                    None => return Err(TiffError::IntSizeError),
+                };
 
-        Ok(height / rows_per_strip)
+                Ok(height / rows_per_strip)
+            }
+            ChunkType::Tile => {
+                let tile_attrs = self.tile_attributes.as_ref().unwrap();
+                Ok(u32::try_from(tile_attrs.tiles_per_plane())?)
+            }
+        }
+    }
+
+    /// Number of strips in image
+    ///
+    /// For planar images this counts the strips of all planes.
+    pub fn strip_count(&mut self) -> TiffResult<u32> {
+        self.check_chunk_type(ChunkType::Strip)?;
+        self.chunk_count()
     }
 
     /// Number of tiles in image
+    ///
+    /// For planar images this counts the tiles of all planes.
     pub fn tile_count(&mut self) -> TiffResult<u32> {
         self.check_chunk_type(ChunkType::Tile)?;
-        let tile_attrs = self.tile_attributes.as_ref().unwrap();
-        Ok(u32::try_from(
-            tile_attrs.tiles_across * tile_attrs.tiles_down,
-        )?)
+        self.chunk_count()
     }
 
-    pub fn read_jpeg(&mut self) -> TiffResult<DecodingResult> {
-        let offsets = self.get_tag_u32_vec(Tag::StripOffsets)?;
-        let bytes = self.get_tag_u32_vec(Tag::StripByteCounts)?;
+    /// Number of strips or tiles in the image, depending on its chunk type
+    ///
+    /// For planar images this counts the chunks of all planes.
+    pub fn chunk_count(&mut self) -> TiffResult<u32> {
+        self.chunks_per_plane()?
+            .checked_mul(u32::try_from(self.planes())?)
+            .ok_or(TiffError::IntSizeError)
+    }
 
-        let jpeg_tables: Option<Vec<u8>> = match self.find_tag(Tag::JPEGTables) {
-            Ok(None) => None,
-            Ok(_) => {
-                let vec = self.get_tag_u8_vec(Tag::JPEGTables)?;
+    /// Index of the plane the chunk with the given index belongs to.
+    ///
+    /// The chunks of planar images are stored plane by plane, each plane holding a single sample
+    /// of every pixel. For chunky images this is always 0.
+    pub fn chunk_plane(&mut self, index: u32) -> TiffResult<u32> {
+        self.check_chunk_index(index)?;
+        Ok(index / self.chunks_per_plane()?)
+    }
 
-                if vec.len() < 2 {
-                    return Err(TiffError::FormatError(
-                        TiffFormatError::InvalidTagValueType(Tag::JPEGTables),
-                    ));
-                }
+    fn check_chunk_index(&mut self, index: u32) -> TiffResult<()> {
+        let count = self.chunk_count()?;
+        if index >= count {
+            return Err(TiffError::UsageError(UsageError::InvalidChunkIndex(
+                index, count,
+            )));
+        }
 
-                Some(vec)
-            }
-            Err(e) => return Err(e),
+        Ok(())
+    }
+
+    /// Nominal dimensions of a single strip or tile in pixels.
+    ///
+    /// Chunks at the right and bottom border of the image may contain less image data than
+    /// this, see [`chunk_data_dimensions`][Decoder::chunk_data_dimensions].
+    pub fn chunk_dimensions(&mut self) -> TiffResult<(u32, u32)> {
+        match self.chunk_type {
+            ChunkType::Strip => {
+                let rows_per_strip = self.get_tag_u32(Tag::RowsPerStrip).unwrap_or(self.height);
+                Ok((self.width, rows_per_strip.min(self.height)))
+            }
+            ChunkType::Tile => {
+                let tile_attrs = self.tile_attributes.as_ref().unwrap();
+                Ok((
+                    u32::try_from(tile_attrs.tile_width)?,
+                    u32::try_from(tile_attrs.tile_length)?,
+                ))
+            }
+        }
+    }
+
+    /// Position of the top left pixel of the chunk with the given index within the stored image.
+    pub fn chunk_origin(&mut self, index: u32) -> TiffResult<(u32, u32)> {
+        self.check_chunk_index(index)?;
+        let index = usize::try_from(index % self.chunks_per_plane()?)?;
+        let (width, height) = self.chunk_dimensions()?;
+
+        let (column, row) = match self.chunk_type {
+            ChunkType::Strip => (0, index),
+            ChunkType::Tile => {
+                let tiles_across = self.tile_attributes.as_ref().unwrap().tiles_across;
+                (index % tiles_across, index / tiles_across)
+            }
+        };
+
+        Ok((u32::try_from(column)? * width, u32::try_from(row)? * height))
+    }
+
+    /// Dimensions of the image data contained in the chunk with the given index.
+    ///
+    /// This is the size of the pixel rectangle starting at
+    /// [`chunk_origin`][Decoder::chunk_origin], with the padding of chunks on the right and bottom
+    /// border of the image removed. It is also the size of the result of
+    /// [`read_chunk`][Decoder::read_chunk].
+    pub fn chunk_data_dimensions(&mut self, index: u32) -> TiffResult<(u32, u32)> {
+        let (x, y) = self.chunk_origin(index)?;
+        let (width, height) = self.chunk_dimensions()?;
+
+        Ok((width.min(self.width - x), height.min(self.height - y)))
+    }
+
+    /// Decodes the strip or tile with the given index into the supplied buffer.
+    ///
+    /// Chunks are addressed in the order of their offsets in the file, that is row by row for
+    /// tiles. The buffer receives the rows of the chunk data, without any padding, and must be
+    /// large enough to hold the [`chunk_data_dimensions`][Decoder::chunk_data_dimensions]. For
+    /// planar images each chunk only holds the single sample of its
+    /// [`chunk_plane`][Decoder::chunk_plane] per pixel.
+    ///
+    /// The samples are converted like by [`read_region`][Decoder::read_region] for the pixels of
+    /// the chunk. Conversions that need all samples of a pixel, such as the RGB output of Lab
+    /// images, are not supported for chunks of planar images.
+    ///
+    /// Unlike [`read_strip_to_buffer`][Decoder::read_strip_to_buffer] this does not depend on,
+    /// nor modify, the position of the decoder in the sequence of chunks.
+    pub fn read_chunk_to_buffer(&mut self, index: u32, buffer: DecodingBuffer) -> TiffResult<()> {
+        if self.converts_region_samples() {
+            let (x, y) = self.chunk_origin(index)?;
+            let (width, height) = self.chunk_data_dimensions(index)?;
+            self.check_converted_chunks()?;
+            return self.read_region_to_buffer(x, y, width, height, buffer);
+        }
+        self.read_stored_chunk_to_buffer(index, buffer)
+    }
+
+    /// Decodes the samples of a chunk as stored in the file.
+    fn read_stored_chunk_to_buffer(
+        &mut self,
+        index: u32,
+        mut buffer: DecodingBuffer,
+    ) -> TiffResult<()> {
+        let (width, height) = self.chunk_data_dimensions(index)?;
+        let index = usize::try_from(index)?;
+
+        match self.chunk_type {
+            ChunkType::Strip => self.read_strip_index_to_buffer(buffer, index),
+            ChunkType::Tile => {
+                let width = usize::try_from(width)?;
+                let buffer_size = self
+                    .buffer_len(width, usize::try_from(height)?, self.chunk_samples())
+                    .ok_or(TiffError::LimitsExceeded)?;
+
+                if buffer.len() < buffer_size {
+                    return Err(TiffError::FormatError(
+                        TiffFormatError::InconsistentSizesEncountered,
+                    ));
+                }
+
+                self.read_tile_to_buffer(&mut buffer, index, width)
+            }
+        }
+    }
+
+    /// Decodes the strip or tile with the given index and returns it as a Vector
+    ///
+    /// See [`read_chunk_to_buffer`][Decoder::read_chunk_to_buffer] for details.
+    pub fn read_chunk(&mut self, index: u32) -> TiffResult<DecodingResult> {
+        if self.converts_region_samples() {
+            let (x, y) = self.chunk_origin(index)?;
+            let (width, height) = self.chunk_data_dimensions(index)?;
+            self.check_converted_chunks()?;
+            return self.read_converted_region((x, y), (width, height));
+        }
+        self.read_stored_chunk(index)
+    }
+
+    /// Decodes the samples of a chunk as stored in the file and returns them as a Vector
+    fn read_stored_chunk(&mut self, index: u32) -> TiffResult<DecodingResult> {
+        let (width, height) = self.chunk_data_dimensions(index)?;
+        let mut result = self.result_buffer(
+            usize::try_from(width)?,
+            usize::try_from(height)?,
+            self.chunk_samples(),
+        )?;
+        self.read_stored_chunk_to_buffer(index, result.as_buffer(0))?;
+        Ok(result)
+    }
+
+    /// Checks that the chunks hold all samples of their pixels if these are converted.
+    fn check_converted_chunks(&self) -> TiffResult<()> {
+        if self.planes() > 1 {
+            return Err(TiffUnsupportedError::UnsupportedPlanarConfig(Some(
+                PlanarConfiguration::Planar,
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Decodes a rectangular region of the image into the supplied buffer.
+    ///
+    /// The region starts at the pixel `x`, `y` of the stored image, within its
+    /// [`dimensions`][Decoder::dimensions], and its pixels are returned in the stored order even
+    /// with [`with_oriented_output`][Decoder::with_oriented_output].
+    ///
+    /// Only the strips or tiles intersecting the region are decoded, one at a time, so the
+    /// memory required does not depend on the size of the whole image. The buffer receives the
+    /// rows of the region, `width * height` pixels in total, and its type must match the one
+    /// [`read_image`][Decoder::read_image] would return. The samples are arranged as configured
+    /// with [`with_planar_output`][Decoder::with_planar_output] and converted as configured
+    /// with the other output options, for example palette images with RGB output receive three
+    /// samples per pixel.
+    pub fn read_region_to_buffer(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        mut buffer: DecodingBuffer,
+    ) -> TiffResult<()> {
+        if self.converts_region_samples() {
+            let result = self.read_converted_region((x, y), (width, height))?;
+            if buffer.len() < result.len() {
+                return Err(TiffError::FormatError(
+                    TiffFormatError::InconsistentSizesEncountered,
+                ));
+            }
+            return buffer.copy_from(0, 1, &result, 0, 1, result.len());
+        }
+        self.read_stored_region_to_buffer(x, y, width, height, buffer)
+    }
+
+    /// Decodes a region with the samples arranged, but not converted, as configured.
+    fn read_stored_region_to_buffer(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        mut buffer: DecodingBuffer,
+    ) -> TiffResult<()> {
+        let x_end = x.checked_add(width).filter(|&end| end <= self.width);
+        let y_end = y.checked_add(height).filter(|&end| end <= self.height);
+        let (x_end, y_end) = match (x_end, y_end) {
+            (Some(x_end), Some(y_end)) => (x_end, y_end),
+            _ => {
+                return Err(TiffError::UsageError(UsageError::InvalidRegion(
+                    (x, y),
+                    (width, height),
+                )))
+            }
+        };
+
+        let samples = self.bits_per_sample.len();
+        let region_width = usize::try_from(width)?;
+        let output_planes = samples / self.output_samples().max(1);
+        let buffer_size = self
+            .buffer_len(
+                region_width,
+                usize::try_from(height)?,
+                self.output_samples(),
+            )
+            .and_then(|x| x.checked_mul(output_planes))
+            .ok_or(TiffError::LimitsExceeded)?;
+
+        if buffer.len() < buffer_size {
+            return Err(TiffError::FormatError(
+                TiffFormatError::InconsistentSizesEncountered,
+            ));
+        }
+
+        if buffer_size == 0 {
+            return Ok(());
+        }
+
+        let packed_bits = self.packed_output_bits();
+        if let Some(bits) = packed_bits {
+            if samples > 1 && self.planar_config != self.planar_output {
+                return self.read_rearranged_packed_region((x, y), (width, height), buffer, bits);
+            }
+        }
+
+        let (chunk_width, chunk_height) = self.chunk_dimensions()?;
+        if chunk_height == 0 {
+            return Err(TiffFormatError::InvalidTagValueType(Tag::RowsPerStrip).into());
+        }
+
+        let chunks_across = match self.chunk_type {
+            ChunkType::Strip => 1,
+            ChunkType::Tile => u32::try_from(self.tile_attributes.as_ref().unwrap().tiles_across)?,
+        };
+        let chunks_per_plane = self.chunks_per_plane()?;
+        let chunk_samples = self.chunk_samples();
+
+        // Steps between the samples of one plane and between pixels in the output
+        let (dst_sample_step, dst_pixel_step) = match self.planar_output {
+            PlanarConfiguration::Planar => (buffer_size / samples, 1),
+            _ => (1, samples),
+        };
+
+        // One intermediate buffer, large enough for any chunk, is reused for all of them.
+        let mut chunk = self.result_buffer(
+            usize::try_from(chunk_width)?,
+            usize::try_from(chunk_height)?,
+            chunk_samples,
+        )?;
+
+        for plane in 0..u32::try_from(self.planes())? {
+            for row in y / chunk_height..=(y_end - 1) / chunk_height {
+                for column in x / chunk_width..=(x_end - 1) / chunk_width {
+                    let index = plane * chunks_per_plane + row * chunks_across + column;
+                    let (chunk_x, chunk_y) = self.chunk_origin(index)?;
+                    let (data_width, data_height) = self.chunk_data_dimensions(index)?;
+                    self.read_stored_chunk_to_buffer(index, chunk.as_buffer(0))?;
+
+                    // Intersection of the chunk data with the region
+                    let left = x.max(chunk_x);
+                    let right = x_end.min(chunk_x + data_width);
+                    let top = y.max(chunk_y);
+                    let bottom = y_end.min(chunk_y + data_height);
+
+                    let pixels = usize::try_from(right - left)?;
+                    if let (Some(bits), DecodingBuffer::U8(buf), DecodingResult::U8(chunk)) =
+                        (packed_bits, &mut buffer, &chunk)
+                    {
+                        // The region has the planar configuration of the file, see above.
+                        let pixel_bits = chunk_samples * usize::from(bits);
+                        let data_width = usize::try_from(data_width)?;
+                        let chunk_row_bits = packed_row_bytes(data_width * chunk_samples, bits) * 8;
+                        let region_row_bits =
+                            packed_row_bytes(region_width * chunk_samples, bits) * 8;
+                        let plane_bits = region_row_bits * usize::try_from(height)?;
+
+                        for line in top..bottom {
+                            let src = usize::try_from(line - chunk_y)? * chunk_row_bits
+                                + usize::try_from(left - chunk_x)? * pixel_bits;
+                            let dst = usize::try_from(plane)? * plane_bits
+                                + usize::try_from(line - y)? * region_row_bits
+                                + usize::try_from(left - x)? * pixel_bits;
+                            copy_bits(buf, dst, chunk, src, pixels * pixel_bits);
+                        }
+                        continue;
+                    }
+
+                    for line in top..bottom {
+                        let src = usize::try_from(line - chunk_y)? * usize::try_from(data_width)?
+                            + usize::try_from(left - chunk_x)?;
+                        let dst =
+                            usize::try_from(line - y)? * region_width + usize::try_from(left - x)?;
+
+                        if chunk_samples == samples && dst_pixel_step == samples {
+                            // Lines are copied as a whole between chunky layouts
+                            let len = pixels * chunk_samples;
+                            let (dst, src) = (dst * chunk_samples, src * chunk_samples);
+                            buffer.copy_from(dst, 1, &chunk, src, 1, len)?;
+                            continue;
+                        }
+
+                        for sample in 0..chunk_samples {
+                            let dst_sample = usize::try_from(plane)? * chunk_samples + sample;
+                            buffer.copy_from(
+                                dst_sample * dst_sample_step + dst * dst_pixel_step,
+                                dst_pixel_step,
+                                &chunk,
+                                src * chunk_samples + sample,
+                                chunk_samples,
+                                pixels,
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decodes a region of packed samples with another planar configuration than the file.
+    fn read_rearranged_packed_region(
+        &mut self,
+        (x, y): (u32, u32),
+        (width, height): (u32, u32),
+        mut buffer: DecodingBuffer,
+        bits: u8,
+    ) -> TiffResult<()> {
+        let planar_output = self.planar_output;
+        self.planar_output = self.planar_config;
+        let stored = self.read_stored_region((x, y), (width, height));
+        self.planar_output = planar_output;
+
+        let (width, height) = (usize::try_from(width)?, usize::try_from(height)?);
+        let stored = match stored? {
+            DecodingResult::U8(stored) => stored,
+            _ => unreachable!(),
+        };
+        let samples = self.unpack_rows(
+            &stored,
+            height * self.planes(),
+            width * self.chunk_samples(),
+            bits,
+        )?;
+
+        let samples = match self.planar_output {
+            PlanarConfiguration::Planar => samples.transposed(width * height, &self.limits)?,
+            _ => samples.transposed(self.bits_per_sample.len(), &self.limits)?,
+        };
+        let packed = self.packed_result(samples, width * self.output_samples())?;
+        buffer.copy_from(0, 1, &packed, 0, 1, packed.len())
+    }
+
+    /// Decodes a rectangular region of the image and returns it as a Vector
+    ///
+    /// See [`read_region_to_buffer`][Decoder::read_region_to_buffer] for details.
+    pub fn read_region(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> TiffResult<DecodingResult> {
+        if self.converts_region_samples() {
+            return self.read_converted_region((x, y), (width, height));
+        }
+        self.read_stored_region((x, y), (width, height))
+    }
+
+    /// Decodes a region with the samples arranged, but not converted, as configured.
+    fn read_stored_region(
+        &mut self,
+        (x, y): (u32, u32),
+        (width, height): (u32, u32),
+    ) -> TiffResult<DecodingResult> {
+        let output_planes = self.bits_per_sample.len() / self.output_samples().max(1);
+        let rows = usize::try_from(height)?
+            .checked_mul(output_planes)
+            .ok_or(TiffError::LimitsExceeded)?;
+        let mut result =
+            self.result_buffer(usize::try_from(width)?, rows, self.output_samples())?;
+        self.read_stored_region_to_buffer(x, y, width, height, result.as_buffer(0))?;
+        Ok(result)
+    }
+
+    /// Whether the samples of regions are converted, not only rearranged, as configured
+    fn converts_region_samples(&self) -> bool {
+        self.converts_colors()
+            || (self.unassociated_alpha_output
+                && self.alpha() == Some(ExtraSamples::AssociatedAlpha))
+    }
+
+    /// Decodes a region and converts its samples like [`read_image`][Decoder::read_image].
+    fn read_converted_region(
+        &mut self,
+        (x, y): (u32, u32),
+        (width, height): (u32, u32),
+    ) -> TiffResult<DecodingResult> {
+        // The samples are converted unpacked, in the arrangement of the output like for the
+        // whole image.
+        let packed_output = self.packed_output;
+        self.packed_output = false;
+        let stored = self.read_stored_region((x, y), (width, height));
+        self.packed_output = packed_output;
+        let mut result = stored?;
+
+        let (width, height) = (usize::try_from(width)?, usize::try_from(height)?);
+        if self.converts_colors() {
+            return self.color_result(result, width, height);
+        }
+
+        self.unassociate_alpha(&mut result);
+        self.packed_result(result, width * self.output_samples())
+    }
+
+    pub fn read_jpeg(&mut self) -> TiffResult<DecodingResult> {
+        let offsets = self.get_tag_u32_vec(Tag::StripOffsets)?;
+        let bytes = self.get_tag_u32_vec(Tag::StripByteCounts)?;
+
+        let jpeg_tables: Option<Vec<u8>> = match self.find_tag(Tag::JPEGTables) {
+            Ok(None) => None,
+            Ok(_) => {
+                let vec = self.get_tag_u8_vec(Tag::JPEGTables)?;
+
+                if vec.len() < 2 {
+                    return Err(TiffError::FormatError(
+                        TiffFormatError::InvalidTagValueType(Tag::JPEGTables),
+                    ));
+                }
+
+                Some(vec)
+            }
+            Err(e) => return Err(e),
         };
 
         if offsets.len() == 0 {
@@ -1401,6 +3373,7 @@ impl<R: Read + Seek> Decoder<R> {
             return Err(TiffError::LimitsExceeded);
         }
 
+        let fill_order = self.fill_order()?;
         let mut res_img = Vec::with_capacity(offsets[0] as usize);
 
         for (idx, offset) in offsets.iter().enumerate() {
@@ -1412,7 +3385,13 @@ impl<R: Read + Seek> Decoder<R> {
                     TiffFormatError::InvalidTagValueType(Tag::JPEGTables),
                 ));
             }
-            let jpeg_reader = JpegReader::new(&mut self.reader, length, &jpeg_tables)?;
+            let jpeg_reader = match fill_order {
+                FillOrder::LsbToMsb => {
+                    let reader = ReverseBitsReader::new(&mut self.reader);
+                    JpegReader::new(reader, length, &jpeg_tables)?
+                }
+                _ => JpegReader::new(&mut self.reader, length, &jpeg_tables)?,
+            };
             let mut decoder = jpeg::Decoder::new(jpeg_reader);
 
             match decoder.decode() {
@@ -1437,9 +3416,20 @@ impl<R: Read + Seek> Decoder<R> {
         Ok(DecodingResult::U8(res_img))
     }
 
-    pub fn read_strip_to_buffer(&mut self, mut buffer: DecodingBuffer) -> TiffResult<()> {
+    pub fn read_strip_to_buffer(&mut self, buffer: DecodingBuffer) -> TiffResult<()> {
         self.check_chunk_type(ChunkType::Strip)?;
         let index = self.strip_decoder.as_ref().unwrap().strip_index;
+        self.read_strip_index_to_buffer(buffer, index)?;
+        self.strip_decoder.as_mut().unwrap().strip_index += 1;
+        Ok(())
+    }
+
+    /// Decompresses the strip with the given index into the supplied buffer.
+    fn read_strip_index_to_buffer(
+        &mut self,
+        mut buffer: DecodingBuffer,
+        index: usize,
+    ) -> TiffResult<()> {
         let offset = *self
             .strip_decoder
             .as_ref()
@@ -1464,7 +3454,12 @@ impl<R: Read + Seek> Decoder<R> {
         let sized_width = usize::try_from(self.width)?;
         let sized_height = usize::try_from(self.height)?;
 
-        let strip_height_without_padding = index
+        // Index of the strip within its plane
+        let strips_per_plane = usize::try_from(self.chunks_per_plane()?)?;
+        let plane = (index / strips_per_plane.max(1)).min(self.planes().saturating_sub(1));
+        let plane_index = index - plane * strips_per_plane;
+
+        let strip_height_without_padding = plane_index
             .checked_mul(rows_per_strip)
             .and_then(|x| sized_height.checked_sub(x))
             .ok_or(TiffError::IntSizeError)?;
@@ -1472,9 +3467,8 @@ impl<R: Read + Seek> Decoder<R> {
         // Ignore potential vertical padding on the bottommost strip
         let strip_height = rows_per_strip.min(strip_height_without_padding);
 
-        let buffer_size = sized_width
-            .checked_mul(strip_height)
-            .and_then(|x| x.checked_mul(self.bits_per_sample.len()))
+        let buffer_size = self
+            .buffer_len(sized_width, strip_height, self.chunk_samples())
             .ok_or(TiffError::LimitsExceeded)?;
 
         if buffer.len() < buffer_size {
@@ -1484,11 +3478,7 @@ impl<R: Read + Seek> Decoder<R> {
         }
 
         self.expand_strip(buffer.subrange(0..buffer_size), offset, byte_count)?;
-        self.strip_decoder.as_mut().unwrap().strip_index += 1;
 
-        if u32::try_from(index)? == self.strip_count()? {
-            self.strip_decoder = None;
-        }
         if let Ok(predictor) = self.get_tag_unsigned(Tag::Predictor) {
             match Predictor::from_u16(predictor) {
                 Some(Predictor::None) => (),
@@ -1497,9 +3487,12 @@ impl<R: Read + Seek> Decoder<R> {
                         buffer.copy(),
                         (self.width, u32::try_from(strip_height)?),
                         usize::try_from(self.width)?,
+                        self.chunk_samples(),
                         self.colortype()?,
                     )?;
                 }
+                // Reversed while expanding the strip, as it works on the bytes of the file.
+                Some(Predictor::FloatingPoint) => {}
                 None => {
                     return Err(TiffError::FormatError(TiffFormatError::UnknownPredictor(
                         predictor,
@@ -1562,9 +3555,12 @@ impl<R: Read + Seek> Decoder<R> {
                             u32::try_from(tile_length - padding_down)?,
                         ),
                         output_width,
+                        self.chunk_samples(),
                         self.colortype()?,
                     )?;
                 }
+                // Reversed while expanding the tile, as it works on the bytes of the file.
+                Some(Predictor::FloatingPoint) => {}
                 None => {
                     return Err(TiffError::FormatError(TiffFormatError::UnknownPredictor(
                         predictor,
@@ -1576,38 +3572,54 @@ impl<R: Read + Seek> Decoder<R> {
         Ok(())
     }
 
-    fn result_buffer(&self, width: usize, height: usize) -> TiffResult<DecodingResult> {
-        let buffer_size = match width
-            .checked_mul(height)
-            .and_then(|x| x.checked_mul(self.bits_per_sample.len()))
-        {
+    fn result_buffer(
+        &self,
+        width: usize,
+        height: usize,
+        samples: usize,
+    ) -> TiffResult<DecodingResult> {
+        let buffer_size = match self.buffer_len(width, height, samples) {
             Some(s) => s,
             None => return Err(TiffError::LimitsExceeded),
         };
+        if self.packed_output_bits().is_some() {
+            return DecodingResult::new_u8(buffer_size, &self.limits);
+        }
 
         let max_sample_bits = self.bits_per_sample.iter().cloned().max().unwrap_or(8);
-        match self.sample_format.first().unwrap_or(&SampleFormat::Uint) {
-            SampleFormat::Uint => match max_sample_bits {
-                n if n <= 8 => DecodingResult::new_u8(buffer_size, &self.limits),
-                n if n <= 16 => DecodingResult::new_u16(buffer_size, &self.limits),
-                n if n <= 32 => DecodingResult::new_u32(buffer_size, &self.limits),
-                n if n <= 64 => DecodingResult::new_u64(buffer_size, &self.limits),
+        let format = self.sample_format.first().unwrap_or(&SampleFormat::Uint);
+        Self::sample_buffer(buffer_size, max_sample_bits, format, &self.limits)
+    }
+
+    /// Allocates a buffer for `size` samples of the given bits and format.
+    fn sample_buffer(
+        size: usize,
+        bits: u8,
+        format: &SampleFormat,
+        limits: &Limits,
+    ) -> TiffResult<DecodingResult> {
+        match format {
+            SampleFormat::Uint => match bits {
+                n if n <= 8 => DecodingResult::new_u8(size, limits),
+                n if n <= 16 => DecodingResult::new_u16(size, limits),
+                n if n <= 32 => DecodingResult::new_u32(size, limits),
+                n if n <= 64 => DecodingResult::new_u64(size, limits),
                 n => Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::UnsupportedBitsPerChannel(n),
                 )),
             },
-            SampleFormat::IEEEFP => match max_sample_bits {
-                32 => DecodingResult::new_f32(buffer_size, &self.limits),
-                64 => DecodingResult::new_f64(buffer_size, &self.limits),
+            SampleFormat::IEEEFP => match bits {
+                32 => DecodingResult::new_f32(size, limits),
+                64 => DecodingResult::new_f64(size, limits),
                 n => Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::UnsupportedBitsPerChannel(n),
                 )),
             },
-            SampleFormat::Int => match max_sample_bits {
-                n if n <= 8 => DecodingResult::new_i8(buffer_size, &self.limits),
-                n if n <= 16 => DecodingResult::new_i16(buffer_size, &self.limits),
-                n if n <= 32 => DecodingResult::new_i32(buffer_size, &self.limits),
-                n if n <= 64 => DecodingResult::new_i64(buffer_size, &self.limits),
+            SampleFormat::Int => match bits {
+                n if n <= 8 => DecodingResult::new_i8(size, limits),
+                n if n <= 16 => DecodingResult::new_i16(size, limits),
+                n if n <= 32 => DecodingResult::new_i32(size, limits),
+                n if n <= 64 => DecodingResult::new_i64(size, limits),
                 n => Err(TiffError::UnsupportedError(
                     TiffUnsupportedError::UnsupportedBitsPerChannel(n),
                 )),
@@ -1619,84 +3631,114 @@ impl<R: Read + Seek> Decoder<R> {
     }
 
     /// Read a single strip from the image and return it as a Vector
+    ///
+    /// The strips of planar images are returned plane by plane.
     pub fn read_strip(&mut self) -> TiffResult<DecodingResult> {
         self.check_chunk_type(ChunkType::Strip)?;
         let index = self.strip_decoder.as_ref().unwrap().strip_index;
 
         let rows_per_strip =
             usize::try_from(self.get_tag_u32(Tag::RowsPerStrip).unwrap_or(self.height))?;
+        let plane_index = index % usize::try_from(self.chunks_per_plane()?)?.max(1);
 
         let strip_height = cmp::min(
             rows_per_strip,
-            usize::try_from(self.height)? - index * rows_per_strip,
+            usize::try_from(self.height)? - plane_index * rows_per_strip,
         );
 
-        let mut result = self.result_buffer(usize::try_from(self.width)?, strip_height)?;
+        let mut result = self.result_buffer(
+            usize::try_from(self.width)?,
+            strip_height,
+            self.chunk_samples(),
+        )?;
         self.read_strip_to_buffer(result.as_buffer(0))?;
-
         Ok(result)
     }
 
     /// Read a single tile from the image and return it as a Vector
+    ///
+    /// The tiles of planar images are returned plane by plane.
     pub fn read_tile(&mut self) -> TiffResult<DecodingResult> {
         self.check_chunk_type(ChunkType::Tile)?;
 
-        let tile = self.tile_decoder.as_ref().map_or(0, |d| d.current_tile);
-
-        let tile_attrs = self.tile_attributes.as_ref().unwrap();
-        let (padding_right, padding_down) = tile_attrs.get_padding(tile);
-
-        let tile_width = tile_attrs.tile_width - padding_right;
-        let tile_length = tile_attrs.tile_length - padding_down;
-
-        let mut result = self.result_buffer(tile_width, tile_length)?;
-
-        self.read_tile_to_buffer(&mut result.as_buffer(0), tile, tile_width)?;
+        let tile = self.tile_decoder.as_ref().unwrap().current_tile;
+        let result = self.read_stored_chunk(u32::try_from(tile)?)?;
 
         self.tile_decoder.as_mut().unwrap().current_tile += 1;
 
         Ok(result)
     }
 
+    /// Decodes the tiles into a buffer with the planar configuration of the file
     fn read_tiled_image(&mut self) -> TiffResult<DecodingResult> {
         let width = usize::try_from(self.width)?;
-        let mut result = self.result_buffer(width, usize::try_from(self.height)?)?;
-
-        let tile_attrs = self.tile_attributes.as_ref().unwrap();
-        let tiles_across = tile_attrs.tiles_across;
-        let tiles_down = tile_attrs.tiles_down;
+        let height = usize::try_from(self.height)?;
+        // The planes of planar images are stored one after the other.
+        let rows = height
+            .checked_mul(self.planes())
+            .ok_or(TiffError::LimitsExceeded)?;
+        let mut result = self.result_buffer(width, rows, self.chunk_samples())?;
 
-        for tile in 0..(tiles_across * tiles_down) {
-            let buffer_offset = self.tile_attributes.as_ref().unwrap().get_offset(tile);
+        let chunk_samples = self.chunk_samples();
+        let plane_len = self
+            .buffer_len(width, height, chunk_samples)
+            .ok_or(TiffError::LimitsExceeded)?;
+        let tiles_per_plane = self.tile_attributes.as_ref().unwrap().tiles_per_plane();
+
+        for tile in 0..tiles_per_plane * self.planes() {
+            let plane = tile / tiles_per_plane;
+            let tile_offset = match self.packed_output_bits() {
+                // Tiles are a multiple of 16 pixels wide, their rows start at a byte boundary.
+                Some(bits) => {
+                    let (x, y) = self.chunk_origin(u32::try_from(tile)?)?;
+                    let line_bytes = packed_row_bytes(width * chunk_samples, bits);
+                    usize::try_from(y)? * line_bytes
+                        + packed_row_bytes(usize::try_from(x)? * chunk_samples, bits)
+                }
+                None => self.tile_attributes.as_ref().unwrap().get_offset(tile),
+            };
+            let buffer_offset = plane * plane_len + tile_offset;
             self.read_tile_to_buffer(&mut result.as_buffer(buffer_offset), tile, width)?;
         }
 
         Ok(result)
     }
 
+    /// Decodes the strips into a buffer with the planar configuration of the file
     fn read_stripped_image(&mut self) -> TiffResult<DecodingResult> {
+        let width = usize::try_from(self.width)?;
+        let height = usize::try_from(self.height)?;
         let rows_per_strip =
             usize::try_from(self.get_tag_u32(Tag::RowsPerStrip).unwrap_or(self.height))?;
 
-        let samples_per_strip = match usize::try_from(self.width)?
-            .checked_mul(rows_per_strip)
-            .and_then(|x| x.checked_mul(self.bits_per_sample.len()))
-        {
+        let strip_len = match self.buffer_len(width, rows_per_strip, self.chunk_samples()) {
             Some(s) => s,
             None => return Err(TiffError::LimitsExceeded),
         };
 
-        let mut result =
-            self.result_buffer(usize::try_from(self.width)?, usize::try_from(self.height)?)?;
+        // The planes of planar images are stored one after the other.
+        let rows = height
+            .checked_mul(self.planes())
+            .ok_or(TiffError::LimitsExceeded)?;
+        let mut result = self.result_buffer(width, rows, self.chunk_samples())?;
+
+        let plane_len = self
+            .buffer_len(width, height, self.chunk_samples())
+            .ok_or(TiffError::LimitsExceeded)?;
+        let strips_per_plane = usize::try_from(self.chunks_per_plane()?)?;
 
         for i in 0..usize::try_from(self.strip_count()?)? {
-            let r = result.as_buffer(samples_per_strip * i);
-            self.read_strip_to_buffer(r)?;
+            let plane = i / strips_per_plane;
+            let offset = plane * plane_len + strip_len * (i % strips_per_plane);
+            self.read_strip_to_buffer(result.as_buffer(offset))?;
         }
         Ok(result)
     }
 
     /// Decodes the entire image and return it as a Vector
+    ///
+    /// The samples are arranged as configured with
+    /// [`with_planar_output`][Decoder::with_planar_output].
     pub fn read_image(&mut self) -> TiffResult<DecodingResult> {
         let result = match (self.chunk_type, self.compression_method) {
             (_, CompressionMethod::ModernJPEG) => self.read_jpeg()?,
@@ -1704,6 +3746,545 @@ impl<R: Read + Seek> Decoder<R> {
             (ChunkType::Tile, _) => self.read_tiled_image()?,
         };
 
+        // Packed samples are returned as stored unless they have to be converted.
+        let result = match (self.packed_output_bits(), result) {
+            (Some(bits), DecodingResult::U8(packed)) if self.converts_samples() => {
+                let width = usize::try_from(self.width)?;
+                let rows = usize::try_from(self.height)? * self.planes();
+                self.unpack_rows(&packed, rows, width * self.chunk_samples(), bits)?
+            }
+            (Some(_), result) => return Ok(result),
+            (None, result) => result,
+        };
+
+        let samples = self.bits_per_sample.len();
+        let result = match (self.planar_config, self.planar_output) {
+            _ if samples == 1 => result,
+            (PlanarConfiguration::Planar, PlanarConfiguration::Chunky) => {
+                result.transposed(samples, &self.limits)?
+            }
+            (PlanarConfiguration::Chunky, PlanarConfiguration::Planar) => {
+                let pixels = usize::try_from(self.width)? * usize::try_from(self.height)?;
+                result.transposed(pixels, &self.limits)?
+            }
+            _ => result,
+        };
+
+        if !self.converts_colors() {
+            let mut result = result;
+            if self.unassociated_alpha_output && self.alpha() == Some(ExtraSamples::AssociatedAlpha)
+            {
+                self.unassociate_alpha(&mut result);
+            }
+
+            // The samples are packed after rotating, the rows may have changed.
+            let result = self.oriented_result(result)?;
+            let width = if self.oriented_output {
+                self.oriented_dimensions().0
+            } else {
+                self.width
+            };
+            let row_samples = usize::try_from(width)? * self.output_samples();
+            return self.packed_result(result, row_samples);
+        }
+
+        let width = usize::try_from(self.width)?;
+        let height = usize::try_from(self.height)?;
+        let result = self.color_result(result, width, height)?;
+        self.oriented_result(result)
+    }
+
+    /// Whether the colors of the samples are converted as configured, see `color_result`
+    fn converts_colors(&self) -> bool {
+        (self.color_map.is_some() && self.palette_output != PaletteOutput::Indices)
+            || self.ycbcr_subsampling.is_some()
+            || (self.is_lab() && self.lab_output != LabOutput::Samples)
+            || (self.log_luv_output == LogLuvOutput::RGB
+                && self.sgilog_encoding().map(sgilog::Encoding::samples) == Some(3))
+    }
+
+    /// Converts the colors of `width * height` unpacked pixels, arranged as configured with
+    /// [`with_planar_output`][Decoder::with_planar_output].
+    fn color_result(
+        &mut self,
+        result: DecodingResult,
+        width: usize,
+        height: usize,
+    ) -> TiffResult<DecodingResult> {
+        match self.color_map {
+            Some(ref color_map) if self.palette_output != PaletteOutput::Indices => {
+                let result = self.expand_palette(result, color_map)?;
+                match self.planar_output {
+                    PlanarConfiguration::Planar => result.transposed(width * height, &self.limits),
+                    _ => Ok(result),
+                }
+            }
+            _ if self.ycbcr_subsampling.is_some() => self.ycbcr_result(result, width, height),
+            _ if self.is_lab() && self.lab_output != LabOutput::Samples => self.lab_result(result),
+            _ => Ok(self.log_luv_result(result)),
+        }
+    }
+
+    /// Whether [`read_image`][Decoder::read_image] converts or rearranges the stored samples
+    fn converts_samples(&self) -> bool {
+        let samples = self.bits_per_sample.len();
+        (samples > 1 && self.planar_config != self.planar_output)
+            || (self.color_map.is_some() && self.palette_output != PaletteOutput::Indices)
+            || (self.is_lab() && self.lab_output != LabOutput::Samples)
+            || (self.unassociated_alpha_output
+                && self.alpha() == Some(ExtraSamples::AssociatedAlpha))
+            || (self.oriented_output && self.orientation != Orientation::TopLeft)
+    }
+
+    /// Rotates or flips the pixels of a result to the order they are displayed in if requested
+    /// with [`with_oriented_output`][Decoder::with_oriented_output].
+    fn oriented_result(&self, result: DecodingResult) -> TiffResult<DecodingResult> {
+        if !self.oriented_output || self.orientation == Orientation::TopLeft {
+            return Ok(result);
+        }
+
+        let width = usize::try_from(self.width)?;
+        let height = usize::try_from(self.height)?;
+        let samples = match result.len().checked_div(width * height) {
+            Some(samples) => samples,
+            None => return Ok(result),
+        };
+        let (planes, samples) = match self.ycbcr_subsampling {
+            Some((h, v)) if self.ycbcr_output == YCbCrOutput::Planes => {
+                let (h, v) = (usize::from(h), usize::from(v));
+                let chroma = (
+                    width.checked_sub(1).map_or(0, |last| last / h + 1),
+                    height.checked_sub(1).map_or(0, |last| last / v + 1),
+                );
+                (vec![(width, height), chroma, chroma], 1)
+            }
+            _ if self.planar_output == PlanarConfiguration::Planar => {
+                (vec![(width, height); samples], 1)
+            }
+            _ => (vec![(width, height)], samples),
+        };
+
+        Ok(result.oriented(&planes, samples, self.orientation))
+    }
+
+    /// Decodes the entire image into one result per channel.
+    ///
+    /// Each result holds one sample of every pixel, in the type declared for that sample. The
+    /// samples are converted as by [`read_image`][Decoder::read_image] without packing, so for
+    /// example palette images with RGB output have three channels. With `YCbCrOutput::Planes` the
+    /// chroma channels are subsampled.
+    ///
+    /// Unlike `read_image` this also decodes images whose samples differ in bit depth or sample
+    /// format, see [`ColorType::Mixed`]. Their samples are returned as stored in the file.
+    pub fn read_image_channels(&mut self) -> TiffResult<Vec<DecodingResult>> {
+        if self.is_mixed() {
+            return self.read_mixed_channels();
+        }
+
+        let (planar_output, packed_output) = (self.planar_output, self.packed_output);
+        self.planar_output = PlanarConfiguration::Planar;
+        self.packed_output = false;
+        let result = self.read_image();
+        self.planar_output = planar_output;
+        self.packed_output = packed_output;
+        let result = result?;
+
+        let pixels = usize::try_from(self.width)? * usize::try_from(self.height)?;
+        let lengths = match self.ycbcr_subsampling {
+            Some(_) if self.ycbcr_output == YCbCrOutput::Planes => {
+                let chroma = (result.len() - pixels) / 2;
+                vec![pixels, chroma, chroma]
+            }
+            _ => {
+                let channels = result.len().checked_div(pixels);
+                vec![pixels; channels.unwrap_or(self.bits_per_sample.len())]
+            }
+        };
+        Ok(result.split(&lengths))
+    }
+
+    /// Decodes the channels of an image whose samples differ in bit depth or sample format.
+    fn read_mixed_channels(&mut self) -> TiffResult<Vec<DecodingResult>> {
+        let predictor = self.find_tag_unsigned(Tag::Predictor)?.unwrap_or(1);
+        match Predictor::from_u16(predictor) {
+            Some(Predictor::None) => {}
+            Some(Predictor::Horizontal) => {
+                return Err(TiffUnsupportedError::HorizontalPredictor(self.colortype()?).into())
+            }
+            Some(Predictor::FloatingPoint) => {
+                return Err(TiffUnsupportedError::FloatingPointPredictor(self.colortype()?).into())
+            }
+            _ => return Err(TiffFormatError::UnknownPredictor(predictor).into()),
+        }
+
+        let width = usize::try_from(self.width)?;
+        let pixels = width
+            .checked_mul(usize::try_from(self.height)?)
+            .ok_or(TiffError::LimitsExceeded)?;
+
+        // All channels together must not exceed the limit of a single result.
+        let mut limits = self.limits.clone();
+        let mut channels = Vec::with_capacity(self.bits_per_sample.len());
+        for (sample, &bits) in self.bits_per_sample.iter().enumerate() {
+            let format = self
+                .sample_format
+                .get(sample)
+                .ok_or(TiffFormatError::InconsistentSizesEncountered)?;
+            if bits == 0 {
+                return Err(TiffUnsupportedError::UnsupportedBitsPerChannel(bits).into());
+            }
+            let mut channel = Self::sample_buffer(pixels, bits, format, &limits)?;
+            limits.decoding_buffer_size -= pixels * channel.as_buffer(0).byte_len();
+            channels.push(channel);
+        }
+
+        for chunk in 0..self.chunk_count()? {
+            let (x, y) = self.chunk_origin(chunk)?;
+            let (x, y) = (usize::try_from(x)?, usize::try_from(y)?);
+            let (data_width, data_height) = self.chunk_data_dimensions(chunk)?;
+            let (data_width, data_height) =
+                (usize::try_from(data_width)?, usize::try_from(data_height)?);
+            let chunk_width = match self.chunk_type {
+                ChunkType::Strip => width,
+                ChunkType::Tile => self.tile_attributes.as_ref().unwrap().tile_width,
+            };
+            let samples = match self.planar_config {
+                PlanarConfiguration::Planar => {
+                    let plane = usize::try_from(self.chunk_plane(chunk)?)?;
+                    plane..plane + 1
+                }
+                _ => 0..self.bits_per_sample.len(),
+            };
+
+            // The samples of each pixel are packed without padding, each row starts at a byte.
+            let pixel_bits: usize = self.bits_per_sample[samples.clone()]
+                .iter()
+                .map(|&bits| usize::from(bits))
+                .sum();
+            let row_bytes = (chunk_width * pixel_bits)
+                .checked_sub(1)
+                .map_or(0, |last| last / 8 + 1);
+            let len = row_bytes * data_height;
+            if len > self.limits.intermediate_buffer_size {
+                return Err(TiffError::LimitsExceeded);
+            }
+
+            let index = usize::try_from(chunk)?;
+            let (offsets, byte_counts) = match self.chunk_type {
+                ChunkType::Strip => {
+                    let strips = self.strip_decoder.as_ref().unwrap();
+                    (&strips.strip_offsets, &strips.strip_bytes)
+                }
+                ChunkType::Tile => {
+                    let tiles = self.tile_decoder.as_ref().unwrap();
+                    (&tiles.tile_offsets, &tiles.tile_bytes)
+                }
+            };
+            let (offset, length) = match offsets.get(index).zip(byte_counts.get(index)) {
+                Some((&offset, &length)) => (offset, length),
+                None => return Err(TiffFormatError::InconsistentSizesEncountered.into()),
+            };
+
+            let fax_options = self.fax_options(chunk_width)?;
+            self.check_compression()?;
+            self.goto_offset_u64(offset)?;
+            let reader = Self::create_reader(
+                &mut self.reader,
+                self.compression_method,
+                length,
+                len,
+                1,
+                self.limits.intermediate_buffer_size,
+                fax_options,
+            )?;
+
+            // If less than the expected amount of bytes can be read, the remaining samples are 0.
+            let mut data = Vec::with_capacity(len);
+            reader.take(u64::try_from(len)?).read_to_end(&mut data)?;
+            data.resize(len, 0);
+
+            for row in 0..data_height {
+                let mut start = row * row_bytes * 8;
+                for column in 0..data_width {
+                    let pixel = (y + row) * width + x + column;
+                    for sample in samples.clone() {
+                        let bits = self.bits_per_sample[sample];
+                        let value = read_sample(&data, start, bits, self.byte_order);
+                        channels[sample].set_sample(pixel, value, bits);
+                        start += usize::from(bits);
+                    }
+                }
+            }
+        }
+
+        if self.oriented_output {
+            let plane = [(width, usize::try_from(self.height)?)];
+            channels = channels
+                .into_iter()
+                .map(|channel| channel.oriented(&plane, 1, self.orientation))
+                .collect();
+        }
+
+        Ok(channels)
+    }
+
+    /// Divides the color samples of an image with associated alpha by their alpha.
+    fn unassociate_alpha(&self, result: &mut DecodingResult) {
+        let samples = self.bits_per_sample.len();
+        let alpha = match samples.checked_sub(self.extra_samples.len()) {
+            Some(alpha) if alpha < samples => alpha,
+            _ => return,
+        };
+        let max = match self.bits_per_sample[alpha] {
+            bits @ 1..=64 => u64::MAX >> (64 - bits),
+            _ => return,
+        };
+
+        let planar = self.planar_output == PlanarConfiguration::Planar;
+        let pixels = match result {
+            DecodingResult::U8(buf) => buf.len(),
+            DecodingResult::U16(buf) => buf.len(),
+            DecodingResult::U32(buf) => buf.len(),
+            DecodingResult::U64(buf) => buf.len(),
+            DecodingResult::F32(buf) => buf.len(),
+            DecodingResult::F64(buf) => buf.len(),
+            _ => return,
+        } / samples;
+        let index = |pixel: usize, sample: usize| {
+            if planar {
+                sample * pixels + pixel
+            } else {
+                pixel * samples + sample
+            }
+        };
+
+        match result {
+            DecodingResult::U8(buf) => unassociate_integers(buf, max, alpha, pixels, index),
+            DecodingResult::U16(buf) => unassociate_integers(buf, max, alpha, pixels, index),
+            DecodingResult::U32(buf) => unassociate_integers(buf, max, alpha, pixels, index),
+            DecodingResult::U64(buf) => unassociate_integers(buf, max, alpha, pixels, index),
+            DecodingResult::F32(buf) => {
+                for pixel in 0..pixels {
+                    let alpha_value = buf[index(pixel, alpha)];
+                    if alpha_value != 0.0 {
+                        for sample in 0..alpha {
+                            buf[index(pixel, sample)] /= alpha_value;
+                        }
+                    }
+                }
+            }
+            DecodingResult::F64(buf) => {
+                for pixel in 0..pixels {
+                    let alpha_value = buf[index(pixel, alpha)];
+                    if alpha_value != 0.0 {
+                        for sample in 0..alpha {
+                            buf[index(pixel, sample)] /= alpha_value;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Converts the samples of a YCbCr image as configured with
+    /// [`with_ycbcr_output`][Decoder::with_ycbcr_output].
+    fn ycbcr_result(
+        &mut self,
+        result: DecodingResult,
+        width: usize,
+        height: usize,
+    ) -> TiffResult<DecodingResult> {
+        let mut buf = match result {
+            DecodingResult::U8(buf) => buf,
+            _ if self.ycbcr_output == YCbCrOutput::YCbCr => return Ok(result),
+            _ => return Err(TiffUnsupportedError::UnsupportedColorType(self.colortype()?).into()),
+        };
+
+        let pixels = width * height;
+        let planar = self.planar_output == PlanarConfiguration::Planar;
+        let index = |pixel: usize, sample: usize| {
+            if planar {
+                sample * pixels + pixel
+            } else {
+                pixel * 3 + sample
+            }
+        };
+
+        match self.ycbcr_output {
+            YCbCrOutput::RGB => {
+                let conversion = YCbCrConversion::new(
+                    self.find_tag_rationals(Tag::YCbCrCoefficients)?,
+                    self.find_tag_rationals(Tag::ReferenceBlackWhite)?,
+                )?;
+                for pixel in 0..pixels {
+                    let ycbcr = [
+                        buf[index(pixel, 0)],
+                        buf[index(pixel, 1)],
+                        buf[index(pixel, 2)],
+                    ];
+                    for (sample, value) in conversion.to_rgb(ycbcr).iter().enumerate() {
+                        buf[index(pixel, sample)] = *value;
+                    }
+                }
+                Ok(DecodingResult::U8(buf))
+            }
+            YCbCrOutput::YCbCr => Ok(DecodingResult::U8(buf)),
+            YCbCrOutput::Planes => {
+                let (h, v) = self.ycbcr_subsampling.unwrap();
+                let (h, v) = (usize::from(h), usize::from(v));
+                let chroma_width = width.checked_sub(1).map_or(0, |last| last / h + 1);
+                let chroma_height = height.checked_sub(1).map_or(0, |last| last / v + 1);
+
+                let mut planes = Vec::with_capacity(pixels + 2 * chroma_width * chroma_height);
+                planes.extend((0..pixels).map(|pixel| buf[index(pixel, 0)]));
+                for sample in 1..3 {
+                    for y in 0..chroma_height {
+                        for x in 0..chroma_width {
+                            planes.push(buf[index(y * v * width + x * h, sample)]);
+                        }
+                    }
+                }
+                Ok(DecodingResult::U8(planes))
+            }
+        }
+    }
+
+    /// Converts the XYZ values of a LogLuv image to RGB.
+    fn log_luv_result(&self, result: DecodingResult) -> DecodingResult {
+        let mut buf = match result {
+            DecodingResult::F32(buf) => buf,
+            result => return result,
+        };
+
+        let pixels = buf.len() / 3;
+        let planar = self.planar_output == PlanarConfiguration::Planar;
+        let index = |pixel: usize, sample: usize| {
+            if planar {
+                sample * pixels + pixel
+            } else {
+                pixel * 3 + sample
+            }
+        };
+        for pixel in 0..pixels {
+            let xyz = [
+                buf[index(pixel, 0)],
+                buf[index(pixel, 1)],
+                buf[index(pixel, 2)],
+            ];
+            for (sample, value) in sgilog::xyz_to_rgb(xyz).iter().enumerate() {
+                buf[index(pixel, sample)] = *value;
+            }
+        }
+        DecodingResult::F32(buf)
+    }
+
+    fn is_lab(&self) -> bool {
+        matches!(
+            self.photometric_interpretation,
+            PhotometricInterpretation::CIELab | PhotometricInterpretation::ICCLab
+        )
+    }
+
+    /// Converts the samples of a CIELab or ICCLab image as configured with
+    /// [`with_lab_output`][Decoder::with_lab_output].
+    fn lab_result(&mut self, result: DecodingResult) -> TiffResult<DecodingResult> {
+        let bits = self.bits_per_sample[0];
+        let samples: Vec<u16> = match result {
+            DecodingResult::U8(buf) if bits == 8 => buf.into_iter().map(u16::from).collect(),
+            DecodingResult::U16(buf) if bits == 16 => buf,
+            _ => return Err(TiffUnsupportedError::UnsupportedColorType(self.colortype()?).into()),
+        };
+
+        let pixels = samples.len() / 3;
+        let planar = self.planar_output == PlanarConfiguration::Planar;
+        let index = |pixel: usize, channel: usize| {
+            if planar {
+                channel * pixels + pixel
+            } else {
+                pixel * 3 + channel
+            }
+        };
+        let signed = self.photometric_interpretation == PhotometricInterpretation::CIELab;
+        let lab = |pixel: usize| {
+            let mut lab = [0.0; 3];
+            for (channel, value) in lab.iter_mut().enumerate() {
+                *value = lab::lab_value(samples[index(pixel, channel)], bits, channel, signed);
+            }
+            lab
+        };
+
+        match self.lab_output {
+            LabOutput::RGB8 => {
+                let conversion = LabConversion::new(match self.photometric_interpretation {
+                    // ICCLab values are always relative to D50.
+                    PhotometricInterpretation::ICCLab => None,
+                    _ => self.find_tag_rationals(Tag::WhitePoint)?,
+                })?;
+                let mut result = DecodingResult::new_u8(samples.len(), &self.limits)?;
+                if let DecodingResult::U8(ref mut buf) = result {
+                    for pixel in 0..pixels {
+                        for (channel, value) in conversion.to_rgb(lab(pixel)).iter().enumerate() {
+                            buf[index(pixel, channel)] = *value;
+                        }
+                    }
+                }
+                Ok(result)
+            }
+            _ => {
+                let mut result = DecodingResult::new_f32(samples.len(), &self.limits)?;
+                if let DecodingResult::F32(ref mut buf) = result {
+                    for pixel in 0..pixels {
+                        for (channel, value) in lab(pixel).iter().enumerate() {
+                            buf[index(pixel, channel)] = *value;
+                        }
+                    }
+                }
+                Ok(result)
+            }
+        }
+    }
+
+    /// Replaces the indices of a palette image by their colors as configured with
+    /// [`with_palette_output`][Decoder::with_palette_output].
+    fn expand_palette(
+        &self,
+        indices: DecodingResult,
+        color_map: &[u16],
+    ) -> TiffResult<DecodingResult> {
+        let indices = match indices {
+            DecodingResult::U8(buf) => buf.into_iter().map(u16::from).collect(),
+            DecodingResult::U16(buf) => buf,
+            _ => return Err(TiffUnsupportedError::UnsupportedDataType.into()),
+        };
+
+        let entries = color_map.len() / 3;
+        let colors = indices.iter().flat_map(|&index| {
+            (0..3).map(move |channel| color_map[channel * entries + usize::from(index)])
+        });
+
+        let size = indices
+            .len()
+            .checked_mul(3)
+            .ok_or(TiffError::LimitsExceeded)?;
+        let mut result = match self.palette_output {
+            PaletteOutput::RGB16 => DecodingResult::new_u16(size, &self.limits)?,
+            _ => DecodingResult::new_u8(size, &self.limits)?,
+        };
+        match result {
+            DecodingResult::U8(ref mut buf) => {
+                for (sample, color) in buf.iter_mut().zip(colors) {
+                    *sample = (color >> 8) as u8;
+                }
+            }
+            DecodingResult::U16(ref mut buf) => {
+                for (sample, color) in buf.iter_mut().zip(colors) {
+                    *sample = color;
+                }
+            }
+            _ => unreachable!(),
+        }
+
         Ok(result)
     }
 }
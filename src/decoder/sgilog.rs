@@ -0,0 +1,370 @@
+//! Decompression of the SGILog and SGILog24 compressed LogL and LogLuv images
+//!
+//! The encodings are described in "The LogLuv Encoding for Full Gamut, High Dynamic Range Images"
+//! by Greg Ward Larson, the compression follows the implementation of libtiff.
+use std::io::{self, Read};
+
+/// The encoding of the pixels of an SGILog or SGILog24 compressed image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    /// The logarithm of the luminance in 16 bits.
+    LogL16,
+    /// The logarithm of the luminance in 16 bits and the chromaticity u and v in 8 bits each.
+    LogLuv32,
+    /// The logarithm of the luminance in 10 bits and an index of the chromaticity in 14 bits.
+    LogLuv24,
+}
+
+impl Encoding {
+    /// The number of samples of the decoded pixels.
+    pub fn samples(self) -> usize {
+        match self {
+            Encoding::LogL16 => 1,
+            Encoding::LogLuv32 | Encoding::LogLuv24 => 3,
+        }
+    }
+}
+
+/// The scale of the u and v chromaticities of LogLuv32 pixels.
+const UV_SCALE: f64 = 410.0;
+
+/// The size of the squares of the chromaticity grid of LogLuv24 pixels.
+const UV_SQUARE: f32 = 0.0035;
+
+/// The v chromaticity of the first row of the grid.
+const UV_V_START: f32 = 0.016_94;
+
+/// The rows of the chromaticity grid of LogLuv24 pixels, from `uvcode.h` of libtiff.
+///
+/// Each row holds the u chromaticity of its first square, the number of squares and the number of
+/// squares in all previous rows.
+const UV_ROWS: [(f32, u16, u16); 163] = [
+    (0.247663, 4, 0),
+    (0.243779, 6, 4),
+    (0.241684, 7, 10),
+    (0.237874, 9, 17),
+    (0.235906, 10, 26),
+    (0.232153, 12, 36),
+    (0.228352, 14, 48),
+    (0.226259, 15, 62),
+    (0.222371, 17, 77),
+    (0.220410, 18, 94),
+    (0.214710, 21, 112),
+    (0.212714, 22, 133),
+    (0.210721, 23, 155),
+    (0.204976, 26, 178),
+    (0.202986, 27, 204),
+    (0.199245, 29, 231),
+    (0.195525, 31, 260),
+    (0.193560, 32, 291),
+    (0.189878, 34, 323),
+    (0.186216, 36, 357),
+    (0.186216, 36, 393),
+    (0.182592, 38, 429),
+    (0.179003, 40, 467),
+    (0.175466, 42, 507),
+    (0.172001, 44, 549),
+    (0.172001, 44, 593),
+    (0.168612, 46, 637),
+    (0.168612, 46, 683),
+    (0.163575, 49, 729),
+    (0.158642, 52, 778),
+    (0.158642, 52, 830),
+    (0.158642, 52, 882),
+    (0.153815, 55, 934),
+    (0.153815, 55, 989),
+    (0.149097, 58, 1044),
+    (0.149097, 58, 1102),
+    (0.142746, 62, 1160),
+    (0.142746, 62, 1222),
+    (0.142746, 62, 1284),
+    (0.138270, 65, 1346),
+    (0.138270, 65, 1411),
+    (0.138270, 65, 1476),
+    (0.132166, 69, 1541),
+    (0.132166, 69, 1610),
+    (0.126204, 73, 1679),
+    (0.126204, 73, 1752),
+    (0.126204, 73, 1825),
+    (0.120381, 77, 1898),
+    (0.120381, 77, 1975),
+    (0.120381, 77, 2052),
+    (0.120381, 77, 2129),
+    (0.112962, 82, 2206),
+    (0.112962, 82, 2288),
+    (0.112962, 82, 2370),
+    (0.107450, 86, 2452),
+    (0.107450, 86, 2538),
+    (0.107450, 86, 2624),
+    (0.107450, 86, 2710),
+    (0.100343, 91, 2796),
+    (0.100343, 91, 2887),
+    (0.100343, 91, 2978),
+    (0.095126, 95, 3069),
+    (0.095126, 95, 3164),
+    (0.095126, 95, 3259),
+    (0.095126, 95, 3354),
+    (0.088276, 100, 3449),
+    (0.088276, 100, 3549),
+    (0.088276, 100, 3649),
+    (0.088276, 100, 3749),
+    (0.081523, 105, 3849),
+    (0.081523, 105, 3954),
+    (0.081523, 105, 4059),
+    (0.081523, 105, 4164),
+    (0.074861, 110, 4269),
+    (0.074861, 110, 4379),
+    (0.074861, 110, 4489),
+    (0.074861, 110, 4599),
+    (0.068290, 115, 4709),
+    (0.068290, 115, 4824),
+    (0.068290, 115, 4939),
+    (0.068290, 115, 5054),
+    (0.063573, 119, 5169),
+    (0.063573, 119, 5288),
+    (0.063573, 119, 5407),
+    (0.063573, 119, 5526),
+    (0.057219, 124, 5645),
+    (0.057219, 124, 5769),
+    (0.057219, 124, 5893),
+    (0.057219, 124, 6017),
+    (0.050985, 129, 6141),
+    (0.050985, 129, 6270),
+    (0.050985, 129, 6399),
+    (0.050985, 129, 6528),
+    (0.050985, 129, 6657),
+    (0.044859, 134, 6786),
+    (0.044859, 134, 6920),
+    (0.044859, 134, 7054),
+    (0.044859, 134, 7188),
+    (0.040571, 138, 7322),
+    (0.040571, 138, 7460),
+    (0.040571, 138, 7598),
+    (0.040571, 138, 7736),
+    (0.036339, 142, 7874),
+    (0.036339, 142, 8016),
+    (0.036339, 142, 8158),
+    (0.036339, 142, 8300),
+    (0.032139, 146, 8442),
+    (0.032139, 146, 8588),
+    (0.032139, 146, 8734),
+    (0.032139, 146, 8880),
+    (0.027947, 150, 9026),
+    (0.027947, 150, 9176),
+    (0.027947, 150, 9326),
+    (0.023739, 154, 9476),
+    (0.023739, 154, 9630),
+    (0.023739, 154, 9784),
+    (0.023739, 154, 9938),
+    (0.019504, 158, 10092),
+    (0.019504, 158, 10250),
+    (0.019504, 158, 10408),
+    (0.016976, 161, 10566),
+    (0.016976, 161, 10727),
+    (0.016976, 161, 10888),
+    (0.016976, 161, 11049),
+    (0.012639, 165, 11210),
+    (0.012639, 165, 11375),
+    (0.012639, 165, 11540),
+    (0.009991, 168, 11705),
+    (0.009991, 168, 11873),
+    (0.009991, 168, 12041),
+    (0.009016, 170, 12209),
+    (0.009016, 170, 12379),
+    (0.009016, 170, 12549),
+    (0.006217, 173, 12719),
+    (0.006217, 173, 12892),
+    (0.005097, 175, 13065),
+    (0.005097, 175, 13240),
+    (0.005097, 175, 13415),
+    (0.003909, 177, 13590),
+    (0.003909, 177, 13767),
+    (0.002340, 177, 13944),
+    (0.002389, 170, 14121),
+    (0.001068, 164, 14291),
+    (0.001653, 157, 14455),
+    (0.000717, 150, 14612),
+    (0.001614, 143, 14762),
+    (0.000270, 136, 14905),
+    (0.000484, 129, 15041),
+    (0.001103, 123, 15170),
+    (0.001242, 115, 15293),
+    (0.001188, 109, 15408),
+    (0.001011, 103, 15517),
+    (0.000709, 97, 15620),
+    (0.000301, 89, 15717),
+    (0.002416, 82, 15806),
+    (0.003251, 76, 15888),
+    (0.003246, 69, 15964),
+    (0.004141, 62, 16033),
+    (0.005963, 55, 16095),
+    (0.008839, 47, 16150),
+    (0.010490, 40, 16197),
+    (0.016994, 31, 16237),
+    (0.023659, 21, 16268),
+];
+
+/// Reads the SGILog or SGILog24 compressed rows of a chunk.
+///
+/// Each row of the chunk is `chunk_width` pixels wide and compressed on its own. The linear XYZ
+/// values of LogLuv pixels or the luminance of LogL pixels of the first `width` pixels of the
+/// first `rows` rows are written to `buf`, with `stride` samples between the starts of the rows.
+/// Missing data is decoded as black.
+pub(crate) fn read_rows(
+    mut reader: impl Read,
+    buf: &mut [f32],
+    encoding: Encoding,
+    chunk_width: usize,
+    width: usize,
+    rows: usize,
+    stride: usize,
+) -> io::Result<()> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let mut data = &data[..];
+
+    let samples = encoding.samples();
+    let mut pixels = vec![0u32; chunk_width];
+    for row in 0..rows {
+        for pixel in pixels.iter_mut() {
+            *pixel = 0;
+        }
+        match encoding {
+            Encoding::LogL16 => decode_runs(&mut data, &mut pixels, 2),
+            Encoding::LogLuv32 => decode_runs(&mut data, &mut pixels, 4),
+            Encoding::LogLuv24 => {
+                for (pixel, bytes) in pixels.iter_mut().zip(data.chunks_exact(3)) {
+                    *pixel =
+                        u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+                }
+                data = &data[(3 * chunk_width).min(data.len())..];
+            }
+        }
+
+        let row = &mut buf[row * stride..][..width * samples];
+        for (values, &pixel) in row.chunks_exact_mut(samples).zip(pixels.iter()) {
+            match encoding {
+                Encoding::LogL16 => values[0] = log_l16_to_y(pixel as u16) as f32,
+                Encoding::LogLuv32 => values.copy_from_slice(&log_luv32_to_xyz(pixel)),
+                Encoding::LogLuv24 => values.copy_from_slice(&log_luv24_to_xyz(pixel)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes the run length encoded bytes of a row of pixels, most significant byte first.
+///
+/// The bytes of each position within the pixels are encoded after another. A control byte of 128
+/// or more repeats the following byte `control - 126` times, other control bytes are followed by
+/// that many literal bytes.
+fn decode_runs(data: &mut &[u8], pixels: &mut [u32], bytes: usize) {
+    for shift in (0..bytes).rev().map(|byte| 8 * byte) {
+        let mut i = 0;
+        while i < pixels.len() {
+            let (control, rest) = match data.split_first() {
+                Some((&control, rest)) => (usize::from(control), rest),
+                None => return,
+            };
+            if control >= 128 {
+                let value = match rest.first() {
+                    Some(&value) => u32::from(value) << shift,
+                    None => return,
+                };
+                for pixel in pixels[i..].iter_mut().take(control - 126) {
+                    *pixel |= value;
+                }
+                i += control - 126;
+                *data = &rest[1..];
+            } else {
+                let literals = &rest[..control.min(rest.len())];
+                for (pixel, &value) in pixels[i..].iter_mut().zip(literals) {
+                    *pixel |= u32::from(value) << shift;
+                }
+                i += control;
+                *data = &rest[literals.len()..];
+            }
+        }
+    }
+}
+
+/// Converts the logarithm of the luminance of LogL16 and LogLuv32 pixels to the luminance.
+fn log_l16_to_y(pixel: u16) -> f64 {
+    let exponent = pixel & 0x7fff;
+    if exponent == 0 {
+        return 0.0;
+    }
+    let y = (std::f64::consts::LN_2 / 256.0 * (f64::from(exponent) + 0.5)
+        - std::f64::consts::LN_2 * 64.0)
+        .exp();
+    if pixel & 0x8000 == 0 {
+        y
+    } else {
+        -y
+    }
+}
+
+/// Converts the logarithm of the luminance of LogLuv24 pixels to the luminance.
+fn log_l10_to_y(exponent: u32) -> f64 {
+    if exponent == 0 {
+        return 0.0;
+    }
+    (std::f64::consts::LN_2 / 64.0 * (f64::from(exponent) + 0.5) - std::f64::consts::LN_2 * 12.0)
+        .exp()
+}
+
+fn log_luv32_to_xyz(pixel: u32) -> [f32; 3] {
+    let y = log_l16_to_y((pixel >> 16) as u16);
+    let u = (f64::from(pixel >> 8 & 0xff) + 0.5) / UV_SCALE;
+    let v = (f64::from(pixel & 0xff) + 0.5) / UV_SCALE;
+    uv_to_xyz(y, u, v)
+}
+
+fn log_luv24_to_xyz(pixel: u32) -> [f32; 3] {
+    let y = log_l10_to_y(pixel >> 14 & 0x3ff);
+    match uv_decode(pixel & 0x3fff) {
+        Some((u, v)) => uv_to_xyz(y, u, v),
+        None => [0.0; 3],
+    }
+}
+
+/// Looks up the chromaticity of the square of the grid with the given index.
+fn uv_decode(index: u32) -> Option<(f64, f64)> {
+    let (_, squares, cumulative) = UV_ROWS[UV_ROWS.len() - 1];
+    if index >= u32::from(cumulative) + u32::from(squares) {
+        return None;
+    }
+
+    // The last row starting at or before the index.
+    let row = UV_ROWS
+        .iter()
+        .rposition(|&(_, _, cumulative)| u32::from(cumulative) <= index)
+        .unwrap_or(0);
+    let (ustart, _, cumulative) = UV_ROWS[row];
+    let column = index - u32::from(cumulative);
+
+    let u = f64::from(ustart) + (f64::from(column) + 0.5) * f64::from(UV_SQUARE);
+    let v = f64::from(UV_V_START) + (row as f64 + 0.5) * f64::from(UV_SQUARE);
+    Some((u, v))
+}
+
+fn uv_to_xyz(y: f64, u: f64, v: f64) -> [f32; 3] {
+    if y <= 0.0 {
+        return [0.0; 3];
+    }
+    let s = 1.0 / (6.0 * u - 16.0 * v + 12.0);
+    let x = 9.0 * u * s;
+    let v = 4.0 * v * s;
+    [(x / v * y) as f32, y as f32, ((1.0 - x - v) / v * y) as f32]
+}
+
+/// Converts linear XYZ values to linear RGB values with the primaries of CCIR 709 and an
+/// equal-energy white, as libtiff does.
+pub(crate) fn xyz_to_rgb([x, y, z]: [f32; 3]) -> [f32; 3] {
+    [
+        2.690 * x - 1.276 * y - 0.414 * z,
+        -1.022 * x + 1.978 * y + 0.044 * z,
+        0.061 * x - 0.224 * y + 1.163 * z,
+    ]
+}
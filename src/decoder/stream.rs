@@ -124,7 +124,7 @@ pub trait EndianReader: Read {
 /// ## Deflate Reader
 ///
 
-pub type DeflateReader<'r, R> = flate2::read::ZlibDecoder<&'r mut SmartReader<R>>;
+pub type DeflateReader<R> = flate2::read::ZlibDecoder<R>;
 
 ///
 /// ## LZW Reader
@@ -137,14 +137,11 @@ pub struct LZWReader {
 
 impl LZWReader {
     /// Wraps a reader
-    pub fn new<R>(
-        reader: &mut SmartReader<R>,
+    pub fn new<R: Read>(
+        mut reader: R,
         compressed_length: usize,
         max_uncompressed_length: usize,
-    ) -> io::Result<(usize, LZWReader)>
-    where
-        R: Read + Seek,
-    {
+    ) -> io::Result<(usize, LZWReader)> {
         let mut compressed = vec![0; compressed_length as usize];
         reader.read_exact(&mut compressed[..])?;
         let mut uncompressed = vec![0; max_uncompressed_length];
@@ -199,7 +196,7 @@ pub(crate) struct JpegReader {
     buffer: io::Cursor<Vec<u8>>,
 }
 impl JpegReader {
-    /// Constructs new JpegReader wrapping a SmartReader.
+    /// Constructs new JpegReader wrapping a reader.
     /// Because JPEG compression in TIFF allows to save quantization and/or huffman tables in one
     /// central location, the constructor accepts this data as `jpeg_tables` here containing either
     /// or both.
@@ -211,14 +208,11 @@ impl JpegReader {
     /// Similary, `jpeg_tables` ends with a `EOI` (HEX: `0xFFD9`) or __end of image__ marker,
     /// this has to be removed as well (last two bytes of `jpeg_tables`).
 
-    pub fn new<R>(
-        reader: &mut SmartReader<R>,
+    pub fn new<R: Read>(
+        mut reader: R,
         length: u32,
         jpeg_tables: &Option<Vec<u8>>,
-    ) -> io::Result<JpegReader>
-    where
-        R: Read + Seek,
-    {
+    ) -> io::Result<JpegReader> {
         // Read jpeg image data
         let mut segment = vec![0; length as usize];
         reader.read_exact(&mut segment[..])?;
@@ -271,10 +265,7 @@ pub struct PackBitsReader {
 
 impl PackBitsReader {
     /// Wraps a reader
-    pub fn new<R: Read + Seek>(
-        mut reader: R,
-        length: usize,
-    ) -> io::Result<(usize, PackBitsReader)> {
+    pub fn new<R: Read>(mut reader: R, length: usize) -> io::Result<(usize, PackBitsReader)> {
         let mut buffer = Vec::new();
         let mut header: [u8; 1] = [0];
         let mut data: [u8; 1] = [0];
@@ -317,6 +308,31 @@ impl Read for PackBitsReader {
     }
 }
 
+/// Reader that reverses the order of the bits within each byte
+///
+/// This converts data with a `FillOrder` of `LsbToMsb` to the default order.
+pub struct ReverseBitsReader<R> {
+    reader: R,
+}
+
+impl<R: Read> ReverseBitsReader<R> {
+    /// Wraps a reader
+    pub fn new(reader: R) -> ReverseBitsReader<R> {
+        ReverseBitsReader { reader }
+    }
+}
+
+impl<R: Read> Read for ReverseBitsReader<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        buf[..read]
+            .iter_mut()
+            .for_each(|byte| *byte = byte.reverse_bits());
+        Ok(read)
+    }
+}
+
 ///
 /// ## SmartReader Reader
 ///
@@ -0,0 +1,109 @@
+//! Subsampled YCbCr samples and their conversion to RGB
+//!
+//! See section 21 of the TIFF 6.0 specification.
+use std::io::{self, Read};
+
+use crate::tags::Tag;
+use crate::{TiffFormatError, TiffResult};
+
+/// The default `YCbCrCoefficients`, the luma weights of red, green and blue of CCIR 601.
+const DEFAULT_COEFFICIENTS: [f32; 3] = [0.299, 0.587, 0.114];
+
+/// The default `ReferenceBlackWhite` for 8 bit samples, as used by libtiff.
+///
+/// The specification lists 0 as the black of the chroma samples, which would shift all colors.
+const DEFAULT_REFERENCE: [f32; 6] = [0.0, 255.0, 128.0, 255.0, 128.0, 255.0];
+
+/// Reads the data units of the subsampled YCbCr samples of a chunk.
+///
+/// Each data unit holds the luma samples of a block of `h * v` pixels, row by row, followed by
+/// the blue and red chroma samples shared by all pixels of the block. The chunk is `chunk_width`
+/// pixels wide. The chunky YCbCr samples of the first `width` pixels of its first `rows` rows are
+/// written to `buf`, with `stride` samples between the starts of the rows.
+pub(crate) fn read_data_units(
+    reader: impl Read,
+    buf: &mut [u8],
+    (h, v): (usize, usize),
+    chunk_width: usize,
+    width: usize,
+    rows: usize,
+    stride: usize,
+) -> io::Result<()> {
+    let units_across = chunk_width.checked_sub(1).map_or(0, |last| last / h + 1);
+    let units_down = rows.checked_sub(1).map_or(0, |last| last / v + 1);
+    let unit_len = h * v + 2;
+
+    // If less than the expected amount of bytes can be read, the remaining samples are 0.
+    let len = units_across * units_down * unit_len;
+    let mut units = Vec::with_capacity(len);
+    reader.take(len as u64).read_to_end(&mut units)?;
+    units.resize(len, 0);
+
+    for (i, unit) in units.chunks(unit_len).enumerate() {
+        let (left, top) = (i % units_across * h, i / units_across * v);
+        let (cb, cr) = (unit[h * v], unit[h * v + 1]);
+        for y in top..(top + v).min(rows) {
+            for x in left..(left + h).min(width) {
+                let luma = unit[(y - top) * h + x - left];
+                buf[y * stride + x * 3..][..3].copy_from_slice(&[luma, cb, cr]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The conversion of YCbCr samples to RGB.
+pub(crate) struct YCbCrConversion {
+    /// The weights of red, green and blue in the luma samples.
+    coefficients: [f32; 3],
+    /// The black and white levels of the luma, blue and red chroma samples.
+    reference: [f32; 6],
+}
+
+impl YCbCrConversion {
+    /// Creates the conversion from the values of the `YCbCrCoefficients` and
+    /// `ReferenceBlackWhite` tags.
+    pub fn new(coefficients: Option<Vec<f32>>, reference: Option<Vec<f32>>) -> TiffResult<Self> {
+        let mut conversion = YCbCrConversion {
+            coefficients: DEFAULT_COEFFICIENTS,
+            reference: DEFAULT_REFERENCE,
+        };
+
+        if let Some(coefficients) = coefficients {
+            if coefficients.len() != 3 || coefficients[1] == 0.0 {
+                return Err(TiffFormatError::InvalidTagValueType(Tag::YCbCrCoefficients).into());
+            }
+            conversion.coefficients.copy_from_slice(&coefficients);
+        }
+        if let Some(reference) = reference {
+            if reference.len() != 6 || reference.chunks(2).any(|range| range[0] == range[1]) {
+                return Err(TiffFormatError::InvalidTagValueType(Tag::ReferenceBlackWhite).into());
+            }
+            conversion.reference.copy_from_slice(&reference);
+        }
+
+        Ok(conversion)
+    }
+
+    /// Converts the YCbCr samples of a pixel to RGB.
+    pub fn to_rgb(&self, pixel: [u8; 3]) -> [u8; 3] {
+        let [red, green, blue] = self.coefficients;
+        let reference = &self.reference;
+        let scale = |sample: u8, channel: usize, range: f32| {
+            let (black, white) = (reference[2 * channel], reference[2 * channel + 1]);
+            (f32::from(sample) - black) * range / (white - black)
+        };
+
+        let y = scale(pixel[0], 0, 255.0);
+        let cb = scale(pixel[1], 1, 127.0);
+        let cr = scale(pixel[2], 2, 127.0);
+
+        let r = cr * (2.0 - 2.0 * red) + y;
+        let b = cb * (2.0 - 2.0 * blue) + y;
+        let g = (y - blue * b - red * r) / green;
+
+        // Casting saturates values outside of the range of `u8`.
+        [r.round() as u8, g.round() as u8, b.round() as u8]
+    }
+}
@@ -1,4 +1,4 @@
-use crate::tags::{PhotometricInterpretation, SampleFormat};
+use crate::tags::{ExtraSamples, PhotometricInterpretation, SampleFormat};
 
 /// Trait for different colortypes that can be encoded.
 pub trait ColorType {
@@ -10,6 +10,9 @@ pub trait ColorType {
     const BITS_PER_SAMPLE: &'static [u16];
     /// The value of the tiff tag `SampleFormat`
     const SAMPLE_FORMAT: &'static [SampleFormat];
+    /// The default value of the tiff tag `ExtraSamples`, describing the samples beyond those of
+    /// the color space
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[];
 }
 
 pub struct Gray8;
@@ -92,6 +95,33 @@ impl ColorType for Gray64Float {
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::IEEEFP];
 }
 
+/// Indices into a color map of 2 colors, one `u8` per pixel
+pub struct Palette1;
+impl ColorType for Palette1 {
+    type Inner = u8;
+    const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGBPalette;
+    const BITS_PER_SAMPLE: &'static [u16] = &[1];
+    const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint];
+}
+
+/// Indices into a color map of 16 colors, one `u8` per pixel
+pub struct Palette4;
+impl ColorType for Palette4 {
+    type Inner = u8;
+    const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGBPalette;
+    const BITS_PER_SAMPLE: &'static [u16] = &[4];
+    const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint];
+}
+
+/// Indices into a color map of 256 colors
+pub struct Palette8;
+impl ColorType for Palette8 {
+    type Inner = u8;
+    const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGBPalette;
+    const BITS_PER_SAMPLE: &'static [u16] = &[8];
+    const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint];
+}
+
 pub struct RGB8;
 impl ColorType for RGB8 {
     type Inner = u8;
@@ -146,6 +176,7 @@ impl ColorType for RGBA8 {
     const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGB;
     const BITS_PER_SAMPLE: &'static [u16] = &[8, 8, 8, 8];
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint; 4];
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[ExtraSamples::UnassociatedAlpha];
 }
 
 pub struct RGBA16;
@@ -154,6 +185,7 @@ impl ColorType for RGBA16 {
     const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGB;
     const BITS_PER_SAMPLE: &'static [u16] = &[16, 16, 16, 16];
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint; 4];
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[ExtraSamples::UnassociatedAlpha];
 }
 
 pub struct RGBA32;
@@ -162,6 +194,7 @@ impl ColorType for RGBA32 {
     const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGB;
     const BITS_PER_SAMPLE: &'static [u16] = &[32, 32, 32, 32];
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint; 4];
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[ExtraSamples::UnassociatedAlpha];
 }
 
 pub struct RGBA32Float;
@@ -170,6 +203,7 @@ impl ColorType for RGBA32Float {
     const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGB;
     const BITS_PER_SAMPLE: &'static [u16] = &[32, 32, 32, 32];
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::IEEEFP; 4];
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[ExtraSamples::UnassociatedAlpha];
 }
 
 pub struct RGBA64;
@@ -178,6 +212,7 @@ impl ColorType for RGBA64 {
     const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGB;
     const BITS_PER_SAMPLE: &'static [u16] = &[64, 64, 64, 64];
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::Uint; 4];
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[ExtraSamples::UnassociatedAlpha];
 }
 
 pub struct RGBA64Float;
@@ -186,6 +221,7 @@ impl ColorType for RGBA64Float {
     const TIFF_VALUE: PhotometricInterpretation = PhotometricInterpretation::RGB;
     const BITS_PER_SAMPLE: &'static [u16] = &[64, 64, 64, 64];
     const SAMPLE_FORMAT: &'static [SampleFormat] = &[SampleFormat::IEEEFP; 4];
+    const EXTRA_SAMPLES: &'static [ExtraSamples] = &[ExtraSamples::UnassociatedAlpha];
 }
 
 pub struct CMYK8;
@@ -11,17 +11,27 @@ use std::{
 };
 
 use crate::{
+    bitpack::pack_rows,
     error::TiffResult,
-    tags::{CompressionMethod, ResolutionUnit, Tag},
+    gdal::{GdalMetadata, NoData},
+    geotiff::GeoTiff,
+    tags::{
+        CompressionMethod, ExtraSamples, GpsTag, Orientation, PhotometricInterpretation, Predictor,
+        ResolutionUnit, SubfileType, Tag,
+    },
 };
 
 pub mod colortype;
 pub mod compression;
+pub mod overview;
+mod predictor;
 mod tiff_value;
 mod writer;
 
 use self::colortype::*;
 use self::compression::*;
+use self::overview::*;
+use self::predictor::*;
 use self::writer::*;
 
 /// Encoder for Tiff and BigTiff files.
@@ -116,6 +126,27 @@ impl<W: Write + Seek, K: TiffKind> TiffEncoder<W, K> {
         ImageEncoder::with_compression(encoder, width, height, compression)
     }
 
+    /// Create a [`TileImageEncoder`] to encode an image one tile at a time.
+    pub fn new_tile_image<C: ColorType>(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> TiffResult<TileImageEncoder<'_, W, C, K, Uncompressed>> {
+        let encoder = DirectoryEncoder::new(&mut self.writer)?;
+        TileImageEncoder::new(encoder, width, height)
+    }
+
+    /// Create a [`TileImageEncoder`] to encode an image one tile at a time.
+    pub fn new_tile_image_with_compression<C: ColorType, D: Compression>(
+        &mut self,
+        width: u32,
+        height: u32,
+        compression: D,
+    ) -> TiffResult<TileImageEncoder<'_, W, C, K, D>> {
+        let encoder = DirectoryEncoder::new(&mut self.writer)?;
+        TileImageEncoder::with_compression(encoder, width, height, compression)
+    }
+
     /// Convenience function to write an entire image from memory.
     pub fn write_image<C: ColorType>(
         &mut self,
@@ -153,12 +184,40 @@ impl<W: Write + Seek, K: TiffKind> TiffEncoder<W, K> {
 ///
 /// You should call `finish` on this when you are finished with it.
 /// Encoding can silently fail while this is dropping.
+///
+/// # Examples
+/// Private directories, like the EXIF and GPS IFDs, are written with
+/// [`sub_directory`][DirectoryEncoder::sub_directory]:
+/// ```
+/// # extern crate tiff;
+/// # fn main() {
+/// # let mut file = std::io::Cursor::new(Vec::new());
+/// # let image_data = vec![0; 100*100*3];
+/// use tiff::encoder::*;
+/// use tiff::tags::{GpsTag, Tag};
+///
+/// let mut tiff = TiffEncoder::new(&mut file).unwrap();
+/// let mut image = tiff.new_image::<colortype::RGB8>(100, 100).unwrap();
+///
+/// let mut exif = image.encoder().sub_directory(Tag::ExifIFD).unwrap();
+/// exif.write_tag(Tag::ExposureTime, Rational { n: 1, d: 250 }).unwrap();
+/// exif.finish().unwrap();
+///
+/// let mut gps = image.encoder().sub_directory(Tag::GPSInfo).unwrap();
+/// gps.write_gps_tag(GpsTag::AltitudeRef, 0u8).unwrap();
+/// gps.write_gps_tag(GpsTag::Altitude, Rational { n: 117, d: 2 }).unwrap();
+/// gps.finish().unwrap();
+///
+/// image.write_data(&image_data).unwrap();
+/// # }
+/// ```
 pub struct DirectoryEncoder<'a, W: 'a + Write + Seek, K: TiffKind> {
     writer: &'a mut TiffWriter<W>,
     dropped: bool,
-    // We use BTreeMap to make sure tags are written in correct order
-    ifd_pointer_pos: u64,
-    ifd: BTreeMap<u16, DirectoryEntry<K::OffsetType>>,
+    ifd_pointer_pos: Option<u64>,
+    // The tag of a parent directory pointing to this one and the entries of the parent
+    parent: Option<(Tag, &'a mut DirectoryEntries<K::OffsetType>)>,
+    ifd: DirectoryEntries<K::OffsetType>,
 }
 
 impl<'a, W: 'a + Write + Seek, K: TiffKind> DirectoryEncoder<'a, W, K> {
@@ -169,28 +228,49 @@ impl<'a, W: 'a + Write + Seek, K: TiffKind> DirectoryEncoder<'a, W, K> {
         Ok(DirectoryEncoder {
             writer,
             dropped: false,
-            ifd_pointer_pos,
+            ifd_pointer_pos: Some(ifd_pointer_pos),
+            parent: None,
+            ifd: BTreeMap::new(),
+        })
+    }
+
+    /// Creates an encoder for an ifd directory outside of the main chain of directories, whose
+    /// offset is stored in a tag of another directory.
+    fn new_sub_directory(writer: &'a mut TiffWriter<W>) -> TiffResult<Self> {
+        writer.pad_word_boundary()?;
+        Ok(DirectoryEncoder {
+            writer,
+            dropped: false,
+            ifd_pointer_pos: None,
+            parent: None,
+            ifd: BTreeMap::new(),
+        })
+    }
+
+    /// Creates an encoder for a private ifd directory, like the EXIF or GPS IFD.
+    ///
+    /// When the sub directory is finished, its offset is written to the given tag of this
+    /// directory, as an `IFD` value for normal Tiff and an `IFD8` value for BigTiff.
+    pub fn sub_directory(&mut self, tag: Tag) -> TiffResult<DirectoryEncoder<'_, W, K>> {
+        self.writer.pad_word_boundary()?;
+        Ok(DirectoryEncoder {
+            writer: &mut *self.writer,
+            dropped: false,
+            ifd_pointer_pos: None,
+            parent: Some((tag, &mut self.ifd)),
             ifd: BTreeMap::new(),
         })
     }
 
     /// Write a single ifd tag.
     pub fn write_tag<T: TiffValue>(&mut self, tag: Tag, value: T) -> TiffResult<()> {
-        let mut bytes = Vec::with_capacity(value.bytes());
-        {
-            let mut writer = TiffWriter::new(&mut bytes);
-            value.write(&mut writer)?;
-        }
-
-        self.ifd.insert(
-            tag.to_u16(),
-            DirectoryEntry {
-                data_type: <T>::FIELD_TYPE.to_u16(),
-                count: value.count().try_into()?,
-                data: bytes,
-            },
-        );
+        self.ifd.insert(tag.to_u16(), DirectoryEntry::new(value)?);
+        Ok(())
+    }
 
+    /// Write a single tag of a GPS IFD.
+    pub fn write_gps_tag<T: TiffValue>(&mut self, tag: GpsTag, value: T) -> TiffResult<()> {
+        self.ifd.insert(tag.to_u16(), DirectoryEntry::new(value)?);
         Ok(())
     }
 
@@ -251,23 +331,32 @@ impl<'a, W: 'a + Write + Seek, K: TiffKind> DirectoryEncoder<'a, W, K> {
         self.writer.last_written()
     }
 
-    fn finish_internal(&mut self) -> TiffResult<()> {
+    /// Writes out the ifd directory and returns its offset.
+    fn finish_internal(&mut self) -> TiffResult<u64> {
         let ifd_pointer = self.write_directory()?;
         let curr_pos = self.writer.offset();
 
-        self.writer.goto_offset(self.ifd_pointer_pos)?;
-        K::write_offset(&mut self.writer, ifd_pointer)?;
-        self.writer.goto_offset(curr_pos)?;
+        if let Some(ifd_pointer_pos) = self.ifd_pointer_pos {
+            self.writer.goto_offset(ifd_pointer_pos)?;
+            K::write_offset(&mut self.writer, ifd_pointer)?;
+            self.writer.goto_offset(curr_pos)?;
+        }
         K::write_offset(&mut self.writer, 0)?;
 
+        if let Some((tag, parent)) = self.parent.as_mut() {
+            let pointer = DirectoryEntry::new(K::convert_ifd_pointer(ifd_pointer)?)?;
+            parent.insert(tag.to_u16(), pointer);
+        }
+
         self.dropped = true;
 
-        Ok(())
+        Ok(ifd_pointer)
     }
 
     /// Write out the ifd directory.
     pub fn finish(mut self) -> TiffResult<()> {
-        self.finish_internal()
+        self.finish_internal()?;
+        Ok(())
     }
 }
 
@@ -326,10 +415,13 @@ pub struct ImageEncoder<
     width: u32,
     height: u32,
     rows_per_strip: u64,
+    predictor: Predictor,
     strip_offsets: Vec<K::OffsetType>,
     strip_byte_count: Vec<K::OffsetType>,
     dropped: bool,
     compression: D,
+    // The georeferencing, scaled for overviews
+    geotiff: Option<GeoTiff>,
     _phantom: ::std::marker::PhantomData<C>,
 }
 
@@ -363,37 +455,23 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
 
         let strip_count = (u64::from(height) + rows_per_strip - 1) / rows_per_strip;
 
-        encoder.write_tag(Tag::ImageWidth, width)?;
-        encoder.write_tag(Tag::ImageLength, height)?;
-        encoder.write_tag(Tag::Compression, D::COMPRESSION_METHOD.to_u16())?;
-
-        encoder.write_tag(Tag::BitsPerSample, <T>::BITS_PER_SAMPLE)?;
-        let sample_format: Vec<_> = <T>::SAMPLE_FORMAT.iter().map(|s| s.to_u16()).collect();
-        encoder.write_tag(Tag::SampleFormat, &sample_format[..])?;
-        encoder.write_tag(Tag::PhotometricInterpretation, <T>::TIFF_VALUE.to_u16())?;
-
+        write_image_tags::<W, T, K, D>(&mut encoder, width, height)?;
         encoder.write_tag(Tag::RowsPerStrip, u32::try_from(rows_per_strip)?)?;
 
-        encoder.write_tag(
-            Tag::SamplesPerPixel,
-            u16::try_from(<T>::BITS_PER_SAMPLE.len())?,
-        )?;
-        encoder.write_tag(Tag::XResolution, Rational { n: 1, d: 1 })?;
-        encoder.write_tag(Tag::YResolution, Rational { n: 1, d: 1 })?;
-        encoder.write_tag(Tag::ResolutionUnit, ResolutionUnit::None.to_u16())?;
-
         Ok(ImageEncoder {
             encoder,
             strip_count,
             strip_idx: 0,
             row_samples,
             rows_per_strip,
+            predictor: Predictor::None,
             width,
             height,
             strip_offsets: Vec::new(),
             strip_byte_count: Vec::new(),
             dropped: false,
             compression: compression,
+            geotiff: None,
             _phantom: ::std::marker::PhantomData,
         })
     }
@@ -426,7 +504,27 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
         }
 
         // Write the (possible compressed) data to the encoder.
-        let offset = self.encoder.write_data(value)?;
+        let offset = match (packed_bits::<T>(), self.predictor) {
+            (Some(bits), _) => {
+                let row_samples = usize::try_from(self.row_samples)?;
+                let indices = value.data();
+                let data = pack_rows(indices.iter().map(|&i| u64::from(i)), row_samples, bits)?;
+                self.encoder.write_data(&data[..])?
+            }
+            (None, Predictor::None) => self.encoder.write_data(value)?,
+            (None, predictor) => {
+                let byte_len = <T::Inner>::BYTE_LEN;
+                let mut data = value.data().into_owned();
+                let row_bytes = usize::try_from(self.row_samples)? * usize::from(byte_len);
+                let samples = <T>::BITS_PER_SAMPLE.len();
+                if predictor == Predictor::FloatingPoint {
+                    predict_floating_point(&mut data, row_bytes, samples, byte_len);
+                } else {
+                    predict_horizontal(&mut data, row_bytes, samples, byte_len);
+                }
+                self.encoder.write_data(&data[..])?
+            }
+        };
         let byte_count = self.encoder.last_written() as usize;
 
         self.strip_offsets.push(K::convert_offset(offset)?);
@@ -438,6 +536,147 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
 
     /// Write strips from data
     pub fn write_data(mut self, data: &[T::Inner]) -> TiffResult<()>
+    where
+        [T::Inner]: TiffValue,
+    {
+        self.write_strips(data)?;
+        self.finish()?;
+        Ok(())
+    }
+
+    /// Write the image followed by its overviews
+    ///
+    /// Each overview is computed from the previous level and written as a reduced resolution
+    /// image with the compression and predictor of this image. The extra samples, orientation and
+    /// GDAL tags of this image are copied to the overviews, the GeoTIFF georeferencing set with
+    /// [`geotiff`][ImageEncoder::geotiff] is scaled to their resolution. The other tags of this
+    /// image are not copied. Returns an error for palette images.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate tiff;
+    /// # fn main() {
+    /// # let mut file = std::io::Cursor::new(Vec::new());
+    /// # let image_data = vec![0; 100*100];
+    /// use tiff::encoder::*;
+    /// use tiff::encoder::overview::*;
+    ///
+    /// let mut tiff = TiffEncoder::new(&mut file).unwrap();
+    /// let image = tiff.new_image::<colortype::Gray8>(100, 100).unwrap();
+    /// let overviews = Overviews {
+    ///     levels: 3,
+    ///     resampling: Resampling::Average,
+    ///     layout: OverviewLayout::Chained,
+    /// };
+    /// image.write_data_with_overviews(&image_data, &overviews).unwrap();
+    /// # }
+    /// ```
+    pub fn write_data_with_overviews(
+        mut self,
+        data: &[T::Inner],
+        overviews: &Overviews,
+    ) -> TiffResult<()>
+    where
+        [T::Inner]: TiffValue,
+        T::Inner: Sample,
+        D: Clone,
+    {
+        if <T>::TIFF_VALUE == PhotometricInterpretation::RGBPalette {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Overviews of palette images are not supported",
+            )
+            .into());
+        }
+        let samples = <T>::BITS_PER_SAMPLE.len();
+        let mut dimensions = (usize::try_from(self.width)?, usize::try_from(self.height)?);
+        let samples_len = (dimensions.0)
+            .checked_mul(dimensions.1)
+            .and_then(|pixels| pixels.checked_mul(samples));
+        if samples_len.filter(|&len| len <= data.len()).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Input data slice is undersized for provided dimensions",
+            )
+            .into());
+        }
+
+        // The tags describing the samples hold for all levels.
+        let tags: Vec<_> = [
+            Tag::ExtraSamples,
+            Tag::Orientation,
+            Tag::GdalNodata,
+            Tag::GdalMetadata,
+        ]
+        .iter()
+        .filter_map(|tag| {
+            let entry = self.encoder.ifd.get(&tag.to_u16())?;
+            Some((tag.to_u16(), entry.clone()))
+        })
+        .collect();
+        let base_dimensions = dimensions;
+
+        self.write_strips(data)?;
+        let chained = overviews.layout == OverviewLayout::Chained;
+        if chained {
+            self.finish_internal()?;
+        }
+
+        let mut offsets = Vec::with_capacity(overviews.levels);
+        let mut level = Vec::new();
+        for i in 0..overviews.levels {
+            let previous = if i == 0 { data } else { &level[..] };
+            let (next, next_dimensions) =
+                downsample(previous, dimensions, samples, overviews.resampling);
+            level = next;
+            dimensions = next_dimensions;
+
+            let writer = &mut *self.encoder.writer;
+            let encoder = if chained {
+                DirectoryEncoder::new(writer)?
+            } else {
+                DirectoryEncoder::new_sub_directory(writer)?
+            };
+            let mut image = ImageEncoder::<W, T, K, D>::with_compression(
+                encoder,
+                u32::try_from(dimensions.0)?,
+                u32::try_from(dimensions.1)?,
+                self.compression.clone(),
+            )?;
+            let subfile_type = SubfileType {
+                reduced_resolution: true,
+                ..SubfileType::default()
+            };
+            image
+                .encoder
+                .write_tag(Tag::NewSubfileType, subfile_type.to_u32())?;
+            if self.predictor != Predictor::None {
+                image.predictor(self.predictor)?;
+            }
+            image.encoder.ifd.extend(tags.iter().cloned());
+            if let Some(ref geotiff) = self.geotiff {
+                let scale = (
+                    base_dimensions.0 as f64 / dimensions.0 as f64,
+                    base_dimensions.1 as f64 / dimensions.1 as f64,
+                );
+                write_geotiff(&mut image.encoder, &geotiff.resampled(scale))?;
+            }
+            image.write_strips(&level)?;
+            offsets.push(K::convert_ifd_pointer(image.finish_internal()?)?);
+        }
+
+        if !chained {
+            if !offsets.is_empty() {
+                self.encoder
+                    .write_tag(Tag::SubIFDs, K::convert_ifd_pointer_slice(&offsets))?;
+            }
+            self.finish_internal()?;
+        }
+        Ok(())
+    }
+
+    /// Write all strips of the image
+    fn write_strips(&mut self, data: &[T::Inner]) -> TiffResult<()>
     where
         [T::Inner]: TiffValue,
     {
@@ -469,7 +708,6 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
         }
 
         self.encoder.writer.reset_compression();
-        self.finish()?;
         Ok(())
     }
 
@@ -501,6 +739,80 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
         self.encoder.write_tag(Tag::YResolution, value).unwrap();
     }
 
+    /// Set the orientation of the image
+    ///
+    /// The image data is written as passed, the orientation tells readers how to rotate or flip
+    /// it for display.
+    pub fn orientation(&mut self, orientation: Orientation) -> TiffResult<()> {
+        self.encoder
+            .write_tag(Tag::Orientation, orientation.to_u16())
+    }
+
+    /// Set the GeoTIFF georeferencing of the image
+    ///
+    /// Writes the `GeoKeyDirectoryTag` with the params tags its keys refer to, and the model
+    /// tags.
+    ///
+    /// # Examples
+    /// ```
+    /// # extern crate tiff;
+    /// # fn main() {
+    /// # let mut file = std::io::Cursor::new(Vec::new());
+    /// # let image_data = vec![0; 100*100];
+    /// use tiff::encoder::*;
+    /// use tiff::geotiff::GeoTiff;
+    ///
+    /// let mut tiff = TiffEncoder::new(&mut file).unwrap();
+    /// let mut image = tiff.new_image::<colortype::Gray8>(100, 100).unwrap();
+    /// let geotiff = GeoTiff::default()
+    ///     .with_projected_epsg(32633)
+    ///     .with_transform([30.0, 0.0, 500_000.0, 0.0, -30.0, 4_000_000.0]);
+    /// image.geotiff(&geotiff).unwrap();
+    /// image.write_data(&image_data).unwrap();
+    /// # }
+    /// ```
+    pub fn geotiff(&mut self, geotiff: &GeoTiff) -> TiffResult<()> {
+        write_geotiff(&mut self.encoder, geotiff)?;
+        self.geotiff = Some(geotiff.clone());
+        Ok(())
+    }
+
+    /// Set the value of the samples of pixels without data
+    ///
+    /// The value is written as text to the `GDAL_NODATA` tag, it should have the type of the
+    /// samples of the image.
+    pub fn gdal_nodata(&mut self, nodata: NoData) -> TiffResult<()> {
+        self.encoder
+            .write_tag(Tag::GdalNodata, &nodata.to_string()[..])
+    }
+
+    /// Set the metadata items of the `GDAL_METADATA` tag
+    pub fn gdal_metadata(&mut self, metadata: &GdalMetadata) -> TiffResult<()> {
+        self.encoder
+            .write_tag(Tag::GdalMetadata, &metadata.to_xml()[..])
+    }
+
+    /// Set the predictor applied to the image data before compression
+    ///
+    /// The horizontal predictor is only available for integer samples, the floating point
+    /// predictor only for floating point samples. This function needs to be called before any
+    /// calls to `write_data` or `write_strip` and will return an error otherwise.
+    pub fn predictor(&mut self, predictor: Predictor) -> TiffResult<()> {
+        if self.strip_idx != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot change predictor after data was written",
+            )
+            .into());
+        }
+        check_predictor::<T>(predictor)?;
+
+        self.encoder.write_tag(Tag::Predictor, predictor.to_u16())?;
+        self.predictor = predictor;
+
+        Ok(())
+    }
+
     /// Set image number of lines per strip
     ///
     /// This function needs to be called before any calls to `write_data` or
@@ -523,7 +835,26 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
         Ok(())
     }
 
-    fn finish_internal(&mut self) -> TiffResult<()> {
+    /// Set the color map of a palette image
+    ///
+    /// The color map holds the red values of all `2^bits` colors, followed by all green and then
+    /// all blue values, each ranging from 0 to 65535. Palette images must have a color map, for
+    /// other color types this function returns an error.
+    pub fn color_map(&mut self, color_map: &[u16]) -> TiffResult<()> {
+        write_color_map::<W, T, K>(&mut self.encoder, color_map)
+    }
+
+    /// Set the meaning of the extra samples of the color type
+    ///
+    /// By default the alpha samples of RGBA color types are written as unassociated alpha. Images
+    /// whose color samples are premultiplied with their alpha need to be written with
+    /// `ExtraSamples::AssociatedAlpha` instead. Returns an error if the number of values differs
+    /// from the number of extra samples of the color type.
+    pub fn extra_samples(&mut self, extra_samples: &[ExtraSamples]) -> TiffResult<()> {
+        write_extra_samples::<W, T, K>(&mut self.encoder, extra_samples)
+    }
+
+    fn finish_internal(&mut self) -> TiffResult<u64> {
         self.encoder
             .write_tag(Tag::StripOffsets, K::convert_slice(&self.strip_offsets))?;
         self.encoder.write_tag(
@@ -541,8 +872,21 @@ impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
     }
 
     /// Write out image and ifd directory.
+    ///
+    /// Returns an error if a palette image has no color map, the directory is written regardless.
     pub fn finish(mut self) -> TiffResult<()> {
-        self.finish_internal()
+        let color_map = has_color_map::<W, T, K>(&self.encoder);
+        self.finish_internal()?;
+
+        if !color_map {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Palette images require a color map",
+            )
+            .into());
+        }
+
+        Ok(())
     }
 }
 
@@ -556,18 +900,610 @@ impl<'a, W: Write + Seek, C: ColorType, K: TiffKind, D: Compression> Drop
     }
 }
 
+/// Writes the tags describing the image layout that strip and tile encoders share.
+fn write_image_tags<W: Write + Seek, T: ColorType, K: TiffKind, D: Compression>(
+    encoder: &mut DirectoryEncoder<W, K>,
+    width: u32,
+    height: u32,
+) -> TiffResult<()> {
+    encoder.write_tag(Tag::ImageWidth, width)?;
+    encoder.write_tag(Tag::ImageLength, height)?;
+    encoder.write_tag(Tag::Compression, D::COMPRESSION_METHOD.to_u16())?;
+
+    encoder.write_tag(Tag::BitsPerSample, <T>::BITS_PER_SAMPLE)?;
+    let sample_format: Vec<_> = <T>::SAMPLE_FORMAT.iter().map(|s| s.to_u16()).collect();
+    encoder.write_tag(Tag::SampleFormat, &sample_format[..])?;
+    encoder.write_tag(Tag::PhotometricInterpretation, <T>::TIFF_VALUE.to_u16())?;
+
+    encoder.write_tag(
+        Tag::SamplesPerPixel,
+        u16::try_from(<T>::BITS_PER_SAMPLE.len())?,
+    )?;
+    if !<T>::EXTRA_SAMPLES.is_empty() {
+        write_extra_samples::<W, T, K>(encoder, <T>::EXTRA_SAMPLES)?;
+    }
+    encoder.write_tag(Tag::XResolution, Rational { n: 1, d: 1 })?;
+    encoder.write_tag(Tag::YResolution, Rational { n: 1, d: 1 })?;
+    encoder.write_tag(Tag::ResolutionUnit, ResolutionUnit::None.to_u16())?;
+
+    Ok(())
+}
+
+/// Writes the GeoTIFF tags of an image.
+fn write_geotiff<W: Write + Seek, K: TiffKind>(
+    encoder: &mut DirectoryEncoder<W, K>,
+    geotiff: &GeoTiff,
+) -> TiffResult<()> {
+    let (directory, doubles, ascii) = geotiff.key_directory()?;
+    encoder.write_tag(Tag::GeoKeyDirectoryTag, &directory[..])?;
+    if !doubles.is_empty() {
+        encoder.write_tag(Tag::GeoDoubleParamsTag, &doubles[..])?;
+    }
+    if !ascii.is_empty() {
+        encoder.write_tag(Tag::GeoAsciiParamsTag, &ascii[..])?;
+    }
+
+    if !geotiff.tiepoints.is_empty() {
+        let tiepoints: Vec<f64> = geotiff.tiepoints.iter().flatten().copied().collect();
+        encoder.write_tag(Tag::ModelTiepointTag, &tiepoints[..])?;
+    }
+    if let Some(pixel_scale) = geotiff.pixel_scale {
+        encoder.write_tag(Tag::ModelPixelScaleTag, &pixel_scale[..])?;
+    }
+    if let Some(transformation) = geotiff.transformation {
+        encoder.write_tag(Tag::ModelTransformationTag, &transformation[..])?;
+    }
+
+    Ok(())
+}
+
+/// Bits of the samples of the color type if several of them are packed into each byte.
+fn packed_bits<T: ColorType>() -> Option<u8> {
+    match *<T>::BITS_PER_SAMPLE {
+        [bits] if bits < 8 => u8::try_from(bits).ok(),
+        _ => None,
+    }
+}
+
+/// Writes the color map of a palette image after checking its size.
+fn write_color_map<W: Write + Seek, T: ColorType, K: TiffKind>(
+    encoder: &mut DirectoryEncoder<W, K>,
+    color_map: &[u16],
+) -> TiffResult<()> {
+    let colors = match *<T>::BITS_PER_SAMPLE {
+        [bits] if <T>::TIFF_VALUE == PhotometricInterpretation::RGBPalette => 1usize << bits,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Color maps are only supported for palette images",
+            )
+            .into())
+        }
+    };
+    if color_map.len() != 3 * colors {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Color map must hold three values for each color",
+        )
+        .into());
+    }
+
+    encoder.write_tag(Tag::ColorMap, color_map)
+}
+
+/// Writes the meaning of the extra samples of the color type after checking their number.
+fn write_extra_samples<W: Write + Seek, T: ColorType, K: TiffKind>(
+    encoder: &mut DirectoryEncoder<W, K>,
+    extra_samples: &[ExtraSamples],
+) -> TiffResult<()> {
+    if <T>::EXTRA_SAMPLES.is_empty() || extra_samples.len() != <T>::EXTRA_SAMPLES.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Extra samples must describe each extra sample of the color type",
+        )
+        .into());
+    }
+
+    let extra_samples: Vec<_> = extra_samples.iter().map(|s| s.to_u16()).collect();
+    encoder.write_tag(Tag::ExtraSamples, &extra_samples[..])
+}
+
+/// Whether the directory has the color map required for images of the color type.
+fn has_color_map<W: Write + Seek, T: ColorType, K: TiffKind>(
+    encoder: &DirectoryEncoder<W, K>,
+) -> bool {
+    <T>::TIFF_VALUE != PhotometricInterpretation::RGBPalette
+        || encoder.ifd.contains_key(&Tag::ColorMap.to_u16())
+}
+
+/// Tiled image encoder
+///
+/// Tiles can be written in any order, but each tile exactly once. Tiles at the right and
+/// bottom border of the image may be given either with their full size or cropped to the image,
+/// the latter are padded before being written.
+///
+/// # Examples
+/// ```
+/// # extern crate tiff;
+/// # fn main() {
+/// # let mut file = std::io::Cursor::new(Vec::new());
+/// # let image_data = vec![0; 100*100*3];
+/// use tiff::encoder::*;
+///
+/// let mut tiff = TiffEncoder::new(&mut file).unwrap();
+/// let mut image = tiff.new_tile_image::<colortype::RGB8>(100, 100).unwrap();
+///
+/// // Tile size can be configured before writing data
+/// image.tile_size(64, 64).unwrap();
+///
+/// for row in (0..image.tiles_down()).rev() {
+///     for column in 0..image.tiles_across() {
+///         let (width, height) = image.tile_data_dimensions(column, row);
+///         let tile = vec![0; (width * height * 3) as usize];
+///         image.write_tile(column, row, &tile).unwrap();
+///     }
+/// }
+/// image.finish().unwrap();
+/// # }
+/// ```
+/// You can also call write_data function wich will encode by tile and finish
+pub struct TileImageEncoder<
+    'a,
+    W: 'a + Write + Seek,
+    C: ColorType,
+    K: TiffKind,
+    D: Compression = Uncompressed,
+> {
+    encoder: DirectoryEncoder<'a, W, K>,
+    width: u32,
+    height: u32,
+    tile_width: u32,
+    tile_length: u32,
+    tiles_across: u32,
+    tiles_down: u32,
+    tile_offsets: Vec<K::OffsetType>,
+    tile_byte_count: Vec<K::OffsetType>,
+    tile_written: Vec<bool>,
+    predictor: Predictor,
+    dropped: bool,
+    compression: D,
+    _phantom: ::std::marker::PhantomData<C>,
+}
+
+impl<'a, W: 'a + Write + Seek, T: ColorType, K: TiffKind, D: Compression>
+    TileImageEncoder<'a, W, T, K, D>
+{
+    fn new(encoder: DirectoryEncoder<'a, W, K>, width: u32, height: u32) -> TiffResult<Self>
+    where
+        D: Default,
+    {
+        Self::with_compression(encoder, width, height, D::default())
+    }
+
+    fn with_compression(
+        mut encoder: DirectoryEncoder<'a, W, K>,
+        width: u32,
+        height: u32,
+        compression: D,
+    ) -> TiffResult<Self> {
+        write_image_tags::<W, T, K, D>(&mut encoder, width, height)?;
+
+        let mut image = TileImageEncoder {
+            encoder,
+            width,
+            height,
+            tile_width: 0,
+            tile_length: 0,
+            tiles_across: 0,
+            tiles_down: 0,
+            tile_offsets: Vec::new(),
+            tile_byte_count: Vec::new(),
+            tile_written: Vec::new(),
+            predictor: Predictor::None,
+            dropped: false,
+            compression,
+            _phantom: ::std::marker::PhantomData,
+        };
+        image.tile_size(256, 256)?;
+
+        Ok(image)
+    }
+
+    /// Set the size of the tiles, the default is 256x256
+    ///
+    /// Both dimensions must be a multiple of 16. This function needs to be called before any
+    /// calls to `write_data` or `write_tile` and will return an error otherwise.
+    pub fn tile_size(&mut self, width: u32, length: u32) -> TiffResult<()> {
+        if self.tile_written.iter().any(|&written| written) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot change tile size after data was written",
+            )
+            .into());
+        }
+        if width == 0 || length == 0 || (width % 16, length % 16) != (0, 0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Tile dimensions must be non-zero multiples of 16",
+            )
+            .into());
+        }
+
+        self.encoder.write_tag(Tag::TileWidth, width)?;
+        self.encoder.write_tag(Tag::TileLength, length)?;
+
+        self.tile_width = width;
+        self.tile_length = length;
+        self.tiles_across = self.width.checked_sub(1).map_or(0, |last| last / width + 1);
+        self.tiles_down = self
+            .height
+            .checked_sub(1)
+            .map_or(0, |last| last / length + 1);
+
+        let tile_count = usize::try_from(self.tiles_across)? * usize::try_from(self.tiles_down)?;
+        self.tile_offsets = (0..tile_count)
+            .map(|_| K::convert_offset(0))
+            .collect::<TiffResult<_>>()?;
+        self.tile_byte_count = (0..tile_count)
+            .map(|_| K::convert_offset(0))
+            .collect::<TiffResult<_>>()?;
+        self.tile_written = vec![false; tile_count];
+
+        Ok(())
+    }
+
+    /// Set the predictor applied to the image data before compression
+    ///
+    /// The horizontal predictor is only available for integer samples, the floating point
+    /// predictor only for floating point samples. This function needs to be called before any
+    /// calls to `write_data` or `write_tile` and will return an error otherwise.
+    pub fn predictor(&mut self, predictor: Predictor) -> TiffResult<()> {
+        if self.tile_written.iter().any(|&written| written) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot change predictor after data was written",
+            )
+            .into());
+        }
+        check_predictor::<T>(predictor)?;
+
+        self.encoder.write_tag(Tag::Predictor, predictor.to_u16())?;
+        self.predictor = predictor;
+
+        Ok(())
+    }
+
+    /// Number of tiles in each row of tiles
+    pub fn tiles_across(&self) -> u32 {
+        self.tiles_across
+    }
+
+    /// Number of rows of tiles
+    pub fn tiles_down(&self) -> u32 {
+        self.tiles_down
+    }
+
+    /// Dimensions of the part of the tile at the given position that lies within the image.
+    pub fn tile_data_dimensions(&self, column: u32, row: u32) -> (u32, u32) {
+        let x = column.saturating_mul(self.tile_width).min(self.width);
+        let y = row.saturating_mul(self.tile_length).min(self.height);
+        (
+            self.tile_width.min(self.width - x),
+            self.tile_length.min(self.height - y),
+        )
+    }
+
+    /// Write the tile at the given position.
+    ///
+    /// The data holds the rows of the tile, either with the full tile size or with the
+    /// [`tile_data_dimensions`][TileImageEncoder::tile_data_dimensions].
+    pub fn write_tile(&mut self, column: u32, row: u32, value: &[T::Inner]) -> TiffResult<()>
+    where
+        [T::Inner]: TiffValue,
+    {
+        if column >= self.tiles_across || row >= self.tiles_down {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Tile position is outside of the image",
+            )
+            .into());
+        }
+
+        let samples = u64::try_from(<T>::BITS_PER_SAMPLE.len())?;
+        let (width, height) = self.tile_data_dimensions(column, row);
+        let data_samples = u64::from(width) * u64::from(height) * samples;
+        let tile_samples = u64::from(self.tile_width) * u64::from(self.tile_length) * samples;
+
+        let line_width = if u64::try_from(value.len())? == data_samples {
+            width
+        } else if u64::try_from(value.len())? == tile_samples {
+            self.tile_width
+        } else {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidData, "Slice is wrong size for tile").into(),
+            );
+        };
+
+        let stride =
+            usize::try_from(u64::from(line_width) * samples)? * usize::from(<T::Inner>::BYTE_LEN);
+        self.write_tile_bytes(column, row, &value.data(), stride)
+    }
+
+    /// Pads the tile data and writes it.
+    ///
+    /// The lines of the tile data are read from `data`, each line starting `stride` bytes after
+    /// the previous one.
+    fn write_tile_bytes(
+        &mut self,
+        column: u32,
+        row: u32,
+        data: &[u8],
+        stride: usize,
+    ) -> TiffResult<()> {
+        let index = usize::try_from(row * self.tiles_across + column)?;
+        if self.tile_written[index] {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidInput, "Tile was already written").into(),
+            );
+        }
+
+        let pixel_bytes = <T>::BITS_PER_SAMPLE.len() * usize::from(<T::Inner>::BYTE_LEN);
+        let (width, height) = self.tile_data_dimensions(column, row);
+        let line_bytes = usize::try_from(width)? * pixel_bytes;
+        let tile_line_bytes = usize::try_from(self.tile_width)? * pixel_bytes;
+
+        let mut tile = vec![0; tile_line_bytes * usize::try_from(self.tile_length)?];
+        for line in 0..usize::try_from(height)? {
+            tile[line * tile_line_bytes..][..line_bytes]
+                .copy_from_slice(&data[line * stride..][..line_bytes]);
+        }
+
+        let samples = <T>::BITS_PER_SAMPLE.len();
+        let byte_len = <T::Inner>::BYTE_LEN;
+        match self.predictor {
+            Predictor::Horizontal => {
+                predict_horizontal(&mut tile, tile_line_bytes, samples, byte_len);
+            }
+            Predictor::FloatingPoint => {
+                predict_floating_point(&mut tile, tile_line_bytes, samples, byte_len);
+            }
+            _ => {}
+        }
+
+        let (tile, tile_line_bytes) = match packed_bits::<T>() {
+            Some(bits) => {
+                let row_samples = usize::try_from(self.tile_width)? * samples;
+                let indices = tile.iter().map(|&index| u64::from(index));
+                let tile = pack_rows(indices, row_samples, bits)?;
+                (tile, tile_line_bytes * usize::from(bits) / 8)
+            }
+            None => (tile, tile_line_bytes),
+        };
+
+        self.encoder
+            .writer
+            .set_compression(self.compression.get_algorithm());
+        let written = self.write_compressed(&tile, tile_line_bytes);
+        self.encoder.writer.reset_compression();
+        let (offset, byte_count) = written?;
+
+        self.tile_offsets[index] = K::convert_offset(offset)?;
+        self.tile_byte_count[index] = usize::try_from(byte_count)?.try_into()?;
+        self.tile_written[index] = true;
+
+        Ok(())
+    }
+
+    /// Write the (possible compressed) tile to the encoder, returning its offset and byte count.
+    fn write_compressed(&mut self, tile: &[u8], line_bytes: usize) -> TiffResult<(u64, u64)> {
+        match D::COMPRESSION_METHOD {
+            // Each row must be packed separately. Do not compress across row boundaries
+            CompressionMethod::PackBits => {
+                let offset = self.encoder.writer.offset();
+                let mut byte_count = 0;
+                for line in tile.chunks(line_bytes) {
+                    self.encoder.write_data(line)?;
+                    byte_count += self.encoder.last_written();
+                }
+                Ok((offset, byte_count))
+            }
+            _ => {
+                let offset = self.encoder.write_data(tile)?;
+                Ok((offset, self.encoder.last_written()))
+            }
+        }
+    }
+
+    /// Write tiles from data
+    pub fn write_data(mut self, data: &[T::Inner]) -> TiffResult<()>
+    where
+        [T::Inner]: TiffValue,
+    {
+        let samples_len = usize::try_from(self.width)?
+            .checked_mul(usize::try_from(self.height)?)
+            .and_then(|pixels| pixels.checked_mul(<T>::BITS_PER_SAMPLE.len()));
+        if samples_len.filter(|&len| len <= data.len()).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Input data slice is undersized for provided dimensions",
+            )
+            .into());
+        }
+
+        let pixel_bytes = <T>::BITS_PER_SAMPLE.len() * usize::from(<T::Inner>::BYTE_LEN);
+        let stride = usize::try_from(self.width)? * pixel_bytes;
+        let data = data.data();
+
+        for row in 0..self.tiles_down {
+            for column in 0..self.tiles_across {
+                let x = usize::try_from(column * self.tile_width)?;
+                let y = usize::try_from(row * self.tile_length)?;
+                let start = y * stride + x * pixel_bytes;
+                self.write_tile_bytes(column, row, &data[start..], stride)?;
+            }
+        }
+
+        self.finish()
+    }
+
+    /// Set image resolution
+    pub fn resolution(&mut self, unit: ResolutionUnit, value: Rational) {
+        self.encoder
+            .write_tag(Tag::ResolutionUnit, unit.to_u16())
+            .unwrap();
+        self.encoder
+            .write_tag(Tag::XResolution, value.clone())
+            .unwrap();
+        self.encoder.write_tag(Tag::YResolution, value).unwrap();
+    }
+
+    /// Set image resolution unit
+    pub fn resolution_unit(&mut self, unit: ResolutionUnit) {
+        self.encoder
+            .write_tag(Tag::ResolutionUnit, unit.to_u16())
+            .unwrap();
+    }
+
+    /// Set image x-resolution
+    pub fn x_resolution(&mut self, value: Rational) {
+        self.encoder.write_tag(Tag::XResolution, value).unwrap();
+    }
+
+    /// Set image y-resolution
+    pub fn y_resolution(&mut self, value: Rational) {
+        self.encoder.write_tag(Tag::YResolution, value).unwrap();
+    }
+
+    /// Set the orientation of the image
+    ///
+    /// See [`ImageEncoder::orientation`].
+    pub fn orientation(&mut self, orientation: Orientation) -> TiffResult<()> {
+        self.encoder
+            .write_tag(Tag::Orientation, orientation.to_u16())
+    }
+
+    /// Set the GeoTIFF georeferencing of the image
+    ///
+    /// See [`ImageEncoder::geotiff`].
+    pub fn geotiff(&mut self, geotiff: &GeoTiff) -> TiffResult<()> {
+        write_geotiff(&mut self.encoder, geotiff)
+    }
+
+    /// Set the value of the samples of pixels without data
+    ///
+    /// See [`ImageEncoder::gdal_nodata`].
+    pub fn gdal_nodata(&mut self, nodata: NoData) -> TiffResult<()> {
+        self.encoder
+            .write_tag(Tag::GdalNodata, &nodata.to_string()[..])
+    }
+
+    /// Set the metadata items of the `GDAL_METADATA` tag
+    pub fn gdal_metadata(&mut self, metadata: &GdalMetadata) -> TiffResult<()> {
+        self.encoder
+            .write_tag(Tag::GdalMetadata, &metadata.to_xml()[..])
+    }
+
+    /// Set the color map of a palette image
+    ///
+    /// See [`ImageEncoder::color_map`] for the layout of the color map.
+    pub fn color_map(&mut self, color_map: &[u16]) -> TiffResult<()> {
+        write_color_map::<W, T, K>(&mut self.encoder, color_map)
+    }
+
+    /// Set the meaning of the extra samples of the color type
+    ///
+    /// See [`ImageEncoder::extra_samples`] for the defaults.
+    pub fn extra_samples(&mut self, extra_samples: &[ExtraSamples]) -> TiffResult<()> {
+        write_extra_samples::<W, T, K>(&mut self.encoder, extra_samples)
+    }
+
+    fn finish_internal(&mut self) -> TiffResult<u64> {
+        self.encoder
+            .write_tag(Tag::TileOffsets, K::convert_slice(&self.tile_offsets))?;
+        self.encoder
+            .write_tag(Tag::TileByteCounts, K::convert_slice(&self.tile_byte_count))?;
+        self.dropped = true;
+
+        self.encoder.finish_internal()
+    }
+
+    /// Get a reference of the underlying `DirectoryEncoder`
+    pub fn encoder(&mut self) -> &mut DirectoryEncoder<'a, W, K> {
+        &mut self.encoder
+    }
+
+    /// Write out image and ifd directory.
+    ///
+    /// Returns an error if not all tiles have been written or a palette image has no color map,
+    /// the directory is written regardless.
+    pub fn finish(mut self) -> TiffResult<()> {
+        let complete = self.tile_written.iter().all(|&written| written);
+        let color_map = has_color_map::<W, T, K>(&self.encoder);
+        self.finish_internal()?;
+
+        if !color_map {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Palette images require a color map",
+            )
+            .into());
+        }
+
+        if !complete {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Not all tiles of the image were written",
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, W: Write + Seek, C: ColorType, K: TiffKind, D: Compression> Drop
+    for TileImageEncoder<'a, W, C, K, D>
+{
+    fn drop(&mut self) {
+        if !self.dropped {
+            let _ = self.finish_internal();
+        }
+    }
+}
+
+// We use BTreeMap to make sure tags are written in correct order
+type DirectoryEntries<S> = BTreeMap<u16, DirectoryEntry<S>>;
+
+#[derive(Clone)]
 struct DirectoryEntry<S> {
     data_type: u16,
     count: S,
     data: Vec<u8>,
 }
 
+impl<S: TryFrom<usize, Error = TryFromIntError>> DirectoryEntry<S> {
+    fn new<T: TiffValue>(value: T) -> TiffResult<Self> {
+        let mut bytes = Vec::with_capacity(value.bytes());
+        {
+            let mut writer = TiffWriter::new(&mut bytes);
+            value.write(&mut writer)?;
+        }
+
+        Ok(DirectoryEntry {
+            data_type: <T>::FIELD_TYPE.to_u16(),
+            count: value.count().try_into()?,
+            data: bytes,
+        })
+    }
+}
+
 /// Trait to abstract over Tiff/BigTiff differences.
 ///
-/// Implemented for [`TiffKindStandard`] and [`TiffKindBig`].
-pub trait TiffKind {
+/// Implemented for [`TiffKindStandard`] and [`TiffKindBig`]. The trait is sealed, it can not be
+/// implemented outside of this crate.
+pub trait TiffKind: private::Sealed {
     /// The type of offset fields, `u32` for normal Tiff, `u64` for BigTiff.
-    type OffsetType: TryFrom<usize, Error = TryFromIntError> + Into<u64> + TiffValue;
+    type OffsetType: TryFrom<usize, Error = TryFromIntError> + Into<u64> + TiffValue + Clone;
 
     /// Needed for the `convert_slice` method.
     type OffsetArrayType: ?Sized + TiffValue;
@@ -672,3 +1608,51 @@ impl TiffKind for TiffKindBig {
         slice
     }
 }
+
+mod private {
+    use super::{Ifd, Ifd8, TiffKindBig, TiffKindStandard, TiffResult, TiffValue};
+    use std::convert::TryFrom;
+
+    /// The differences of Tiff and BigTiff that only the encoder needs.
+    pub trait Sealed {
+        /// The type of tags pointing to an IFD, `Ifd` for normal Tiff, `Ifd8` for BigTiff.
+        type IfdPointerType: TiffValue;
+
+        /// Needed for the `convert_ifd_pointer_slice` method.
+        type IfdPointerArrayType: ?Sized + TiffValue;
+
+        /// Convert the offset of an IFD to `Self::IfdPointerType`.
+        ///
+        /// Like `convert_offset`, this errors if `offset > u32::MAX` for normal Tiff.
+        fn convert_ifd_pointer(offset: u64) -> TiffResult<Self::IfdPointerType>;
+
+        /// Like `convert_slice`, makes slices of `IfdPointerType` usable with `write_tag`.
+        fn convert_ifd_pointer_slice(slice: &[Self::IfdPointerType]) -> &Self::IfdPointerArrayType;
+    }
+
+    impl Sealed for TiffKindStandard {
+        type IfdPointerType = Ifd;
+        type IfdPointerArrayType = [Ifd];
+
+        fn convert_ifd_pointer(offset: u64) -> TiffResult<Self::IfdPointerType> {
+            Ok(Ifd(u32::try_from(offset)?))
+        }
+
+        fn convert_ifd_pointer_slice(slice: &[Self::IfdPointerType]) -> &Self::IfdPointerArrayType {
+            slice
+        }
+    }
+
+    impl Sealed for TiffKindBig {
+        type IfdPointerType = Ifd8;
+        type IfdPointerArrayType = [Ifd8];
+
+        fn convert_ifd_pointer(offset: u64) -> TiffResult<Self::IfdPointerType> {
+            Ok(Ifd8(offset))
+        }
+
+        fn convert_ifd_pointer_slice(slice: &[Self::IfdPointerType]) -> &Self::IfdPointerArrayType {
+            slice
+        }
+    }
+}
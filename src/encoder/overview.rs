@@ -0,0 +1,140 @@
+//! Overviews, the reduced resolution levels of an image pyramid
+//!
+//! See [`ImageEncoder::write_data_with_overviews`][super::ImageEncoder::write_data_with_overviews].
+
+/// The overviews written after an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overviews {
+    /// The number of overviews, each half the width and height of the previous level, rounded up.
+    pub levels: usize,
+    /// The filter computing the samples of each level from the previous one.
+    pub resampling: Resampling,
+    /// Where the overviews are stored in the file.
+    pub layout: OverviewLayout,
+}
+
+/// The filter computing the samples of an overview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resampling {
+    /// Each sample is the top left sample of its block of 2x2 samples.
+    Nearest,
+    /// Each sample is the mean of its block of 2x2 samples.
+    Average,
+    /// Each sample is a weighted mean of the 4x4 samples around its block of 2x2 samples, with
+    /// the binomial weights `1 3 3 1` approximating a Gaussian in each direction.
+    Gaussian,
+}
+
+/// Where the overviews of an image are stored in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverviewLayout {
+    /// The overviews follow the image in the main chain of directories, as in Cloud Optimized
+    /// GeoTIFFs.
+    Chained,
+    /// The overviews are listed in the `SubIFDs` tag of the image, as in OME-TIFF pyramids.
+    SubIfds,
+}
+
+/// A sample type whose values can be interpolated to compute overviews.
+pub trait Sample: Copy {
+    /// Converts the sample to a floating point value.
+    fn to_f64(self) -> f64;
+    /// Converts an interpolated value back, rounding and saturating integers.
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! integer_sample {
+    ($($ty:ty),*) => {
+        $(
+            impl Sample for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $ty
+                }
+            }
+        )*
+    };
+}
+
+integer_sample!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Sample for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Sample for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// Computes the next level of a pyramid from the chunky samples of an image.
+///
+/// Returns the samples and the dimensions of the level.
+pub(crate) fn downsample<S: Sample>(
+    data: &[S],
+    (width, height): (usize, usize),
+    samples: usize,
+    resampling: Resampling,
+) -> (Vec<S>, (usize, usize)) {
+    let dimensions = (width - width / 2, height - height / 2);
+    let mut level = Vec::with_capacity(dimensions.0 * dimensions.1 * samples);
+
+    // The offsets of the samples around the first sample of a block and their weights.
+    let taps: &[(isize, f64)] = match resampling {
+        // The samples are copied, as interpolating would round 64 bit integers above 2^53.
+        Resampling::Nearest => {
+            for y in 0..dimensions.1 {
+                for x in 0..dimensions.0 {
+                    let start = (y * 2 * width + x * 2) * samples;
+                    level.extend_from_slice(&data[start..][..samples]);
+                }
+            }
+            return (level, dimensions);
+        }
+        Resampling::Average => &[(0, 1.0), (1, 1.0)],
+        Resampling::Gaussian => &[(-1, 1.0), (0, 3.0), (1, 3.0), (2, 1.0)],
+    };
+    // Taps outside of the image are left out, the weights of the others are normalized.
+    let source = |position: usize, len: usize| {
+        taps.iter().filter_map(move |&(offset, weight)| {
+            let position = (position * 2) as isize + offset;
+            if position >= 0 && (position as usize) < len {
+                Some((position as usize, weight))
+            } else {
+                None
+            }
+        })
+    };
+
+    for y in 0..dimensions.1 {
+        for x in 0..dimensions.0 {
+            for sample in 0..samples {
+                let (mut sum, mut weights) = (0.0, 0.0);
+                for (row, row_weight) in source(y, height) {
+                    for (column, weight) in source(x, width) {
+                        let value = data[(row * width + column) * samples + sample].to_f64();
+                        sum += value * row_weight * weight;
+                        weights += row_weight * weight;
+                    }
+                }
+                level.push(S::from_f64(sum / weights));
+            }
+        }
+    }
+
+    (level, dimensions)
+}
@@ -0,0 +1,116 @@
+//! Predictors applied to the image data before compression
+use std::{convert::TryInto, io, mem};
+
+use crate::{
+    encoder::colortype::ColorType, error::TiffResult, tags::Predictor, tags::SampleFormat,
+};
+
+/// Checks that the predictor can be applied to samples of the color type.
+pub fn check_predictor<T: ColorType>(predictor: Predictor) -> TiffResult<()> {
+    match predictor {
+        Predictor::None => Ok(()),
+        Predictor::Horizontal if T::BITS_PER_SAMPLE.iter().any(|&bits| bits < 8) => {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Horizontal predictor requires samples of at least 8 bits",
+            )
+            .into())
+        }
+        Predictor::Horizontal
+            if T::SAMPLE_FORMAT
+                .iter()
+                .all(|format| *format == SampleFormat::Uint || *format == SampleFormat::Int) =>
+        {
+            Ok(())
+        }
+        Predictor::Horizontal => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Horizontal predictor requires integer samples",
+        )
+        .into()),
+        Predictor::FloatingPoint
+            if T::SAMPLE_FORMAT
+                .iter()
+                .all(|format| *format == SampleFormat::IEEEFP) =>
+        {
+            Ok(())
+        }
+        Predictor::FloatingPoint => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Floating point predictor requires floating point samples",
+        )
+        .into()),
+        Predictor::__NonExhaustive => unreachable!(),
+    }
+}
+
+macro_rules! horizontal_differencing {
+    ($name:ident, $int:ty) => {
+        fn $name(row: &mut [u8], samples: usize) {
+            const LEN: usize = mem::size_of::<$int>();
+            // Proceed from right to left, so the preceding sample is still unmodified.
+            for i in (samples..row.len() / LEN).rev() {
+                let prev = &row[(i - samples) * LEN..][..LEN];
+                let prev = <$int>::from_ne_bytes(prev.try_into().unwrap());
+                let sample = &mut row[i * LEN..][..LEN];
+                let diff = <$int>::from_ne_bytes((&*sample).try_into().unwrap()).wrapping_sub(prev);
+                sample.copy_from_slice(&diff.to_ne_bytes());
+            }
+        }
+    };
+}
+
+horizontal_differencing!(difference_u8, u8);
+horizontal_differencing!(difference_u16, u16);
+horizontal_differencing!(difference_u32, u32);
+horizontal_differencing!(difference_u64, u64);
+
+/// Replaces each sample by its difference to the same sample of the preceding pixel in the row.
+///
+/// The data holds rows of `row_bytes` bytes each, made up of native endian integers of
+/// `byte_len` bytes with `samples` samples per pixel. Signed samples are differenced like
+/// unsigned ones, the wrapping arithmetic yields the same bits.
+pub fn predict_horizontal(data: &mut [u8], row_bytes: usize, samples: usize, byte_len: u8) {
+    let difference = match byte_len {
+        1 => difference_u8,
+        2 => difference_u16,
+        4 => difference_u32,
+        8 => difference_u64,
+        _ => unreachable!(),
+    };
+
+    for row in data.chunks_mut(row_bytes) {
+        difference(row, samples);
+    }
+}
+
+/// Applies the floating point predictor of Adobe's Technical Note 3.
+///
+/// The bytes of each row are rearranged into planes, beginning with the most significant byte
+/// of every sample, and then differenced bytewise like a horizontal predictor. The data holds
+/// rows of `row_bytes` bytes each, made up of native endian floats of `byte_len` bytes with
+/// `samples` samples per pixel.
+pub fn predict_floating_point(data: &mut [u8], row_bytes: usize, samples: usize, byte_len: u8) {
+    let byte_len = usize::from(byte_len);
+    let row_samples = row_bytes / byte_len;
+    let mut shuffled = vec![0; row_bytes];
+
+    for row in data.chunks_mut(row_bytes) {
+        for (i, sample) in row.chunks(byte_len).enumerate() {
+            for (n, byte) in sample.iter().enumerate() {
+                let plane = if cfg!(target_endian = "little") {
+                    byte_len - 1 - n
+                } else {
+                    n
+                };
+                shuffled[plane * row_samples + i] = *byte;
+            }
+        }
+
+        for i in (samples..row.len()).rev() {
+            shuffled[i] = shuffled[i].wrapping_sub(shuffled[i - samples]);
+        }
+
+        row.copy_from_slice(&shuffled[..row.len()]);
+    }
+}
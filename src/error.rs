@@ -60,6 +60,7 @@ pub enum TiffFormatError {
     InvalidTagValueType(Tag),
     RequiredTagNotFound(Tag),
     UnknownPredictor(u16),
+    UnknownFillOrder(u16),
     ByteExpected(Value),
     UnsignedIntegerExpected(Value),
     SignedIntegerExpected(Value),
@@ -108,6 +109,9 @@ impl fmt::Display for TiffFormatError {
             UnknownPredictor(ref predictor) => {
                 write!(fmt, "Unknown predictor “{}” encountered", predictor)
             }
+            UnknownFillOrder(ref fill_order) => {
+                write!(fmt, "Unknown fill order “{}” encountered", fill_order)
+            }
             ByteExpected(ref val) => write!(fmt, "Expected byte, {:?} found.", val),
             UnsignedIntegerExpected(ref val) => {
                 write!(fmt, "Expected unsigned integer, {:?} found.", val)
@@ -135,6 +139,7 @@ impl fmt::Display for TiffFormatError {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TiffUnsupportedError {
     HorizontalPredictor(ColorType),
+    FloatingPointPredictor(ColorType),
     InterpretationWithBits(PhotometricInterpretation, Vec<u8>),
     UnknownInterpretation,
     UnknownCompressionMethod,
@@ -159,6 +164,11 @@ impl fmt::Display for TiffUnsupportedError {
                 "Horizontal predictor for {:?} is unsupported.",
                 color_type
             ),
+            FloatingPointPredictor(color_type) => write!(
+                fmt,
+                "Floating point predictor for {:?} is unsupported.",
+                color_type
+            ),
             InterpretationWithBits(ref photometric_interpretation, ref bits_per_sample) => write!(
                 fmt,
                 "{:?} with {:?} bits per sample is unsupported",
@@ -196,9 +206,14 @@ impl fmt::Display for TiffUnsupportedError {
 /// User attempted to use the Decoder in a way that is incompatible with a specific image.
 ///
 /// For example: attempting to read a tile from a stripped image.
+///
+/// The list of variants may grow, matches on it need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum UsageError {
     InvalidChunkType(ChunkType, ChunkType),
+    InvalidChunkIndex(u32, u32),
+    InvalidRegion((u32, u32), (u32, u32)),
 }
 
 impl fmt::Display for UsageError {
@@ -212,6 +227,16 @@ impl fmt::Display for UsageError {
                     expected, actual
                 )
             }
+            InvalidChunkIndex(index, count) => write!(
+                fmt,
+                "Image chunk index {} out of range (image has {} chunks).",
+                index, count
+            ),
+            InvalidRegion((x, y), (width, height)) => write!(
+                fmt,
+                "Region of size {}x{} at ({}, {}) exceeds the image.",
+                width, height, x, y
+            ),
         }
     }
 }
@@ -0,0 +1,158 @@
+//! The EXIF and GPS tags of photographs
+//!
+//! Cameras store the settings of a shot in the private IFD of the `ExifIFD` tag and the position
+//! it was taken at in the private IFD of the `GPSInfo` tag. Both IFDs hold tags without image
+//! data.
+//!
+//! See the [EXIF specification](https://www.cipa.jp/std/documents/download_e.html?DC-008-Translation-2019-E)
+//! for reference.
+use std::collections::HashMap;
+
+use crate::decoder::ifd::Value;
+use crate::tags::{GpsTag, Tag};
+
+/// The tags of the EXIF IFD.
+///
+/// Returned by [`Decoder::exif`][crate::decoder::Decoder::exif].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Exif {
+    /// The values of all entries of the IFD.
+    pub entries: HashMap<Tag, Value>,
+}
+
+impl Exif {
+    /// The value of a tag.
+    pub fn get(&self, tag: Tag) -> Option<&Value> {
+        self.entries.get(&tag)
+    }
+
+    /// The exposure time in seconds.
+    pub fn exposure_time(&self) -> Option<f64> {
+        self.get(Tag::ExposureTime).and_then(float)
+    }
+
+    /// The ratio of the focal length to the diameter of the aperture.
+    pub fn f_number(&self) -> Option<f64> {
+        self.get(Tag::FNumber).and_then(float)
+    }
+
+    /// The exposure bias in EV.
+    pub fn exposure_bias(&self) -> Option<f64> {
+        self.get(Tag::ExposureBiasValue).and_then(float)
+    }
+
+    /// The ISO speed of the sensor.
+    pub fn iso_speed(&self) -> Option<u32> {
+        match self.get(Tag::ISOSpeedRatings)? {
+            Value::List(values) => values.first().and_then(unsigned),
+            value => unsigned(value),
+        }
+    }
+
+    /// The focal length of the lens in millimeters.
+    pub fn focal_length(&self) -> Option<f64> {
+        self.get(Tag::FocalLength).and_then(float)
+    }
+
+    /// The focal length of a lens with the same angle of view on 35 mm film, in millimeters.
+    pub fn focal_length_in_35mm_film(&self) -> Option<u32> {
+        self.get(Tag::FocalLengthIn35mmFilm).and_then(unsigned)
+    }
+
+    /// The date and time the photograph was taken, as `YYYY:MM:DD HH:MM:SS`.
+    pub fn date_time_original(&self) -> Option<&str> {
+        self.get(Tag::DateTimeOriginal).and_then(ascii)
+    }
+}
+
+/// The tags of the GPS IFD.
+///
+/// Returned by [`Decoder::gps`][crate::decoder::Decoder::gps].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gps {
+    /// The values of all entries of the IFD.
+    pub entries: HashMap<GpsTag, Value>,
+}
+
+impl Gps {
+    /// The value of a tag.
+    pub fn get(&self, tag: GpsTag) -> Option<&Value> {
+        self.entries.get(&tag)
+    }
+
+    /// The latitude in decimal degrees, negative in the southern hemisphere.
+    pub fn latitude(&self) -> Option<f64> {
+        self.degrees(GpsTag::Latitude, GpsTag::LatitudeRef, "S")
+    }
+
+    /// The longitude in decimal degrees, negative west of the prime meridian.
+    pub fn longitude(&self) -> Option<f64> {
+        self.degrees(GpsTag::Longitude, GpsTag::LongitudeRef, "W")
+    }
+
+    /// The altitude in meters, negative below sea level.
+    pub fn altitude(&self) -> Option<f64> {
+        let altitude = self.get(GpsTag::Altitude).and_then(float)?;
+        match self.get(GpsTag::AltitudeRef).and_then(unsigned) {
+            Some(1) => Some(-altitude),
+            _ => Some(altitude),
+        }
+    }
+
+    /// Converts the degrees, minutes and seconds of a coordinate into decimal degrees.
+    fn degrees(&self, tag: GpsTag, reference: GpsTag, negative: &str) -> Option<f64> {
+        let values = match self.get(tag)? {
+            Value::List(values) => values.iter().map(float).collect::<Option<Vec<_>>>()?,
+            value => vec![float(value)?],
+        };
+        let degrees = values
+            .iter()
+            .zip(&[1.0, 60.0, 3600.0])
+            .map(|(value, unit)| value / unit)
+            .sum::<f64>();
+
+        match self.get(reference).and_then(ascii) {
+            Some(reference) if reference.eq_ignore_ascii_case(negative) => Some(-degrees),
+            _ => Some(degrees),
+        }
+    }
+}
+
+/// Converts a numeric value, `None` for rationals with a zero denominator.
+fn float(value: &Value) -> Option<f64> {
+    match *value {
+        Value::Byte(value) => Some(value.into()),
+        Value::Short(value) => Some(value.into()),
+        Value::Signed(value) => Some(value.into()),
+        Value::SignedBig(value) => Some(value as f64),
+        Value::Unsigned(value) => Some(value.into()),
+        Value::UnsignedBig(value) => Some(value as f64),
+        Value::Float(value) => Some(value.into()),
+        Value::Double(value) => Some(value),
+        Value::Rational(_, 0)
+        | Value::RationalBig(_, 0)
+        | Value::SRational(_, 0)
+        | Value::SRationalBig(_, 0) => None,
+        Value::Rational(n, d) => Some(f64::from(n) / f64::from(d)),
+        Value::RationalBig(n, d) => Some(n as f64 / d as f64),
+        Value::SRational(n, d) => Some(f64::from(n) / f64::from(d)),
+        Value::SRationalBig(n, d) => Some(n as f64 / d as f64),
+        _ => None,
+    }
+}
+
+fn unsigned(value: &Value) -> Option<u32> {
+    match *value {
+        Value::Byte(value) => Some(value.into()),
+        Value::Short(value) => Some(value.into()),
+        Value::Unsigned(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn ascii(value: &Value) -> Option<&str> {
+    match value {
+        Value::Ascii(value) => Some(value),
+        _ => None,
+    }
+}
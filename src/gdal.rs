@@ -0,0 +1,359 @@
+//! The metadata tags written by GDAL
+//!
+//! The `GDAL_NODATA` tag holds the value of the samples of pixels without data as text. The
+//! `GDAL_METADATA` tag holds an XML document of metadata items, which mostly describe the
+//! samples of each band.
+//!
+//! See the [GDAL GeoTIFF driver](https://gdal.org/drivers/raster/gtiff.html) for reference.
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::decoder::DecodingResult;
+use crate::tags::{SampleFormat, Tag};
+use crate::{TiffFormatError, TiffResult};
+
+/// The value of the samples of pixels without data, in the type of the samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoData {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+}
+
+impl NoData {
+    /// Parses the value of a `GDAL_NODATA` tag for samples of the given format and bits.
+    ///
+    /// Returns `None` for values outside of the range of the samples, which GDAL ignores as
+    /// well, and for samples of other sizes than 8, 16, 32 and 64 bits.
+    pub(crate) fn parse(value: &str, format: SampleFormat, bits: u8) -> TiffResult<Option<Self>> {
+        let value = value.trim();
+        let float =
+            parse_float(value).ok_or(TiffFormatError::InvalidTagValueType(Tag::GdalNodata))?;
+
+        Ok(match (format, bits) {
+            (SampleFormat::Uint, 8) => integer(value, float).map(NoData::U8),
+            (SampleFormat::Uint, 16) => integer(value, float).map(NoData::U16),
+            (SampleFormat::Uint, 32) => integer(value, float).map(NoData::U32),
+            (SampleFormat::Uint, 64) => integer(value, float).map(NoData::U64),
+            (SampleFormat::Int, 8) => integer(value, float).map(NoData::I8),
+            (SampleFormat::Int, 16) => integer(value, float).map(NoData::I16),
+            (SampleFormat::Int, 32) => integer(value, float).map(NoData::I32),
+            (SampleFormat::Int, 64) => integer(value, float).map(NoData::I64),
+            (SampleFormat::IEEEFP, 32) => Some(NoData::F32(float as f32)),
+            (SampleFormat::IEEEFP, 64) => Some(NoData::F64(float)),
+            _ => None,
+        })
+    }
+
+    /// Whether each of the samples is the nodata value, with all NaN values being equal.
+    ///
+    /// Samples of another type than the nodata value are never equal to it.
+    pub fn mask(&self, samples: &DecodingResult) -> Vec<bool> {
+        macro_rules! mask {
+            ($buf:expr, $value:expr) => {
+                $buf.iter().map(|sample| *sample == $value).collect()
+            };
+        }
+
+        match (*self, samples) {
+            (NoData::U8(value), DecodingResult::U8(buf)) => mask!(buf, value),
+            (NoData::U16(value), DecodingResult::U16(buf)) => mask!(buf, value),
+            (NoData::U32(value), DecodingResult::U32(buf)) => mask!(buf, value),
+            (NoData::U64(value), DecodingResult::U64(buf)) => mask!(buf, value),
+            (NoData::I8(value), DecodingResult::I8(buf)) => mask!(buf, value),
+            (NoData::I16(value), DecodingResult::I16(buf)) => mask!(buf, value),
+            (NoData::I32(value), DecodingResult::I32(buf)) => mask!(buf, value),
+            (NoData::I64(value), DecodingResult::I64(buf)) => mask!(buf, value),
+            (NoData::F32(value), DecodingResult::F32(buf)) if value.is_nan() => {
+                buf.iter().map(|sample| sample.is_nan()).collect()
+            }
+            (NoData::F32(value), DecodingResult::F32(buf)) => mask!(buf, value),
+            (NoData::F64(value), DecodingResult::F64(buf)) if value.is_nan() => {
+                buf.iter().map(|sample| sample.is_nan()).collect()
+            }
+            (NoData::F64(value), DecodingResult::F64(buf)) => mask!(buf, value),
+            _ => vec![false; samples.len()],
+        }
+    }
+}
+
+/// Formats the value as written to the `GDAL_NODATA` tag.
+impl fmt::Display for NoData {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NoData::U8(value) => write!(fmt, "{}", value),
+            NoData::U16(value) => write!(fmt, "{}", value),
+            NoData::U32(value) => write!(fmt, "{}", value),
+            NoData::U64(value) => write!(fmt, "{}", value),
+            NoData::I8(value) => write!(fmt, "{}", value),
+            NoData::I16(value) => write!(fmt, "{}", value),
+            NoData::I32(value) => write!(fmt, "{}", value),
+            NoData::I64(value) => write!(fmt, "{}", value),
+            NoData::F32(value) => write_float(fmt, f64::from(value)),
+            NoData::F64(value) => write_float(fmt, value),
+        }
+    }
+}
+
+fn write_float(fmt: &mut fmt::Formatter, value: f64) -> fmt::Result {
+    if value.is_nan() {
+        write!(fmt, "nan")
+    } else {
+        write!(fmt, "{}", value)
+    }
+}
+
+/// Parses a floating point number, accepting the spellings of NaN and infinity used by C.
+fn parse_float(value: &str) -> Option<f64> {
+    let value = value.to_ascii_lowercase();
+    match value.trim_start_matches('+') {
+        "nan" | "-nan" => Some(f64::NAN),
+        "inf" | "infinity" => Some(f64::INFINITY),
+        "-inf" | "-infinity" => Some(f64::NEG_INFINITY),
+        value => value.parse().ok(),
+    }
+}
+
+/// Parses an integer, which may also be written as a floating point number without fraction.
+fn integer<T: FromStr + TryFrom<i128>>(value: &str, float: f64) -> Option<T> {
+    value.parse().ok().or_else(|| {
+        if float.fract() == 0.0 {
+            T::try_from(float as i128).ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// An item of the `GDAL_METADATA` tag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MetadataItem {
+    /// The name of the item, like `SCALE` or `STATISTICS_MEAN`.
+    pub name: String,
+    /// The band the item describes, `None` for items of the whole image.
+    pub sample: Option<usize>,
+    /// The role of the item, like `scale` or `description`.
+    pub role: Option<String>,
+    /// The domain of the item, `None` for the default domain.
+    pub domain: Option<String>,
+    pub value: String,
+}
+
+/// The statistics of the samples of a band.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+}
+
+/// The metadata items of the `GDAL_METADATA` tag.
+///
+/// Returned by [`Decoder::gdal_metadata`][crate::decoder::Decoder::gdal_metadata] and written
+/// with [`ImageEncoder::gdal_metadata`][crate::encoder::ImageEncoder::gdal_metadata].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GdalMetadata {
+    pub items: Vec<MetadataItem>,
+}
+
+impl GdalMetadata {
+    /// Parses the XML document of a `GDAL_METADATA` tag.
+    pub fn from_xml(xml: &str) -> TiffResult<Self> {
+        let invalid = || TiffFormatError::InvalidTagValueType(Tag::GdalMetadata);
+        let mut items = Vec::new();
+
+        let mut rest = xml;
+        while let Some(start) = rest.find("<Item") {
+            rest = &rest[start + "<Item".len()..];
+            let end = rest.find('>').ok_or_else(invalid)?;
+            let (attributes, content) = (&rest[..end], &rest[end + 1..]);
+
+            let mut item = MetadataItem::default();
+            rest = match attributes.strip_suffix('/') {
+                Some(_) => content,
+                None => {
+                    let close = content.find("</Item>").ok_or_else(invalid)?;
+                    item.value = unescape(&content[..close]);
+                    &content[close + "</Item>".len()..]
+                }
+            };
+
+            let mut attributes = attributes.trim_end_matches('/');
+            while let Some(equals) = attributes.find('=') {
+                let name = attributes[..equals].trim();
+                let value = attributes[equals + 1..].trim_start();
+                let quote = value.chars().next().filter(|&c| c == '"' || c == '\'');
+                let quote = quote.ok_or_else(invalid)?;
+                let len = value[1..].find(quote).ok_or_else(invalid)?;
+                let value = unescape(&value[1..1 + len]);
+                attributes = &attributes[equals + 1..].trim_start()[len + 2..];
+
+                match name {
+                    "name" => item.name = value,
+                    "sample" => item.sample = Some(value.parse().map_err(|_| invalid())?),
+                    "role" => item.role = Some(value),
+                    "domain" => item.domain = Some(value),
+                    _ => {}
+                }
+            }
+            items.push(item);
+        }
+
+        Ok(GdalMetadata { items })
+    }
+
+    /// The XML document of the `GDAL_METADATA` tag holding the items.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<GDALMetadata>\n");
+        for item in &self.items {
+            xml.push_str(&format!("  <Item name=\"{}\"", escape(&item.name)));
+            if let Some(sample) = item.sample {
+                xml.push_str(&format!(" sample=\"{}\"", sample));
+            }
+            if let Some(role) = &item.role {
+                xml.push_str(&format!(" role=\"{}\"", escape(role)));
+            }
+            if let Some(domain) = &item.domain {
+                xml.push_str(&format!(" domain=\"{}\"", escape(domain)));
+            }
+            xml.push_str(&format!(">{}</Item>\n", escape(&item.value)));
+        }
+        xml.push_str("</GDALMetadata>");
+        xml
+    }
+
+    /// The value of the item of the default domain with the given name.
+    ///
+    /// Items describing the band of the given sample are found with `Some(sample)`, those of the
+    /// whole image with `None`.
+    pub fn item(&self, name: &str, sample: Option<usize>) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|item| item.name == name && item.sample == sample && item.domain.is_none())
+            .map(|item| item.value.as_str())
+    }
+
+    /// The scale of the samples of a band, the factor of the value of a sample.
+    ///
+    /// The value of a sample is `sample * scale + offset`.
+    pub fn scale(&self, sample: usize) -> Option<f64> {
+        self.number("SCALE", sample)
+    }
+
+    /// The offset of the samples of a band, added to the value of a sample.
+    ///
+    /// The value of a sample is `sample * scale + offset`.
+    pub fn offset(&self, sample: usize) -> Option<f64> {
+        self.number("OFFSET", sample)
+    }
+
+    /// The description of a band.
+    pub fn description(&self, sample: usize) -> Option<&str> {
+        self.item("DESCRIPTION", Some(sample))
+    }
+
+    /// The statistics of the samples of a band.
+    pub fn statistics(&self, sample: usize) -> Statistics {
+        Statistics {
+            minimum: self.number("STATISTICS_MINIMUM", sample),
+            maximum: self.number("STATISTICS_MAXIMUM", sample),
+            mean: self.number("STATISTICS_MEAN", sample),
+            std_dev: self.number("STATISTICS_STDDEV", sample),
+        }
+    }
+
+    fn number(&self, name: &str, sample: usize) -> Option<f64> {
+        self.item(name, Some(sample))
+            .and_then(|value| value.trim().parse().ok())
+    }
+
+    /// Sets the value of the item of the default domain with the given name.
+    pub fn with_item(mut self, name: &str, sample: Option<usize>, value: &str) -> Self {
+        self.set(name, sample, None, value.to_string());
+        self
+    }
+
+    /// Sets the scale of the samples of a band.
+    pub fn with_scale(mut self, sample: usize, scale: f64) -> Self {
+        self.set("SCALE", Some(sample), Some("scale"), scale.to_string());
+        self
+    }
+
+    /// Sets the offset of the samples of a band.
+    pub fn with_offset(mut self, sample: usize, offset: f64) -> Self {
+        self.set("OFFSET", Some(sample), Some("offset"), offset.to_string());
+        self
+    }
+
+    /// Sets the description of a band.
+    pub fn with_description(mut self, sample: usize, description: &str) -> Self {
+        let description = description.to_string();
+        self.set(
+            "DESCRIPTION",
+            Some(sample),
+            Some("description"),
+            description,
+        );
+        self
+    }
+
+    /// Sets the statistics of the samples of a band, leaving out missing values.
+    pub fn with_statistics(mut self, sample: usize, statistics: Statistics) -> Self {
+        let values = [
+            ("STATISTICS_MINIMUM", statistics.minimum),
+            ("STATISTICS_MAXIMUM", statistics.maximum),
+            ("STATISTICS_MEAN", statistics.mean),
+            ("STATISTICS_STDDEV", statistics.std_dev),
+        ];
+        for &(name, value) in &values {
+            if let Some(value) = value {
+                self.set(name, Some(sample), None, value.to_string());
+            }
+        }
+        self
+    }
+
+    fn set(&mut self, name: &str, sample: Option<usize>, role: Option<&str>, value: String) {
+        let position = self
+            .items
+            .iter()
+            .position(|item| item.name == name && item.sample == sample && item.domain.is_none());
+        let item = MetadataItem {
+            name: name.to_string(),
+            sample,
+            role: role.map(str::to_string),
+            domain: None,
+            value,
+        };
+        match position {
+            Some(position) => self.items[position] = item,
+            None => self.items.push(item),
+        }
+    }
+}
+
+/// Replaces the characters with a special meaning in XML by entity references.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Replaces the predefined entity references of XML by their characters.
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
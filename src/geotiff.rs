@@ -0,0 +1,371 @@
+//! GeoTIFF georeferencing of images
+//!
+//! The keys of the `GeoKeyDirectoryTag` describe the coordinate system of an image, their values
+//! are stored in the directory itself or in the `GeoDoubleParamsTag` and `GeoAsciiParamsTag`.
+//! The model tags map the raster coordinates of the pixels into that coordinate system.
+//!
+//! See the [GeoTIFF specification](http://geotiff.maptools.org/spec/geotiffhome.html).
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::tags::{GeoKey, ModelType, RasterType, Tag};
+use crate::{TiffFormatError, TiffResult};
+
+/// The value of a GeoKey.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeoKeyValue {
+    /// Short values, stored in the key directory.
+    Short(Vec<u16>),
+    /// Double values, stored in the `GeoDoubleParamsTag`.
+    Double(Vec<f64>),
+    /// A string, stored in the `GeoAsciiParamsTag`.
+    Ascii(String),
+}
+
+/// The georeferencing of an image, from its GeoTIFF tags.
+///
+/// Returned by [`Decoder::geotiff`][crate::decoder::Decoder::geotiff] and written with
+/// [`ImageEncoder::geotiff`][crate::encoder::ImageEncoder::geotiff].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoTiff {
+    /// The keys of the `GeoKeyDirectoryTag` with their values.
+    pub keys: HashMap<GeoKey, GeoKeyValue>,
+    /// The tiepoints of the `ModelTiepointTag`, each the raster coordinates `(i, j, k)` of a
+    /// point followed by its model coordinates `(x, y, z)`.
+    pub tiepoints: Vec<[f64; 6]>,
+    /// The size of a pixel in model coordinates, from the `ModelPixelScaleTag`.
+    pub pixel_scale: Option<[f64; 3]>,
+    /// The matrix mapping raster coordinates to model coordinates in row-major order, from the
+    /// `ModelTransformationTag`.
+    pub transformation: Option<[f64; 16]>,
+}
+
+impl GeoTiff {
+    /// Creates the georeferencing from the values of the GeoTIFF tags.
+    pub(crate) fn from_tags(
+        directory: Option<Vec<u16>>,
+        doubles: Option<Vec<f64>>,
+        ascii: Option<String>,
+        tiepoints: Option<Vec<f64>>,
+        pixel_scale: Option<Vec<f64>>,
+        transformation: Option<Vec<f64>>,
+    ) -> TiffResult<Self> {
+        let keys = match directory {
+            Some(directory) => parse_keys(
+                &directory,
+                &doubles.unwrap_or_default(),
+                &ascii.unwrap_or_default(),
+            )?,
+            None => HashMap::new(),
+        };
+
+        let tiepoints = match tiepoints {
+            Some(values) => {
+                let chunks = values.chunks_exact(6);
+                if !chunks.remainder().is_empty() {
+                    return Err(TiffFormatError::InvalidTagValueType(Tag::ModelTiepointTag).into());
+                }
+                chunks
+                    .map(|chunk| <[f64; 6]>::try_from(chunk).unwrap())
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        let pixel_scale = pixel_scale
+            .map(|values| <[f64; 3]>::try_from(&values[..]))
+            .transpose()
+            .map_err(|_| TiffFormatError::InvalidTagValueType(Tag::ModelPixelScaleTag))?;
+        let transformation = transformation
+            .map(|values| <[f64; 16]>::try_from(&values[..]))
+            .transpose()
+            .map_err(|_| TiffFormatError::InvalidTagValueType(Tag::ModelTransformationTag))?;
+
+        Ok(GeoTiff {
+            keys,
+            tiepoints,
+            pixel_scale,
+            transformation,
+        })
+    }
+
+    /// The georeferencing of a resampled image, whose pixels span `scale` pixels of this image
+    /// horizontally and vertically.
+    pub(crate) fn resampled(&self, (x_scale, y_scale): (f64, f64)) -> Self {
+        let mut geotiff = self.clone();
+        for tiepoint in geotiff.tiepoints.iter_mut() {
+            tiepoint[0] /= x_scale;
+            tiepoint[1] /= y_scale;
+        }
+        if let Some(ref mut pixel_scale) = geotiff.pixel_scale {
+            pixel_scale[0] *= x_scale;
+            pixel_scale[1] *= y_scale;
+        }
+        if let Some(ref mut transformation) = geotiff.transformation {
+            for row in transformation.chunks_exact_mut(4) {
+                row[0] *= x_scale;
+                row[1] *= y_scale;
+            }
+        }
+        geotiff
+    }
+
+    /// Sets the EPSG code of a projected coordinate system, like 32633 for UTM zone 33N.
+    pub fn with_projected_epsg(self, code: u16) -> Self {
+        self.with_short(GeoKey::GTModelType, ModelType::Projected.to_u16())
+            .with_short(GeoKey::ProjectedCSType, code)
+    }
+
+    /// Sets the EPSG code of a geographic coordinate system, like 4326 for WGS 84.
+    pub fn with_geographic_epsg(self, code: u16) -> Self {
+        self.with_short(GeoKey::GTModelType, ModelType::Geographic.to_u16())
+            .with_short(GeoKey::GeographicType, code)
+    }
+
+    /// Sets what the raster coordinates refer to.
+    pub fn with_raster_type(self, raster_type: RasterType) -> Self {
+        self.with_short(GeoKey::GTRasterType, raster_type.to_u16())
+    }
+
+    /// Sets the description of the coordinate system.
+    pub fn with_citation(self, citation: &str) -> Self {
+        self.with_key(GeoKey::GTCitation, GeoKeyValue::Ascii(citation.to_string()))
+    }
+
+    /// Sets the value of a key.
+    pub fn with_key(mut self, key: GeoKey, value: GeoKeyValue) -> Self {
+        self.keys.insert(key, value);
+        self
+    }
+
+    fn with_short(self, key: GeoKey, value: u16) -> Self {
+        self.with_key(key, GeoKeyValue::Short(vec![value]))
+    }
+
+    /// Adds a tiepoint, the raster coordinates `(i, j, k)` of a point followed by its model
+    /// coordinates `(x, y, z)`.
+    pub fn with_tiepoint(mut self, tiepoint: [f64; 6]) -> Self {
+        self.tiepoints.push(tiepoint);
+        self
+    }
+
+    /// Sets the size of a pixel in model coordinates.
+    pub fn with_pixel_scale(mut self, pixel_scale: [f64; 3]) -> Self {
+        self.pixel_scale = Some(pixel_scale);
+        self
+    }
+
+    /// Sets the affine transform of raster coordinates to model coordinates.
+    ///
+    /// The transform is given as returned by [`transform`][GeoTiff::transform]. Like GDAL,
+    /// north up transforms without rotation are stored as a tiepoint and the pixel scale, others
+    /// as the transformation matrix, since a pixel scale can not hold a flipped y axis. This
+    /// replaces any previous tiepoints, pixel scale and matrix.
+    pub fn with_transform(mut self, transform: [f64; 6]) -> Self {
+        let [a, b, c, d, e, f] = transform;
+        if b == 0.0 && d == 0.0 && e < 0.0 {
+            self.tiepoints = vec![[0.0, 0.0, 0.0, c, f, 0.0]];
+            self.pixel_scale = Some([a, -e, 0.0]);
+            self.transformation = None;
+        } else {
+            self.tiepoints = Vec::new();
+            self.pixel_scale = None;
+            #[rustfmt::skip]
+            let matrix = [
+                a, b, 0.0, c,
+                d, e, 0.0, f,
+                0.0, 0.0, 0.0, 0.0,
+                0.0, 0.0, 0.0, 1.0,
+            ];
+            self.transformation = Some(matrix);
+        }
+        self
+    }
+
+    /// The values of the `GeoKeyDirectoryTag`, `GeoDoubleParamsTag` and `GeoAsciiParamsTag`
+    /// holding the keys.
+    pub(crate) fn key_directory(&self) -> TiffResult<(Vec<u16>, Vec<f64>, String)> {
+        // The keys are sorted by their id.
+        let mut keys: Vec<_> = self.keys.iter().collect();
+        keys.sort_by_key(|(key, _)| key.to_u16());
+
+        // Version 1, key revision 1.0, followed by the entries and the short params.
+        let mut directory = vec![1, 1, 0, u16::try_from(keys.len())?];
+        let mut shorts = Vec::new();
+        let mut doubles = Vec::new();
+        let mut ascii = String::new();
+        let shorts_start = 4 + 4 * keys.len();
+
+        for (key, value) in keys {
+            let (location, count, offset) = match value {
+                GeoKeyValue::Short(values) if values.len() == 1 => (0, 1, usize::from(values[0])),
+                GeoKeyValue::Short(values) => {
+                    shorts.extend_from_slice(values);
+                    let offset = shorts_start + shorts.len() - values.len();
+                    (Tag::GeoKeyDirectoryTag.to_u16(), values.len(), offset)
+                }
+                GeoKeyValue::Double(values) => {
+                    doubles.extend_from_slice(values);
+                    let offset = doubles.len() - values.len();
+                    (Tag::GeoDoubleParamsTag.to_u16(), values.len(), offset)
+                }
+                GeoKeyValue::Ascii(value) => {
+                    let offset = ascii.len();
+                    ascii.push_str(value);
+                    ascii.push('|');
+                    (Tag::GeoAsciiParamsTag.to_u16(), value.len() + 1, offset)
+                }
+            };
+            directory.extend_from_slice(&[
+                key.to_u16(),
+                location,
+                u16::try_from(count)?,
+                u16::try_from(offset)?,
+            ]);
+        }
+        directory.extend_from_slice(&shorts);
+
+        Ok((directory, doubles, ascii))
+    }
+
+    /// The single short value of a key.
+    pub fn short(&self, key: GeoKey) -> Option<u16> {
+        match self.keys.get(&key) {
+            Some(GeoKeyValue::Short(values)) if values.len() == 1 => Some(values[0]),
+            _ => None,
+        }
+    }
+
+    /// The single double value of a key.
+    pub fn double(&self, key: GeoKey) -> Option<f64> {
+        match self.keys.get(&key) {
+            Some(GeoKeyValue::Double(values)) if values.len() == 1 => Some(values[0]),
+            _ => None,
+        }
+    }
+
+    /// The string value of a key.
+    pub fn ascii(&self, key: GeoKey) -> Option<&str> {
+        match self.keys.get(&key) {
+            Some(GeoKeyValue::Ascii(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The kind of coordinate system.
+    pub fn model_type(&self) -> Option<ModelType> {
+        self.short(GeoKey::GTModelType)
+            .and_then(ModelType::from_u16)
+    }
+
+    /// What the raster coordinates refer to.
+    ///
+    /// Without a `GTRasterType` key this is usually taken to be `PixelIsArea`.
+    pub fn raster_type(&self) -> Option<RasterType> {
+        self.short(GeoKey::GTRasterType)
+            .and_then(RasterType::from_u16)
+    }
+
+    /// The EPSG code of the coordinate system.
+    ///
+    /// This is the code of the projected coordinate system, or of the geographic coordinate
+    /// system for images without one. The user-defined code 32767 is not returned.
+    pub fn epsg(&self) -> Option<u16> {
+        let epsg = |key| self.short(key).filter(|&code| code != USER_DEFINED);
+        epsg(GeoKey::ProjectedCSType).or_else(|| epsg(GeoKey::GeographicType))
+    }
+
+    /// The description of the coordinate system from the `GTCitation` key.
+    pub fn citation(&self) -> Option<&str> {
+        self.ascii(GeoKey::GTCitation)
+    }
+
+    /// The EPSG code of the linear unit of the coordinate system, like 9001 for metres.
+    ///
+    /// This is the unit of the projected coordinate system, or of the geographic coordinate
+    /// system for images without one.
+    pub fn linear_units(&self) -> Option<u16> {
+        self.short(GeoKey::ProjLinearUnits)
+            .or_else(|| self.short(GeoKey::GeogLinearUnits))
+    }
+
+    /// The affine transform of raster coordinates to model coordinates.
+    ///
+    /// Returns `[a, b, c, d, e, f]` mapping the raster coordinates `(i, j)` to the model
+    /// coordinates `x = a * i + b * j + c` and `y = d * i + e * j + f`. The transform is taken
+    /// from the transformation matrix, or computed from the first tiepoint and the pixel scale.
+    pub fn transform(&self) -> Option<[f64; 6]> {
+        if let Some(m) = self.transformation {
+            return Some([m[0], m[1], m[3], m[4], m[5], m[7]]);
+        }
+
+        let [i, j, _, x, y, _] = *self.tiepoints.first()?;
+        let [scale_x, scale_y, _] = self.pixel_scale?;
+        // Rows go down while model coordinates go up.
+        Some([
+            scale_x,
+            0.0,
+            x - i * scale_x,
+            0.0,
+            -scale_y,
+            y + j * scale_y,
+        ])
+    }
+
+    /// Maps raster coordinates to model coordinates with the [`transform`][GeoTiff::transform].
+    pub fn pixel_to_model(&self, i: f64, j: f64) -> Option<(f64, f64)> {
+        let [a, b, c, d, e, f] = self.transform()?;
+        Some((a * i + b * j + c, d * i + e * j + f))
+    }
+}
+
+/// The code of user-defined coordinate systems and units.
+const USER_DEFINED: u16 = 32767;
+
+/// Parses the keys of a `GeoKeyDirectoryTag`, resolving the references into the params tags.
+fn parse_keys(
+    directory: &[u16],
+    doubles: &[f64],
+    ascii: &str,
+) -> TiffResult<HashMap<GeoKey, GeoKeyValue>> {
+    let invalid = || TiffFormatError::InvalidTagValueType(Tag::GeoKeyDirectoryTag);
+
+    // The header holds the version, the key revision, the minor revision and the number of keys.
+    let count = usize::from(*directory.get(3).ok_or_else(invalid)?);
+    let entries = directory.get(4..4 + count * 4).ok_or_else(invalid)?;
+
+    let mut keys = HashMap::with_capacity(count);
+    for entry in entries.chunks(4) {
+        let key = GeoKey::from_u16_exhaustive(entry[0]);
+        let (offset, len) = (usize::from(entry[3]), usize::from(entry[2]));
+        let range = offset..offset + len;
+
+        let value = match Tag::from_u16_exhaustive(entry[1]) {
+            // Single short values are stored in place of the offset, with no tag location.
+            Tag::Unknown(0) => GeoKeyValue::Short(vec![entry[3]]),
+            Tag::GeoKeyDirectoryTag => {
+                let values = directory.get(range).ok_or_else(invalid)?;
+                GeoKeyValue::Short(values.to_vec())
+            }
+            Tag::GeoDoubleParamsTag => {
+                let values = doubles
+                    .get(range)
+                    .ok_or(TiffFormatError::InvalidTagValueType(
+                        Tag::GeoDoubleParamsTag,
+                    ))?;
+                GeoKeyValue::Double(values.to_vec())
+            }
+            Tag::GeoAsciiParamsTag => {
+                let value = ascii
+                    .get(range)
+                    .ok_or(TiffFormatError::InvalidTagValueType(Tag::GeoAsciiParamsTag))?;
+                // Strings are terminated by a `|`, which is not part of the value.
+                let value = value.strip_suffix('|').unwrap_or(value);
+                GeoKeyValue::Ascii(value.to_string())
+            }
+            _ => return Err(invalid().into()),
+        };
+        keys.insert(key, value);
+    }
+
+    Ok(keys)
+}
@@ -9,16 +9,23 @@
 extern crate jpeg;
 extern crate weezl;
 
+mod bitpack;
 mod bytecast;
 pub mod decoder;
 pub mod encoder;
 mod error;
+pub mod exif;
+pub mod gdal;
+pub mod geotiff;
 pub mod tags;
 
 pub use self::error::{TiffError, TiffFormatError, TiffResult, TiffUnsupportedError, UsageError};
 
 /// An enumeration over supported color types and their bit depths
+///
+/// More color types may be added in minor versions, matches have to include a wildcard arm.
 #[derive(Copy, PartialEq, Eq, Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum ColorType {
     /// Pixel is grayscale
     Gray(u8),
@@ -37,4 +44,26 @@ pub enum ColorType {
 
     /// Pixel is CMYK
     CMYK(u8),
+
+    /// Pixel is YCbCr
+    YCbCr(u8),
+
+    /// Pixel is CIE L*a*b*
+    Lab(u8),
+
+    /// Pixel is CIE XYZ
+    XYZ(u8),
+
+    /// Pixel has multiple samples of the same bit depth, beyond those of a known color type
+    ///
+    /// The meaning of the samples following the color samples is described by
+    /// [`Decoder::extra_samples`](decoder::Decoder::extra_samples).
+    Multiband { bit_depth: u8, num_samples: u16 },
+
+    /// Pixel has samples of different bit depths or sample formats
+    ///
+    /// The samples are described by [`Decoder::bits_per_sample`](decoder::Decoder::bits_per_sample)
+    /// and [`Decoder::sample_formats`](decoder::Decoder::sample_formats) and can only be decoded
+    /// with [`Decoder::read_image_channels`](decoder::Decoder::read_image_channels).
+    Mixed { num_samples: u16 },
 }
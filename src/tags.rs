@@ -79,12 +79,12 @@ pub enum Tag(u16) unknown("A private or extension tag") {
     CellLength = 265, // TODO add support
     CellWidth = 264, // TODO add support
     // palette-color images (PhotometricInterpretation 3)
-    ColorMap = 320, // TODO add support
+    ColorMap = 320,
     Compression = 259, // TODO add support for 2 and 32773
     Copyright = 33_432,
     DateTime = 306,
-    ExtraSamples = 338, // TODO add support
-    FillOrder = 266, // TODO add support
+    ExtraSamples = 338,
+    FillOrder = 266,
     FreeByteCounts = 289, // TODO add support
     FreeOffsets = 288, // TODO add support
     GrayResponseCurve = 291, // TODO add support
@@ -97,8 +97,8 @@ pub enum Tag(u16) unknown("A private or extension tag") {
     MaxSampleValue = 281, // TODO add support
     MinSampleValue = 280, // TODO add support
     Model = 272,
-    NewSubfileType = 254, // TODO add support
-    Orientation = 274, // TODO add support
+    NewSubfileType = 254,
+    Orientation = 274,
     PhotometricInterpretation = 262,
     PlanarConfiguration = 284,
     ResolutionUnit = 296, // TODO add support
@@ -107,11 +107,14 @@ pub enum Tag(u16) unknown("A private or extension tag") {
     Software = 305,
     StripByteCounts = 279,
     StripOffsets = 273,
-    SubfileType = 255, // TODO add support
+    SubfileType = 255,
+    SubIFDs = 330,
     Threshholding = 263, // TODO add support
     XResolution = 282,
     YResolution = 283,
     // Advanced tags
+    T4Options = 292,
+    T6Options = 293,
     Predictor = 317,
     TileWidth = 322,
     TileLength = 323,
@@ -121,6 +124,12 @@ pub enum Tag(u16) unknown("A private or extension tag") {
     SampleFormat = 339,
     SMinSampleValue = 340, // TODO add support
     SMaxSampleValue = 341, // TODO add support
+    // Colorimetry
+    WhitePoint = 318,
+    // YCbCr
+    YCbCrCoefficients = 529,
+    YCbCrSubSampling = 530,
+    ReferenceBlackWhite = 532,
     // JPEG
     JPEGTables = 347,
     // GeoTIFF
@@ -130,7 +139,120 @@ pub enum Tag(u16) unknown("A private or extension tag") {
     GeoKeyDirectoryTag = 34735, // (SPOT)
     GeoDoubleParamsTag = 34736, // (SPOT)
     GeoAsciiParamsTag = 34737, // (SPOT)
+    GdalMetadata = 42112, // XML of band metadata
     GdalNodata = 42113, // Contains areas with missing data
+    // Private IFDs
+    ExifIFD = 34665,
+    GPSInfo = 34853,
+    InteroperabilityIFD = 40965,
+    // EXIF tags, stored in the IFD of the ExifIFD tag
+    ExposureTime = 33434,
+    FNumber = 33437,
+    ExposureProgram = 34850,
+    SpectralSensitivity = 34852,
+    ISOSpeedRatings = 34855,
+    OECF = 34856,
+    ExifVersion = 36864,
+    DateTimeOriginal = 36867,
+    DateTimeDigitized = 36868,
+    OffsetTime = 36880,
+    OffsetTimeOriginal = 36881,
+    OffsetTimeDigitized = 36882,
+    ComponentsConfiguration = 37121,
+    CompressedBitsPerPixel = 37122,
+    ShutterSpeedValue = 37377,
+    ApertureValue = 37378,
+    BrightnessValue = 37379,
+    ExposureBiasValue = 37380,
+    MaxApertureValue = 37381,
+    SubjectDistance = 37382,
+    MeteringMode = 37383,
+    LightSource = 37384,
+    Flash = 37385,
+    FocalLength = 37386,
+    SubjectArea = 37396,
+    MakerNote = 37500,
+    UserComment = 37510,
+    SubSecTime = 37520,
+    SubSecTimeOriginal = 37521,
+    SubSecTimeDigitized = 37522,
+    FlashpixVersion = 40960,
+    ColorSpace = 40961,
+    PixelXDimension = 40962,
+    PixelYDimension = 40963,
+    RelatedSoundFile = 40964,
+    FlashEnergy = 41483,
+    SpatialFrequencyResponse = 41484,
+    FocalPlaneXResolution = 41486,
+    FocalPlaneYResolution = 41487,
+    FocalPlaneResolutionUnit = 41488,
+    SubjectLocation = 41492,
+    ExposureIndex = 41493,
+    SensingMethod = 41495,
+    FileSource = 41728,
+    SceneType = 41729,
+    CFAPattern = 41730,
+    CustomRendered = 41985,
+    ExposureMode = 41986,
+    WhiteBalance = 41987,
+    DigitalZoomRatio = 41988,
+    FocalLengthIn35mmFilm = 41989,
+    SceneCaptureType = 41990,
+    GainControl = 41991,
+    Contrast = 41992,
+    Saturation = 41993,
+    Sharpness = 41994,
+    DeviceSettingDescription = 41995,
+    SubjectDistanceRange = 41996,
+    ImageUniqueID = 42016,
+    CameraOwnerName = 42032,
+    BodySerialNumber = 42033,
+    LensSpecification = 42034,
+    LensMake = 42035,
+    LensModel = 42036,
+    LensSerialNumber = 42037,
+}
+}
+
+tags! {
+/// The tags of the GPS IFD, which is pointed to by the `GPSInfo` tag.
+///
+/// Their numbers are only meaningful within that IFD. See the
+/// [EXIF specification](https://www.cipa.jp/std/documents/download_e.html?DC-008-Translation-2019-E)
+/// for reference.
+pub enum GpsTag(u16) unknown("A private or unknown GPS tag") {
+    VersionID = 0,
+    LatitudeRef = 1,
+    Latitude = 2,
+    LongitudeRef = 3,
+    Longitude = 4,
+    AltitudeRef = 5,
+    Altitude = 6,
+    TimeStamp = 7,
+    Satellites = 8,
+    Status = 9,
+    MeasureMode = 10,
+    DOP = 11,
+    SpeedRef = 12,
+    Speed = 13,
+    TrackRef = 14,
+    Track = 15,
+    ImgDirectionRef = 16,
+    ImgDirection = 17,
+    MapDatum = 18,
+    DestLatitudeRef = 19,
+    DestLatitude = 20,
+    DestLongitudeRef = 21,
+    DestLongitude = 22,
+    DestBearingRef = 23,
+    DestBearing = 24,
+    DestDistanceRef = 25,
+    DestDistance = 26,
+    ProcessingMethod = 27,
+    AreaInformation = 28,
+    DateStamp = 29,
+    Differential = 30,
+    HPositioningError = 31,
 }
 }
 
@@ -187,6 +309,92 @@ pub enum CompressionMethod(u16) {
     Deflate = 8,
     OldDeflate = 0x80B2,
     PackBits = 0x8005,
+    SGILog = 34676,
+    SGILog24 = 34677,
+}
+}
+
+tags! {
+/// The order of the bits within the bytes of the image data.
+///
+/// See [TIFF FillOrder tag](https://www.awaresystems.be/imaging/tiff/tifftags/fillorder.html)
+/// for reference.
+pub enum FillOrder(u16) {
+    /// Pixels with lower column values are stored in the higher-order bits of the byte.
+    MsbToLsb = 1,
+    /// Pixels with lower column values are stored in the lower-order bits of the byte.
+    LsbToMsb = 2,
+}
+}
+
+/// The kind of data of an image, from the flags of the `NewSubfileType` tag.
+///
+/// See [TIFF NewSubfileType tag](https://www.awaresystems.be/imaging/tiff/tifftags/newsubfiletype.html)
+/// for reference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SubfileType {
+    /// The image is a reduced resolution version of another image, like an overview.
+    pub reduced_resolution: bool,
+    /// The image is a single page of a multi-page image.
+    pub page: bool,
+    /// The image is a transparency mask for another image.
+    pub mask: bool,
+}
+
+impl SubfileType {
+    /// Parses the flags of a `NewSubfileType` value, ignoring unknown flags.
+    pub fn from_u32(value: u32) -> SubfileType {
+        SubfileType {
+            reduced_resolution: value & 1 != 0,
+            page: value & 2 != 0,
+            mask: value & 4 != 0,
+        }
+    }
+
+    /// Parses a value of the deprecated `SubfileType` tag.
+    pub fn from_subfile_type(value: u16) -> Option<SubfileType> {
+        match value {
+            1 => Some(SubfileType::default()),
+            2 => Some(SubfileType {
+                reduced_resolution: true,
+                ..SubfileType::default()
+            }),
+            3 => Some(SubfileType {
+                page: true,
+                ..SubfileType::default()
+            }),
+            _ => None,
+        }
+    }
+
+    /// The `NewSubfileType` value of the flags.
+    pub fn to_u32(&self) -> u32 {
+        u32::from(self.reduced_resolution) | u32::from(self.page) << 1 | u32::from(self.mask) << 2
+    }
+}
+
+tags! {
+/// The position of the first row and column of the image data when the image is displayed.
+///
+/// See [TIFF Orientation tag](https://www.awaresystems.be/imaging/tiff/tifftags/orientation.html)
+/// for reference.
+pub enum Orientation(u16) {
+    /// The first row is the top and the first column the left side of the image.
+    TopLeft = 1,
+    /// The first row is the top and the first column the right side of the image.
+    TopRight = 2,
+    /// The first row is the bottom and the first column the right side of the image.
+    BottomRight = 3,
+    /// The first row is the bottom and the first column the left side of the image.
+    BottomLeft = 4,
+    /// The first row is the left side and the first column the top of the image.
+    LeftTop = 5,
+    /// The first row is the right side and the first column the top of the image.
+    RightTop = 6,
+    /// The first row is the right side and the first column the bottom of the image.
+    RightBottom = 7,
+    /// The first row is the left side and the first column the bottom of the image.
+    LeftBottom = 8,
 }
 }
 
@@ -200,6 +408,9 @@ pub enum PhotometricInterpretation(u16) {
     CMYK = 5,
     YCbCr = 6,
     CIELab = 8,
+    ICCLab = 9,
+    LogL = 32844,
+    LogLuv = 32845,
 }
 }
 
@@ -214,6 +425,7 @@ tags! {
 pub enum Predictor(u16) {
     None = 1,
     Horizontal = 2,
+    FloatingPoint = 3,
 }
 }
 
@@ -226,6 +438,21 @@ pub enum ResolutionUnit(u16) {
 }
 }
 
+tags! {
+/// The meaning of the extra samples of a pixel, following the samples of its color space.
+///
+/// See [TIFF ExtraSamples tag](https://www.awaresystems.be/imaging/tiff/tifftags/extrasamples.html)
+/// for reference.
+pub enum ExtraSamples(u16) unknown("An unknown extension meaning of the samples") {
+    /// The samples have no specified meaning.
+    Unspecified = 0,
+    /// Alpha samples the color samples have been multiplied with, also called premultiplied alpha.
+    AssociatedAlpha = 1,
+    /// Alpha samples independent of the color samples.
+    UnassociatedAlpha = 2,
+}
+}
+
 tags! {
 pub enum SampleFormat(u16) unknown("An unknown extension sample format") {
     Uint = 1,
@@ -234,3 +461,85 @@ pub enum SampleFormat(u16) unknown("An unknown extension sample format") {
     Void = 4,
 }
 }
+
+tags! {
+/// The keys of the GeoTIFF `GeoKeyDirectoryTag`.
+///
+/// See [GeoTIFF GeoKeys](http://geotiff.maptools.org/spec/geotiff6.html#6.2) for reference.
+pub enum GeoKey(u16) unknown("A private or unknown GeoKey") {
+    // Configuration keys
+    GTModelType = 1024,
+    GTRasterType = 1025,
+    GTCitation = 1026,
+    // Geographic coordinate system keys
+    GeographicType = 2048,
+    GeogCitation = 2049,
+    GeogGeodeticDatum = 2050,
+    GeogPrimeMeridian = 2051,
+    GeogLinearUnits = 2052,
+    GeogLinearUnitSize = 2053,
+    GeogAngularUnits = 2054,
+    GeogAngularUnitSize = 2055,
+    GeogEllipsoid = 2056,
+    GeogSemiMajorAxis = 2057,
+    GeogSemiMinorAxis = 2058,
+    GeogInvFlattening = 2059,
+    GeogAzimuthUnits = 2060,
+    GeogPrimeMeridianLong = 2061,
+    GeogTOWGS84 = 2062,
+    // Projected coordinate system keys
+    ProjectedCSType = 3072,
+    PCSCitation = 3073,
+    Projection = 3074,
+    ProjCoordTrans = 3075,
+    ProjLinearUnits = 3076,
+    ProjLinearUnitSize = 3077,
+    ProjStdParallel1 = 3078,
+    ProjStdParallel2 = 3079,
+    ProjNatOriginLong = 3080,
+    ProjNatOriginLat = 3081,
+    ProjFalseEasting = 3082,
+    ProjFalseNorthing = 3083,
+    ProjFalseOriginLong = 3084,
+    ProjFalseOriginLat = 3085,
+    ProjFalseOriginEasting = 3086,
+    ProjFalseOriginNorthing = 3087,
+    ProjCenterLong = 3088,
+    ProjCenterLat = 3089,
+    ProjCenterEasting = 3090,
+    ProjCenterNorthing = 3091,
+    ProjScaleAtNatOrigin = 3092,
+    ProjScaleAtCenter = 3093,
+    ProjAzimuthAngle = 3094,
+    ProjStraightVertPoleLong = 3095,
+    // Vertical coordinate system keys
+    VerticalCSType = 4096,
+    VerticalCitation = 4097,
+    VerticalDatum = 4098,
+    VerticalUnits = 4099,
+}
+}
+
+tags! {
+/// The kind of coordinate system of a GeoTIFF, from its `GTModelType` key.
+pub enum ModelType(u16) {
+    /// A projected coordinate system, like UTM.
+    Projected = 1,
+    /// A geographic coordinate system of latitudes and longitudes.
+    Geographic = 2,
+    /// A geocentric cartesian coordinate system.
+    Geocentric = 3,
+}
+}
+
+tags! {
+/// What the raster coordinates of a GeoTIFF refer to, from its `GTRasterType` key.
+pub enum RasterType(u16) {
+    /// A pixel covers the area between its raster coordinates and those of the next pixels, the
+    /// raster coordinates `(0, 0)` are the top left corner of the image.
+    PixelIsArea = 1,
+    /// A pixel is a point sample at its raster coordinates, the raster coordinates `(0, 0)` are
+    /// the center of the top left pixel.
+    PixelIsPoint = 2,
+}
+}
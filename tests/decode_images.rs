@@ -1,6 +1,7 @@
 extern crate tiff;
 
-use tiff::decoder::{ifd, Decoder, DecodingResult};
+use tiff::decoder::{ifd, Decoder, DecodingBuffer, DecodingResult, PaletteOutput, YCbCrOutput};
+use tiff::tags::{GpsTag, SubfileType, Tag};
 use tiff::ColorType;
 
 use std::fs::File;
@@ -37,22 +38,6 @@ test_image_sum!(test_image_sum_u64, U64, u64);
 test_image_sum!(test_image_sum_f32, F32, f32);
 test_image_sum!(test_image_sum_f64, F64, f64);
 
-/// Tests that a decoder can be constructed for an image and the color type
-/// read from the IFD and is of the appropriate type, but the type is
-/// unsupported.
-fn test_image_color_type_unsupported(file: &str, expected_type: ColorType) {
-    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
-    let img_file = File::open(path).expect("Cannot find test image!");
-    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
-    assert_eq!(decoder.colortype().unwrap(), expected_type);
-    assert!(match decoder.read_image() {
-        Err(tiff::TiffError::UnsupportedError(
-            tiff::TiffUnsupportedError::UnsupportedColorType(_),
-        )) => true,
-        _ => false,
-    });
-}
-
 #[test]
 fn test_cmyk_u8() {
     test_image_sum_u8("cmyk-3c-8b.tiff", ColorType::CMYK(8), 8522658);
@@ -75,7 +60,7 @@ fn test_gray_u8() {
 
 #[test]
 fn test_gray_u12() {
-    test_image_color_type_unsupported("12bit.cropped.tiff", ColorType::Gray(12));
+    test_image_sum_u16("12bit.cropped.tiff", ColorType::Gray(12), 10973);
 }
 
 #[test]
@@ -114,7 +99,7 @@ fn test_rgb_u8() {
 
 #[test]
 fn test_rgb_u12() {
-    test_image_color_type_unsupported("12bit.cropped.rgb.tiff", ColorType::RGB(12));
+    test_image_sum_u16("12bit.cropped.rgb.tiff", ColorType::RGB(12), 32919);
 }
 
 #[test]
@@ -137,6 +122,59 @@ fn test_rgb_f32() {
     test_image_sum_f32("gradient-3c-32b-float.tiff", ColorType::RGB(32), 472.8405);
 }
 
+fn test_palette(file: &str, bits: u8, color_map_sum: u64, rgb8_sum: u64, rgb16_sum: u64) {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_palette_output(PaletteOutput::RGB8);
+    assert_eq!(decoder.colortype().unwrap(), ColorType::Palette(bits));
+    let color_map = decoder.color_map().unwrap();
+    assert_eq!(color_map.len(), 3 << bits);
+    assert_eq!(
+        color_map.iter().copied().map(u64::from).sum::<u64>(),
+        color_map_sum
+    );
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => {
+            assert_eq!(image.len(), 157 * 151 * 3);
+            assert_eq!(image.into_iter().map(u64::from).sum::<u64>(), rgb8_sum);
+        }
+        _ => panic!("Wrong data type"),
+    }
+
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_palette_output(PaletteOutput::RGB16);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U16(image) => {
+            assert_eq!(image.into_iter().map(u64::from).sum::<u64>(), rgb16_sum);
+        }
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_palette_indices() {
+    test_image_sum_u8("palette-1c-1b.tiff", ColorType::Palette(1), 11853);
+    test_image_sum_u8("palette-1c-4b.tiff", ColorType::Palette(4), 173645);
+    test_image_sum_u8("palette-1c-8b.tiff", ColorType::Palette(8), 2876948);
+}
+
+#[test]
+fn test_palette_1b() {
+    test_palette("palette-1c-1b.tiff", 1, 196605, 9067545, 2330359065);
+}
+
+#[test]
+fn test_palette_4b() {
+    test_palette("palette-1c-4b.tiff", 4, 1414523, 7898113, 2029579950);
+}
+
+#[test]
+fn test_palette_8b() {
+    test_palette("palette-1c-8b.tiff", 8, 23310678, 7876384, 2025707778);
+}
+
 #[test]
 fn test_int8() {
     test_image_sum_i8("int8.tif", ColorType::Gray(8), 3111)
@@ -205,16 +243,16 @@ fn issue_69() {
     test_image_sum_u16("issue_69_packbits.tiff", ColorType::Gray(16), 1015486);
 }
 
-// TODO: GrayA support
-//#[test]
-//fn test_gray_alpha_u8()
-//{
-//let img_file = File::open("./tests/images/minisblack-2c-8b-alpha.tiff").expect("Cannot find test image!");
-//let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
-//assert_eq!(decoder.colortype().unwrap(), ColorType::GrayA(8));
-//let img_res = decoder.read_image();
-//assert!(img_res.is_ok());
-//}
+#[test]
+fn test_gray_alpha_u8() {
+    use tiff::tags::ExtraSamples;
+
+    test_image_sum_u8("minisblack-2c-8b-alpha.tiff", ColorType::GrayA(8), 740303);
+
+    let img_file = File::open("./tests/images/minisblack-2c-8b-alpha.tiff").unwrap();
+    let decoder = Decoder::new(img_file).unwrap();
+    assert_eq!(decoder.extra_samples(), [ExtraSamples::AssociatedAlpha]);
+}
 
 #[test]
 fn test_tiled_rgb_u8() {
@@ -226,6 +264,26 @@ fn test_tiled_rect_rgb_u8() {
     test_image_sum_u8("tiled-rect-rgb-u8.tif", ColorType::RGB(8), 62081032);
 }
 
+#[test]
+fn test_ycbcr_jpeg() {
+    // JPEG compressed YCbCr images decode to RGB.
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("ycbcr-3c-8b-jpeg.tiff");
+    let mut decoder = Decoder::new(File::open(path).unwrap()).unwrap();
+    assert_eq!(decoder.colortype().unwrap(), ColorType::RGB(8));
+    let image = match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => image,
+        _ => panic!("Wrong data type"),
+    };
+    assert_eq!(image.len(), 16 * 16 * 3);
+    for (i, rgb) in image.chunks(3).enumerate() {
+        let (x, y) = (i % 16, i / 16);
+        let expected = [(x * 8 + 40) as u8, (y * 8 + 40) as u8, 120];
+        for (&sample, &expected) in rgb.iter().zip(&expected) {
+            assert!((i32::from(sample) - i32::from(expected)).abs() <= 12);
+        }
+    }
+}
+
 /* #[test]
 fn test_tiled_jpeg_rgb_u8() {
     test_image_sum_u8("tiled-jpeg-rgb-u8.tif", ColorType::RGB(8), 93031606);
@@ -285,6 +343,1375 @@ fn test_tiled_incremental() {
     }
 }
 
+#[test]
+fn test_tiled_random_access() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("tiled-rgb-u8.tif");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    let tiles = decoder.tile_count().unwrap();
+    let mut sequential = Vec::new();
+    for _ in 0..tiles {
+        sequential.push(decoder.read_tile().unwrap());
+    }
+
+    for tile in (0..tiles).rev() {
+        match (
+            decoder.read_chunk(tile).unwrap(),
+            &sequential[tile as usize],
+        ) {
+            (DecodingResult::U8(res), DecodingResult::U8(expected)) => assert_eq!(&res, expected),
+            _ => panic!("Wrong bit depth"),
+        }
+    }
+}
+
+#[test]
+fn test_chunk_geometry() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("tiled-rect-rgb-u8.tif");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    assert_eq!(decoder.dimensions().unwrap(), (490, 367));
+    assert_eq!(decoder.chunk_count().unwrap(), 48);
+    assert_eq!(decoder.chunk_dimensions().unwrap(), (32, 128));
+    assert_eq!(decoder.chunk_origin(0).unwrap(), (0, 0));
+    assert_eq!(decoder.chunk_data_dimensions(0).unwrap(), (32, 128));
+    assert_eq!(decoder.chunk_origin(17).unwrap(), (32, 128));
+    assert_eq!(decoder.chunk_origin(47).unwrap(), (480, 256));
+    assert_eq!(decoder.chunk_data_dimensions(47).unwrap(), (10, 111));
+    match decoder.read_chunk(47).unwrap() {
+        DecodingResult::U8(res) => assert_eq!(res.len(), 10 * 111 * 3),
+        _ => panic!("Wrong bit depth"),
+    }
+    assert!(decoder.chunk_origin(48).is_err());
+    match decoder.read_chunk(48) {
+        Err(tiff::TiffError::UsageError(error)) => assert_eq!(
+            error.to_string(),
+            "Image chunk index 48 out of range (image has 48 chunks)."
+        ),
+        _ => panic!("Expected a usage error"),
+    }
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    assert_eq!(decoder.chunk_count().unwrap(), 3);
+    assert_eq!(decoder.chunk_dimensions().unwrap(), (157, 52));
+    assert_eq!(decoder.chunk_origin(2).unwrap(), (0, 104));
+    assert_eq!(decoder.chunk_data_dimensions(2).unwrap(), (157, 47));
+}
+
+#[test]
+fn test_stripped_random_access() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    let image = match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => res,
+        _ => panic!("Wrong bit depth"),
+    };
+
+    let strip_len = 157 * 52;
+    for strip in (0..decoder.chunk_count().unwrap()).rev() {
+        let start = strip as usize * strip_len;
+        let expected = &image[start..image.len().min(start + strip_len)];
+
+        let mut buffer = vec![0; expected.len()];
+        decoder
+            .read_chunk_to_buffer(strip, DecodingBuffer::U8(&mut buffer))
+            .unwrap();
+        assert_eq!(&buffer[..], expected);
+    }
+}
+
+fn test_region_matches_image(file: &str, regions: &[(u32, u32, u32, u32)]) {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    let (width, _) = decoder.dimensions().unwrap();
+    let samples = match decoder.colortype().unwrap() {
+        ColorType::Gray(_) => 1,
+        ColorType::RGB(_) => 3,
+        _ => panic!("Unexpected color type"),
+    };
+    let image = match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => res,
+        _ => panic!("Wrong bit depth"),
+    };
+
+    for &(x, y, w, h) in regions {
+        let mut expected = Vec::new();
+        for line in y..y + h {
+            let start = (line * width + x) as usize * samples;
+            expected.extend_from_slice(&image[start..start + w as usize * samples]);
+        }
+
+        match decoder.read_region(x, y, w, h).unwrap() {
+            DecodingResult::U8(res) => assert_eq!(res, expected),
+            _ => panic!("Wrong bit depth"),
+        }
+    }
+}
+
+#[test]
+fn test_tiled_region() {
+    test_region_matches_image(
+        "tiled-rect-rgb-u8.tif",
+        &[
+            (0, 0, 490, 367),
+            (20, 100, 50, 60),
+            (470, 300, 20, 67),
+            (33, 129, 1, 1),
+        ],
+    );
+    // Tiles with a horizontal predictor
+    test_region_matches_image(
+        "tiled-rgb-u8.tif",
+        &[(10, 10, 100, 100), (300, 450, 74, 49)],
+    );
+}
+
+#[test]
+fn test_stripped_region() {
+    test_region_matches_image(
+        "minisblack-1c-8b.tiff",
+        &[(0, 0, 157, 151), (10, 40, 30, 100), (156, 150, 1, 1)],
+    );
+}
+
+#[test]
+fn test_region_out_of_bounds() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    match decoder.read_region(100, 100, 58, 10) {
+        Err(tiff::TiffError::UsageError(tiff::UsageError::InvalidRegion(..))) => {}
+        unexpected => panic!("Unexpected result {:?}", unexpected),
+    }
+
+    let mut buffer = vec![0; 10];
+    assert!(decoder
+        .read_region_to_buffer(0, 0, 4, 4, DecodingBuffer::U8(&mut buffer))
+        .is_err());
+    let mut buffer = vec![0; 16];
+    assert!(decoder
+        .read_region_to_buffer(0, 0, 4, 4, DecodingBuffer::U16(&mut buffer))
+        .is_err());
+}
+
+/// Compares regions and the first chunk of an image decoded with conversions to the whole image.
+fn test_converted_region(
+    file: &str,
+    options: fn(Decoder<File>) -> Decoder<File>,
+    regions: &[(u32, u32, u32, u32)],
+) {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let mut decoder = options(Decoder::new(File::open(path).unwrap()).unwrap());
+
+    let (width, height) = decoder.dimensions().unwrap();
+    let image = match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => res,
+        _ => panic!("Wrong data type"),
+    };
+    let samples = image.len() / (width * height) as usize;
+    let region = |x: u32, y: u32, w: u32, h: u32| {
+        let mut expected = Vec::new();
+        for line in y..y + h {
+            let start = (line * width + x) as usize * samples;
+            expected.extend_from_slice(&image[start..start + w as usize * samples]);
+        }
+        expected
+    };
+
+    for &(x, y, w, h) in regions {
+        match decoder.read_region(x, y, w, h).unwrap() {
+            DecodingResult::U8(res) => assert_eq!(res, region(x, y, w, h)),
+            _ => panic!("Wrong data type"),
+        }
+
+        let mut buffer = vec![0; (w * h) as usize * samples];
+        decoder
+            .read_region_to_buffer(x, y, w, h, DecodingBuffer::U8(&mut buffer))
+            .unwrap();
+        assert_eq!(buffer, region(x, y, w, h));
+        buffer.pop();
+        assert!(decoder
+            .read_region_to_buffer(x, y, w, h, DecodingBuffer::U8(&mut buffer))
+            .is_err());
+    }
+
+    let (w, h) = decoder.chunk_data_dimensions(0).unwrap();
+    match decoder.read_chunk(0).unwrap() {
+        DecodingResult::U8(res) => assert_eq!(res, region(0, 0, w, h)),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_palette_region() {
+    fn rgb8(decoder: Decoder<File>) -> Decoder<File> {
+        decoder.with_palette_output(PaletteOutput::RGB8)
+    }
+    test_converted_region(
+        "palette-1c-8b.tiff",
+        rgb8,
+        &[(0, 0, 4, 4), (0, 0, 2, 2), (100, 30, 57, 121)],
+    );
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("palette-1c-8b.tiff");
+    let mut decoder = rgb8(Decoder::new(File::open(path).unwrap()).unwrap());
+    match decoder.read_region(0, 0, 4, 4).unwrap() {
+        DecodingResult::U8(res) => assert_eq!(res.len(), 4 * 4 * 3),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+const PLANAR_WIDTH: u32 = 37;
+const PLANAR_HEIGHT: u32 = 21;
+
+/// The samples of the planar RGB test images.
+fn planar_sample(x: u32, y: u32, sample: u32) -> u8 {
+    (x * 3 + y * 7 + sample * 50) as u8
+}
+
+fn test_planar_image(file: &str) {
+    use tiff::tags::PlanarConfiguration;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let mut chunky = Vec::new();
+    let mut planar = Vec::new();
+    for y in 0..PLANAR_HEIGHT {
+        for x in 0..PLANAR_WIDTH {
+            chunky.extend((0..3).map(|sample| planar_sample(x, y, sample)));
+        }
+    }
+    for sample in 0..3 {
+        for y in 0..PLANAR_HEIGHT {
+            planar.extend((0..PLANAR_WIDTH).map(|x| planar_sample(x, y, sample)));
+        }
+    }
+
+    let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(decoder.planar_configuration(), PlanarConfiguration::Planar);
+    assert_eq!(decoder.colortype().unwrap(), ColorType::RGB(8));
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => assert_eq!(res, chunky),
+        _ => panic!("Wrong bit depth"),
+    }
+    match decoder.read_region(5, 3, 20, 15).unwrap() {
+        DecodingResult::U8(res) => {
+            let expected: Vec<u8> = (3..18)
+                .flat_map(|y| {
+                    (5..25).flat_map(move |x| (0..3).map(move |s| planar_sample(x, y, s)))
+                })
+                .collect();
+            assert_eq!(res, expected);
+        }
+        _ => panic!("Wrong bit depth"),
+    }
+
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_planar_output(PlanarConfiguration::Planar);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => assert_eq!(res, planar),
+        _ => panic!("Wrong bit depth"),
+    }
+
+    // The chunks of each plane only contain that sample.
+    let chunks = decoder.chunk_count().unwrap();
+    assert_eq!(chunks % 3, 0);
+    let last = chunks - 1;
+    assert_eq!(decoder.chunk_plane(0).unwrap(), 0);
+    assert_eq!(decoder.chunk_plane(last).unwrap(), 2);
+    assert_eq!(
+        decoder.chunk_origin(last).unwrap(),
+        decoder.chunk_origin(chunks / 3 - 1).unwrap()
+    );
+    let (x, y) = decoder.chunk_origin(last).unwrap();
+    let (width, height) = decoder.chunk_data_dimensions(last).unwrap();
+    match decoder.read_chunk(last).unwrap() {
+        DecodingResult::U8(res) => {
+            let expected: Vec<u8> = (y..y + height)
+                .flat_map(|y| (x..x + width).map(move |x| planar_sample(x, y, 2)))
+                .collect();
+            assert_eq!(res, expected);
+        }
+        _ => panic!("Wrong bit depth"),
+    }
+}
+
+#[test]
+fn test_stripped_planar() {
+    test_planar_image("rgb-3c-8b-planar.tiff");
+}
+
+#[test]
+fn test_tiled_planar() {
+    test_planar_image("rgb-3c-8b-planar-tiled.tiff");
+}
+
+#[test]
+fn test_planar_output_limits() {
+    use tiff::decoder::Limits;
+
+    // Rearranging the samples needs a second buffer of the size of the image.
+    let mut limits = Limits::default();
+    limits.decoding_buffer_size = (PLANAR_WIDTH * PLANAR_HEIGHT * 3) as usize;
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("rgb-3c-8b-planar.tiff");
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_limits(limits);
+    match decoder.read_image().unwrap_err() {
+        tiff::TiffError::LimitsExceeded => {}
+        unexpected => panic!("Unexpected error {}", unexpected),
+    }
+
+    let mut limits = Limits::default();
+    limits.decoding_buffer_size = (PLANAR_WIDTH * PLANAR_HEIGHT * 3 * 2) as usize;
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_limits(limits);
+    assert!(decoder.read_image().is_ok());
+}
+
+#[test]
+fn test_chunky_as_planar_output() {
+    use tiff::tags::PlanarConfiguration;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("rgb-3c-8b.tiff");
+    let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+    let chunky = match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => res,
+        _ => panic!("Wrong bit depth"),
+    };
+
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_planar_output(PlanarConfiguration::Planar);
+    let planar = match decoder.read_image().unwrap() {
+        DecodingResult::U8(res) => res,
+        _ => panic!("Wrong bit depth"),
+    };
+
+    let pixels = chunky.len() / 3;
+    for (i, pixel) in chunky.chunks(3).enumerate() {
+        for (sample, &value) in pixel.iter().enumerate() {
+            assert_eq!(planar[sample * pixels + i], value);
+        }
+    }
+}
+
+#[test]
+fn test_floating_point_predictor() {
+    let gradient = |i: usize| (i % 9) as f64 / 2.0 - (i / 9) as f64 * 1.25;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-32b-float-predictor.tiff");
+    let mut decoder = Decoder::new(File::open(path).unwrap()).unwrap();
+    assert_eq!(
+        decoder.find_tag_unsigned::<u16>(Tag::Predictor).unwrap(),
+        Some(3)
+    );
+    match decoder.read_image().unwrap() {
+        DecodingResult::F32(image) => {
+            let expected: Vec<f32> = (0..45).map(|i| gradient(i) as f32).collect();
+            assert_eq!(image, expected);
+        }
+        unexpected => panic!("Unexpected result: {:?}", unexpected),
+    }
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-64b-float-predictor.tiff");
+    let mut decoder = Decoder::new(File::open(path).unwrap()).unwrap();
+    match decoder.read_image().unwrap() {
+        DecodingResult::F64(image) => {
+            let expected: Vec<f64> = (0..45).map(gradient).collect();
+            assert_eq!(image, expected);
+        }
+        unexpected => panic!("Unexpected result: {:?}", unexpected),
+    }
+}
+
+/// Reads a binary PBM file as one byte per pixel, with 1 for black pixels
+fn read_pbm(file: &str) -> (u32, u32, Vec<u8>) {
+    let data = std::fs::read(PathBuf::from(TEST_IMAGE_DIR).join(file)).unwrap();
+    let header: Vec<_> = data.splitn(4, |b| b.is_ascii_whitespace()).collect();
+    assert_eq!(header[0], b"P4");
+    let width: u32 = std::str::from_utf8(header[1]).unwrap().parse().unwrap();
+    let height: u32 = std::str::from_utf8(header[2]).unwrap().parse().unwrap();
+
+    let row_bytes = (width as usize + 7) / 8;
+    let pixels = header[3]
+        .chunks(row_bytes)
+        .take(height as usize)
+        .flat_map(|row| (0..width as usize).map(move |x| row[x / 8] >> (7 - x % 8) & 1))
+        .collect();
+    (width, height, pixels)
+}
+
+fn test_bilevel(file: &str) {
+    let (width, height, pbm) = read_pbm("miniswhite-1c-1b.pbm");
+    // WhiteIsZero images decode with 0 for black pixels.
+    let expected: Vec<u8> = pbm.iter().map(|pixel| 1 - pixel).collect();
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(decoder.colortype().unwrap(), ColorType::Gray(1));
+    assert_eq!(decoder.dimensions().unwrap(), (width, height));
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => assert!(image == expected, "{} differs", file),
+        _ => panic!("Wrong data type"),
+    }
+
+    // Packed rows are returned as stored, with 1 for black pixels.
+    let row_bytes = (width as usize + 7) / 8;
+    let expected_packed: Vec<u8> = pbm
+        .chunks(width as usize)
+        .flat_map(|row| {
+            (0..row_bytes).map(move |byte| {
+                row.iter()
+                    .skip(byte * 8)
+                    .take(8)
+                    .enumerate()
+                    .fold(0, |acc, (bit, pixel)| acc | pixel << (7 - bit))
+            })
+        })
+        .collect();
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_packed_output(true);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => assert!(image == expected_packed, "{} differs", file),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_bilevel_uncompressed() {
+    test_bilevel("miniswhite-1c-1b.tiff");
+}
+
+#[test]
+fn test_bilevel_huffman() {
+    test_bilevel("miniswhite-1c-1b-huffman.tiff");
+}
+
+#[test]
+fn test_bilevel_fax3() {
+    test_bilevel("miniswhite-1c-1b-fax3.tiff");
+}
+
+#[test]
+fn test_bilevel_fax3_2d() {
+    test_bilevel("miniswhite-1c-1b-fax3-2d.tiff");
+}
+
+#[test]
+fn test_bilevel_fax4() {
+    test_bilevel("miniswhite-1c-1b-fax4.tiff");
+}
+
+#[test]
+fn test_bilevel_fax4_lsb_to_msb() {
+    test_bilevel("miniswhite-1c-1b-fax4-lsb.tiff");
+}
+
+#[test]
+fn test_bilevel_fax4_tiled() {
+    test_bilevel("miniswhite-1c-1b-fax4-tiled.tiff");
+}
+
+#[test]
+fn test_bilevel_lsb_to_msb() {
+    test_bilevel("miniswhite-1c-1b-lsb.tiff");
+}
+
+#[test]
+fn test_bilevel_lzw_lsb_to_msb() {
+    test_bilevel("miniswhite-1c-1b-lzw-lsb.tiff");
+}
+
+#[test]
+fn test_unknown_fill_order() {
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    // Unknown fill orders are ignored for other compressions than the CCITT ones.
+    let data: Vec<u8> = (0..8).collect();
+    let mut file = std::io::Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::Gray8>(4, 2).unwrap();
+        image.encoder().write_tag(Tag::FillOrder, 7u16).unwrap();
+        image.write_data(&data).unwrap();
+    }
+
+    let mut decoder = Decoder::new(std::io::Cursor::new(file.into_inner())).unwrap();
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => assert_eq!(image, data),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_sub_images() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b-subifds.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+    let page = SubfileType {
+        page: true,
+        ..SubfileType::default()
+    };
+    let reduced = SubfileType {
+        reduced_resolution: true,
+        ..SubfileType::default()
+    };
+    let mask = SubfileType {
+        mask: true,
+        ..SubfileType::default()
+    };
+    assert_eq!(decoder.subfile_type(), page);
+
+    let images = decoder.images().unwrap();
+    let layout: Vec<_> = images
+        .iter()
+        .map(|image| (image.depth, image.width, image.height, image.subfile_type))
+        .collect();
+    assert_eq!(
+        layout,
+        [
+            (0, 8, 8, page),
+            (1, 4, 4, reduced),
+            (1, 2, 2, reduced),
+            (0, 6, 6, page),
+            (1, 6, 6, mask),
+        ]
+    );
+    assert_eq!(decoder.sub_images().unwrap(), &images[1..3]);
+
+    // Listing the images does not change the current image.
+    assert_eq!(decoder.dimensions().unwrap(), (8, 8));
+    assert!(decoder.more_images());
+
+    decoder.seek_to_ifd(images[2].offset).unwrap();
+    assert_eq!(decoder.dimensions().unwrap(), (2, 2));
+    assert_eq!(decoder.subfile_type(), reduced);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(data) => assert_eq!(data, [200; 4]),
+        _ => panic!("Wrong data type"),
+    }
+
+    decoder.seek_to_image(1).unwrap();
+    assert_eq!(decoder.dimensions().unwrap(), (6, 6));
+    assert!(!decoder.more_images());
+    assert_eq!(decoder.sub_images().unwrap(), &images[4..]);
+
+    decoder.seek_to_image(0).unwrap();
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(data) => assert_eq!(data, (0..64).collect::<Vec<u8>>()),
+        _ => panic!("Wrong data type"),
+    }
+    decoder.next_image().unwrap();
+    assert_eq!(decoder.dimensions().unwrap(), (6, 6));
+    assert!(decoder.seek_to_image(2).is_err());
+}
+
+#[test]
+fn test_deeply_nested_sub_images() {
+    // Each image is the only sub image of the previous one, nested too deep for recursion.
+    const DEPTH: u32 = 20_000;
+    const IFD_LEN: u32 = 2 + 7 * 12 + 4;
+
+    let mut file = b"II*\0\x08\0\0\0".to_vec();
+    for level in 0..DEPTH {
+        let offset = 8 + level * IFD_LEN;
+        let mut entries = vec![
+            (254u16, 1u32),
+            (256, 1),
+            (257, 1),
+            (262, 1),
+            (273, 0),
+            (279, 1),
+        ];
+        if level + 1 < DEPTH {
+            entries.push((330, offset + IFD_LEN));
+        } else {
+            entries.push((305, 0));
+        }
+
+        file.extend_from_slice(&7u16.to_le_bytes());
+        for (tag, value) in entries {
+            file.extend_from_slice(&tag.to_le_bytes());
+            file.extend_from_slice(&4u16.to_le_bytes());
+            file.extend_from_slice(&1u32.to_le_bytes());
+            file.extend_from_slice(&value.to_le_bytes());
+        }
+        file.extend_from_slice(&0u32.to_le_bytes());
+    }
+
+    let mut decoder = Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let images = decoder.images().unwrap();
+    assert_eq!(images.len(), DEPTH as usize);
+    assert_eq!(images.last().unwrap().depth, DEPTH as usize - 1);
+}
+
+const PACKED_WIDTH: u32 = 19;
+const PACKED_HEIGHT: u32 = 11;
+
+/// The samples of the packed test images, hashed to use all of their bits.
+fn packed_sample(x: u32, y: u32, sample: u32, bits: u8) -> u32 {
+    let value = (x * 7 + y * 13 + sample * 5).wrapping_mul(2_654_435_761);
+    value >> (32 - u32::from(bits))
+}
+
+/// Packs the samples of a row most significant bit first, padded to a full byte.
+fn pack_row(samples: &[u32], bits: u8) -> Vec<u8> {
+    let mut packed = vec![0u8; (samples.len() * bits as usize + 7) / 8];
+    for (i, sample) in samples.iter().enumerate() {
+        for bit in 0..bits as usize {
+            if sample >> (bits as usize - 1 - bit) & 1 == 1 {
+                let position = i * bits as usize + bit;
+                packed[position / 8] |= 0x80 >> (position % 8);
+            }
+        }
+    }
+    packed
+}
+
+/// Tests the page of a packed test image with samples of the given size.
+fn test_packed_samples(file: &str, page: usize, bits: u8, samples: u16) {
+    use tiff::tags::PlanarConfiguration;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let open = || Decoder::new(File::open(&path).unwrap()).unwrap();
+
+    let expected: Vec<u32> = (0..PACKED_HEIGHT)
+        .flat_map(|y| {
+            (0..PACKED_WIDTH).flat_map(move |x| {
+                (0..u32::from(samples)).map(move |sample| packed_sample(x, y, sample, bits))
+            })
+        })
+        .collect();
+    let expected_packed: Vec<u8> = expected
+        .chunks((PACKED_WIDTH * u32::from(samples)) as usize)
+        .flat_map(|row| pack_row(row, bits))
+        .collect();
+
+    let mut decoder = open();
+    decoder.seek_to_image(page).unwrap();
+    assert_eq!(decoder.bits_per_sample()[0], bits);
+    let image: Vec<u32> = match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) if bits < 8 => image.into_iter().map(u32::from).collect(),
+        DecodingResult::U16(image) if bits < 16 => image.into_iter().map(u32::from).collect(),
+        DecodingResult::U32(image) => image,
+        _ => panic!("Wrong data type for {} bits", bits),
+    };
+    assert!(image == expected, "{} bits differ", bits);
+
+    let mut decoder = open().with_packed_output(true);
+    decoder.seek_to_image(page).unwrap();
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => assert!(image == expected_packed),
+        _ => panic!("Wrong data type"),
+    }
+
+    // Chunks and regions hold the rows of their pixels only.
+    let (width, height) = decoder.chunk_data_dimensions(1).unwrap();
+    let (x, y) = decoder.chunk_origin(1).unwrap();
+    let chunk: Vec<u32> = (y..y + height)
+        .flat_map(|y| {
+            (x..x + width).flat_map(move |x| {
+                (0..u32::from(samples)).map(move |sample| packed_sample(x, y, sample, bits))
+            })
+        })
+        .collect();
+    let chunk: Vec<u8> = chunk
+        .chunks((width * u32::from(samples)) as usize)
+        .flat_map(|row| pack_row(row, bits))
+        .collect();
+    match decoder.read_chunk(1).unwrap() {
+        DecodingResult::U8(data) => assert!(data == chunk, "{} bit chunk differs", bits),
+        _ => panic!("Wrong data type"),
+    }
+
+    let region: Vec<u32> = (3..9)
+        .flat_map(|y| {
+            (5..18).flat_map(move |x| {
+                (0..u32::from(samples)).map(move |sample| packed_sample(x, y, sample, bits))
+            })
+        })
+        .collect();
+    let region: Vec<u8> = region
+        .chunks(13 * usize::from(samples))
+        .flat_map(|row| pack_row(row, bits))
+        .collect();
+    match decoder.read_region(5, 3, 13, 6).unwrap() {
+        DecodingResult::U8(data) => assert!(data == region, "{} bit region differs", bits),
+        _ => panic!("Wrong data type"),
+    }
+
+    // Rearranged samples are packed again, each plane with its own rows.
+    if samples > 1 {
+        let planes: Vec<u8> = (0..u32::from(samples))
+            .flat_map(|sample| {
+                (0..PACKED_HEIGHT).flat_map(move |y| {
+                    let row: Vec<u32> = (0..PACKED_WIDTH)
+                        .map(|x| packed_sample(x, y, sample, bits))
+                        .collect();
+                    pack_row(&row, bits)
+                })
+            })
+            .collect();
+        let mut decoder = open()
+            .with_packed_output(true)
+            .with_planar_output(PlanarConfiguration::Planar);
+        decoder.seek_to_image(page).unwrap();
+        match decoder.read_image().unwrap() {
+            DecodingResult::U8(image) => assert!(image == planes, "{} bit planes differ", bits),
+            _ => panic!("Wrong data type"),
+        }
+    }
+}
+
+#[test]
+fn test_packed_gray() {
+    // Each size has a page in strips followed by a page in tiles.
+    let sizes = [2, 3, 4, 5, 7, 10, 12, 14, 17, 24, 31];
+    for (page, &bits) in sizes.iter().flat_map(|bits| vec![bits, bits]).enumerate() {
+        test_packed_samples("minisblack-1c-packed.tiff", page, bits, 1);
+    }
+}
+
+#[test]
+fn test_packed_rgb() {
+    let sizes = [1, 5, 6, 10, 12, 20];
+    for (page, &bits) in sizes.iter().flat_map(|bits| vec![bits, bits]).enumerate() {
+        test_packed_samples("rgb-3c-packed.tiff", page, bits, 3);
+    }
+}
+
+const YCBCR_WIDTH: u32 = 13;
+const YCBCR_HEIGHT: u32 = 9;
+
+/// The luma sample of a pixel or a chroma sample of a block of pixels in the subsampled test image.
+fn ycbcr_sample(x: u32, y: u32, sample: u32) -> u8 {
+    (x * 31 + y * 17 + sample * 101) as u8
+}
+
+#[test]
+fn test_ycbcr_subsampling() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("ycbcr-3c-8b-subsampled.tiff");
+    let subsamplings = [(1u16, 1u16), (2, 1), (2, 2), (4, 2), (4, 4)];
+    for (i, &(h, v)) in subsamplings.iter().enumerate() {
+        let (chroma_width, chroma_height) = (
+            (YCBCR_WIDTH + u32::from(h) - 1) / u32::from(h),
+            (YCBCR_HEIGHT + u32::from(v) - 1) / u32::from(v),
+        );
+        let mut expected_planes: Vec<u8> = (0..YCBCR_HEIGHT)
+            .flat_map(|y| (0..YCBCR_WIDTH).map(move |x| ycbcr_sample(x, y, 0)))
+            .collect();
+        for sample in 1..3 {
+            expected_planes.extend(
+                (0..chroma_height)
+                    .flat_map(|y| (0..chroma_width).map(move |x| ycbcr_sample(x, y, sample))),
+            );
+        }
+        let expected: Vec<u8> = (0..YCBCR_HEIGHT)
+            .flat_map(|y| {
+                (0..YCBCR_WIDTH).flat_map(move |x| {
+                    let (chroma_x, chroma_y) = (x / u32::from(h), y / u32::from(v));
+                    vec![
+                        ycbcr_sample(x, y, 0),
+                        ycbcr_sample(chroma_x, chroma_y, 1),
+                        ycbcr_sample(chroma_x, chroma_y, 2),
+                    ]
+                })
+            })
+            .collect();
+
+        // Each subsampling has a page in strips followed by a page in tiles.
+        for page in 2 * i..2 * i + 2 {
+            // The samples are returned as stored by default.
+            let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+            decoder.seek_to_image(page).unwrap();
+            assert_eq!(decoder.colortype().unwrap(), ColorType::YCbCr(8));
+            assert_eq!(decoder.ycbcr_subsampling(), Some((h, v)));
+            match decoder.read_image().unwrap() {
+                DecodingResult::U8(image) => assert!(image == expected, "{}x{} differ", h, v),
+                _ => panic!("Wrong data type"),
+            }
+
+            let mut decoder = Decoder::new(File::open(&path).unwrap())
+                .unwrap()
+                .with_ycbcr_output(YCbCrOutput::Planes);
+            decoder.seek_to_image(page).unwrap();
+            match decoder.read_image().unwrap() {
+                DecodingResult::U8(image) => {
+                    assert!(image == expected_planes, "{}x{} planes differ", h, v)
+                }
+                _ => panic!("Wrong data type"),
+            }
+
+            // A region starting at the second chroma sample in both directions
+            let (x, y) = (u32::from(h), u32::from(v));
+            let mut region_planes: Vec<u8> = (y..YCBCR_HEIGHT)
+                .flat_map(|y| (x..YCBCR_WIDTH).map(move |x| ycbcr_sample(x, y, 0)))
+                .collect();
+            for sample in 1..3 {
+                region_planes.extend(
+                    (1..chroma_height)
+                        .flat_map(|y| (1..chroma_width).map(move |x| ycbcr_sample(x, y, sample))),
+                );
+            }
+            match decoder
+                .read_region(x, y, YCBCR_WIDTH - x, YCBCR_HEIGHT - y)
+                .unwrap()
+            {
+                DecodingResult::U8(region) => {
+                    assert!(region == region_planes, "{}x{} region differs", h, v)
+                }
+                _ => panic!("Wrong data type"),
+            }
+        }
+    }
+
+    // Regions of the 4x2 subsampled page in tiles converted to RGB
+    test_converted_region(
+        "ycbcr-3c-8b-subsampled.tiff",
+        |mut decoder| {
+            decoder.seek_to_image(7).unwrap();
+            decoder.with_ycbcr_output(YCbCrOutput::RGB)
+        },
+        &[(0, 0, 13, 9), (1, 1, 7, 5), (12, 8, 1, 1)],
+    );
+}
+
+#[test]
+fn test_ycbcr_rgb() {
+    // A red and a gray block of 2x2 pixels.
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("ycbcr-3c-8b.tiff");
+    let mut decoder = Decoder::new(File::open(path).unwrap())
+        .unwrap()
+        .with_ycbcr_output(YCbCrOutput::RGB);
+    assert_eq!(decoder.ycbcr_subsampling(), Some((2, 2)));
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => {
+            let red = [254, 0, 0];
+            let gray = [100, 100, 100];
+            let row: Vec<u8> = [red, red, gray, gray].concat();
+            assert_eq!(image, [&row[..], &row[..]].concat());
+        }
+        _ => panic!("Wrong data type"),
+    }
+    match decoder.read_region(0, 0, 4, 2).unwrap() {
+        DecodingResult::U8(region) => assert_eq!(&region[..6], [254, 0, 0, 254, 0, 0]),
+        _ => panic!("Wrong data type"),
+    }
+
+    test_converted_region(
+        "ycbcr-3c-8b.tiff",
+        |decoder| decoder.with_ycbcr_output(YCbCrOutput::RGB),
+        &[(0, 0, 4, 2), (1, 0, 2, 2), (3, 1, 1, 1)],
+    );
+}
+
+/// The L*a*b* values of the pixels of the Lab test images, with L* in 255ths of 100, and their
+/// sRGB colors.
+const LAB_PIXELS: [([i16; 3], [u8; 3]); 6] = [
+    ([255, 0, 0], [255, 255, 255]),
+    ([138, 81, 70], [255, 0, 0]),
+    ([128, 0, 0], [119, 119, 119]),
+    ([0, 0, 0], [0, 0, 0]),
+    ([200, -80, 80], [0, 227, 0]),
+    ([60, 20, -60], [0, 49, 149]),
+];
+
+fn test_lab(file: &str, bits: u8, samples: DecodingResult) {
+    use tiff::decoder::LabOutput;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join(file);
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_lab_output(LabOutput::Lab);
+    assert_eq!(decoder.colortype().unwrap(), ColorType::Lab(bits));
+    match decoder.read_image().unwrap() {
+        DecodingResult::F32(image) => {
+            for (lab, &(expected, _)) in image.chunks(3).zip(LAB_PIXELS.iter()) {
+                let expected = [
+                    f32::from(expected[0]) * 100.0 / 255.0,
+                    f32::from(expected[1]),
+                    f32::from(expected[2]),
+                ];
+                for (value, expected) in lab.iter().zip(expected.iter()) {
+                    assert!((value - expected).abs() < 1e-4, "{:?}", lab);
+                }
+            }
+            match decoder.read_region(1, 0, 2, 1).unwrap() {
+                DecodingResult::F32(region) => assert!(region[..] == image[3..9]),
+                _ => panic!("Wrong data type"),
+            }
+        }
+        _ => panic!("Wrong data type"),
+    }
+
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_lab_output(LabOutput::RGB8);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => {
+            let expected: Vec<u8> = LAB_PIXELS
+                .iter()
+                .flat_map(|&(_, rgb)| rgb.to_vec())
+                .collect();
+            assert_eq!(image, expected);
+        }
+        _ => panic!("Wrong data type"),
+    }
+    // Regions are converted the same way.
+    match decoder.read_region(1, 0, 2, 1).unwrap() {
+        DecodingResult::U8(region) => {
+            assert_eq!(region, [LAB_PIXELS[1].1, LAB_PIXELS[2].1].concat())
+        }
+        _ => panic!("Wrong data type"),
+    }
+    match decoder.read_chunk(0).unwrap() {
+        DecodingResult::U8(chunk) => assert_eq!(chunk.len(), LAB_PIXELS.len() * 3),
+        _ => panic!("Wrong data type"),
+    }
+
+    // The samples are returned as stored by default.
+    let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+    match (decoder.read_image().unwrap(), samples) {
+        (DecodingResult::U8(image), DecodingResult::U8(expected)) => assert_eq!(image, expected),
+        (DecodingResult::U16(image), DecodingResult::U16(expected)) => {
+            assert_eq!(image, expected)
+        }
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_cielab_u8() {
+    let samples: Vec<u8> = LAB_PIXELS
+        .iter()
+        .flat_map(|&(lab, _)| lab.iter().map(|&value| value as u8).collect::<Vec<_>>())
+        .collect();
+    test_lab("cielab-3c-8b.tiff", 8, DecodingResult::U8(samples));
+}
+
+#[test]
+fn test_icclab_u16() {
+    let samples: Vec<u16> = LAB_PIXELS
+        .iter()
+        .flat_map(|&([l, a, b], _)| {
+            vec![
+                l as u16 * 257,
+                (a + 128) as u16 * 256,
+                (b + 128) as u16 * 256,
+            ]
+        })
+        .collect();
+    test_lab("icclab-3c-16b.tiff", 16, DecodingResult::U16(samples));
+}
+
+#[test]
+fn test_logluv_sgilog() {
+    test_image_sum_f32("logluv-3c-16b.tiff", ColorType::XYZ(32), 0.0);
+    test_image_sum_f32("logluv-3c-32b-sgilog.tiff", ColorType::XYZ(32), 109182.57);
+    test_image_sum_f32(
+        "logluv-3c-32b-sgilog-tiled.tiff",
+        ColorType::XYZ(32),
+        109182.57,
+    );
+}
+
+#[test]
+fn test_logluv_sgilog24() {
+    test_image_sum_f32("logluv-3c-32b-sgilog24.tiff", ColorType::XYZ(32), 10043.72);
+}
+
+#[test]
+fn test_logl_sgilog() {
+    test_image_sum_f32("logl-1c-32b-sgilog.tiff", ColorType::Gray(32), 61107.785);
+}
+
+#[test]
+fn test_sgilog_without_log_photometric() {
+    use tiff::encoder::{colortype, TiffEncoder};
+    use tiff::tags::CompressionMethod;
+    use tiff::{TiffError, TiffUnsupportedError};
+
+    // SGILog compression can only be decoded for LogL and LogLuv images.
+    for &method in [CompressionMethod::SGILog, CompressionMethod::SGILog24].iter() {
+        let mut file = std::io::Cursor::new(Vec::new());
+        {
+            let mut tiff = TiffEncoder::new(&mut file).unwrap();
+            let mut image = tiff.new_image::<colortype::RGB8>(4, 2).unwrap();
+            image
+                .encoder()
+                .write_tag(Tag::Compression, method.to_u16())
+                .unwrap();
+            image.write_data(&[0; 24]).unwrap();
+        }
+
+        let mut decoder = Decoder::new(std::io::Cursor::new(file.into_inner())).unwrap();
+        assert_eq!(decoder.colortype().unwrap(), ColorType::RGB(8));
+        let results = vec![
+            decoder.read_image(),
+            decoder.read_chunk(0),
+            decoder.read_region(0, 0, 2, 2),
+        ];
+        for result in results {
+            match result {
+                Err(TiffError::UnsupportedError(
+                    TiffUnsupportedError::UnsupportedCompressionMethod(m),
+                )) => assert_eq!(m, method),
+                unexpected => panic!("Unexpected result {:?}", unexpected),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_logluv_rgb() {
+    use tiff::decoder::LogLuvOutput;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("logluv-3c-32b-sgilog.tiff");
+    let xyz = match Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .read_image()
+        .unwrap()
+    {
+        DecodingResult::F32(image) => image,
+        _ => panic!("Wrong data type"),
+    };
+    let rgb = match Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_log_luv_output(LogLuvOutput::RGB)
+        .read_image()
+        .unwrap()
+    {
+        DecodingResult::F32(image) => image,
+        _ => panic!("Wrong data type"),
+    };
+
+    for (xyz, rgb) in xyz.chunks(3).zip(rgb.chunks(3)) {
+        let expected = [
+            2.690 * xyz[0] - 1.276 * xyz[1] - 0.414 * xyz[2],
+            -1.022 * xyz[0] + 1.978 * xyz[1] + 0.044 * xyz[2],
+            0.061 * xyz[0] - 0.224 * xyz[1] + 1.163 * xyz[2],
+        ];
+        assert_eq!(rgb, expected);
+    }
+
+    // Regions are converted the same way.
+    let mut decoder = Decoder::new(File::open(&path).unwrap())
+        .unwrap()
+        .with_log_luv_output(LogLuvOutput::RGB);
+    let (width, _) = decoder.dimensions().unwrap();
+    match decoder.read_region(0, 1, width, 2).unwrap() {
+        DecodingResult::F32(region) => {
+            let row = width as usize * 3;
+            assert!(region[..] == rgb[row..3 * row]);
+        }
+        _ => panic!("Wrong data type"),
+    }
+}
+
+/// The 16 bit samples of the 7x5 multiband test images, in chunky order.
+fn multiband_samples(samples: usize) -> Vec<u16> {
+    (0..35 * samples)
+        .map(|i| (i as u16).wrapping_mul(997))
+        .collect()
+}
+
+#[test]
+fn test_multiband() {
+    use tiff::tags::ExtraSamples;
+
+    // The samples are interleaved in the first page and stored in planes in the second.
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-7c-16b.tiff");
+    for page in 0..2 {
+        let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+        decoder.seek_to_image(page).unwrap();
+        assert_eq!(
+            decoder.colortype().unwrap(),
+            ColorType::Multiband {
+                bit_depth: 16,
+                num_samples: 7
+            }
+        );
+        assert_eq!(decoder.extra_samples(), [ExtraSamples::Unspecified; 6]);
+        match decoder.read_image().unwrap() {
+            DecodingResult::U16(image) => assert_eq!(image, multiband_samples(7)),
+            _ => panic!("Wrong data type"),
+        }
+    }
+}
+
+#[test]
+fn test_rgb_two_alpha() {
+    use tiff::tags::ExtraSamples;
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("rgb-5c-16b-alpha.tiff");
+    let mut decoder = Decoder::new(File::open(path).unwrap()).unwrap();
+    assert_eq!(
+        decoder.colortype().unwrap(),
+        ColorType::Multiband {
+            bit_depth: 16,
+            num_samples: 5
+        }
+    );
+    assert_eq!(
+        decoder.extra_samples(),
+        [
+            ExtraSamples::UnassociatedAlpha,
+            ExtraSamples::AssociatedAlpha
+        ]
+    );
+    match decoder.read_image().unwrap() {
+        DecodingResult::U16(image) => assert_eq!(image, multiband_samples(5)),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+/// The strips of a number of rows or the square tiles of a size.
+enum Chunks {
+    Strips(u32),
+    Tiles(u32),
+}
+
+/// Encodes an RGB image whose samples differ in size, which libtiff can not write.
+///
+/// The samples after the first three are unspecified extra samples. `data` holds the bytes of
+/// each strip or tile, of all planes one after the other for planar images.
+fn mixed_rgb_image(
+    (width, height): (u32, u32),
+    bits: &[u16],
+    sample_formats: &[u16],
+    planar: bool,
+    chunks: Chunks,
+    data: &[Vec<u8>],
+) -> Vec<u8> {
+    use tiff::encoder::TiffEncoder;
+    use tiff::tags::Tag;
+
+    let mut file = std::io::Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut dir = tiff.new_directory().unwrap();
+        dir.write_tag(Tag::ImageWidth, width).unwrap();
+        dir.write_tag(Tag::ImageLength, height).unwrap();
+        dir.write_tag(Tag::BitsPerSample, bits).unwrap();
+        dir.write_tag(Tag::SampleFormat, sample_formats).unwrap();
+        dir.write_tag(Tag::Compression, 1u16).unwrap();
+        dir.write_tag(Tag::PhotometricInterpretation, 2u16).unwrap();
+        dir.write_tag(Tag::SamplesPerPixel, bits.len() as u16)
+            .unwrap();
+        if bits.len() > 3 {
+            dir.write_tag(Tag::ExtraSamples, &vec![0u16; bits.len() - 3][..])
+                .unwrap();
+        }
+        if planar {
+            dir.write_tag(Tag::PlanarConfiguration, 2u16).unwrap();
+        }
+
+        let mut offsets = Vec::new();
+        let mut byte_counts = Vec::new();
+        for chunk in data {
+            offsets.push(dir.write_data(&chunk[..]).unwrap() as u32);
+            byte_counts.push(chunk.len() as u32);
+        }
+        match chunks {
+            Chunks::Strips(rows) => {
+                dir.write_tag(Tag::RowsPerStrip, rows).unwrap();
+                dir.write_tag(Tag::StripOffsets, &offsets[..]).unwrap();
+                dir.write_tag(Tag::StripByteCounts, &byte_counts[..])
+                    .unwrap();
+            }
+            Chunks::Tiles(size) => {
+                dir.write_tag(Tag::TileWidth, size).unwrap();
+                dir.write_tag(Tag::TileLength, size).unwrap();
+                dir.write_tag(Tag::TileOffsets, &offsets[..]).unwrap();
+                dir.write_tag(Tag::TileByteCounts, &byte_counts[..])
+                    .unwrap();
+            }
+        }
+        dir.finish().unwrap();
+    }
+    file.into_inner()
+}
+
+/// Encodes a 4x3 image of 8 bit RGB samples and a 32 bit floating point quality sample.
+fn rgb_quality_image(planar: bool) -> Vec<u8> {
+    let (width, height) = (4u32, 3u32);
+    let sample = |x: u32, y: u32, sample: u32| (x * 16 + y * 4 + sample) as u8;
+    let quality = |x: u32, y: u32| (x + y * width) as f32 / 8.0;
+
+    let mut strips = Vec::new();
+    if planar {
+        for plane in 0..4 {
+            for rows in &[0..2, 2..height] {
+                let mut strip = Vec::new();
+                for y in rows.clone() {
+                    for x in 0..width {
+                        match plane {
+                            3 => strip.extend_from_slice(&quality(x, y).to_ne_bytes()),
+                            _ => strip.push(sample(x, y, plane)),
+                        }
+                    }
+                }
+                strips.push(strip);
+            }
+        }
+    } else {
+        let mut strip = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                strip.extend((0..3).map(|s| sample(x, y, s)));
+                strip.extend_from_slice(&quality(x, y).to_ne_bytes());
+            }
+        }
+        strips.push(strip);
+    }
+
+    let rows = if planar { 2 } else { height };
+    mixed_rgb_image(
+        (width, height),
+        &[8, 8, 8, 32],
+        &[1, 1, 1, 3],
+        planar,
+        Chunks::Strips(rows),
+        &strips,
+    )
+}
+
+#[test]
+fn test_mixed_sample_formats() {
+    use tiff::tags::SampleFormat;
+    use tiff::{TiffError, TiffUnsupportedError};
+
+    for &planar in &[false, true] {
+        let mut decoder = Decoder::new(std::io::Cursor::new(rgb_quality_image(planar))).unwrap();
+        assert_eq!(
+            decoder.colortype().unwrap(),
+            ColorType::Mixed { num_samples: 4 }
+        );
+        assert_eq!(decoder.bits_per_sample(), [8, 8, 8, 32]);
+        assert_eq!(
+            decoder.sample_formats(),
+            [
+                SampleFormat::Uint,
+                SampleFormat::Uint,
+                SampleFormat::Uint,
+                SampleFormat::IEEEFP
+            ]
+        );
+
+        let channels = decoder.read_image_channels().unwrap();
+        assert_eq!(channels.len(), 4);
+        for (channel, result) in channels.iter().enumerate() {
+            match result {
+                DecodingResult::U8(samples) if channel < 3 => {
+                    let expected: Vec<u8> = (0..3)
+                        .flat_map(|y| (0..4).map(move |x| (x * 16 + y * 4 + channel) as u8))
+                        .collect();
+                    assert_eq!(samples, &expected);
+                }
+                DecodingResult::F32(samples) if channel == 3 => {
+                    let expected: Vec<f32> = (0..12).map(|i| i as f32 / 8.0).collect();
+                    assert_eq!(samples, &expected);
+                }
+                _ => panic!("Wrong data type of channel {}", channel),
+            }
+        }
+
+        match decoder.read_image() {
+            Err(TiffError::UnsupportedError(TiffUnsupportedError::UnsupportedColorType(
+                ColorType::Mixed { num_samples: 4 },
+            ))) => {}
+            unexpected => panic!("Unexpected result {:?}", unexpected),
+        }
+    }
+}
+
+#[test]
+fn test_rgb_565() {
+    // A 6x3 image in 4x4 tiles, the samples of each pixel packed into 16 bits.
+    let (width, height) = (6u32, 3u32);
+    let pixel = |x: u32, y: u32| [(x * 5 + y) % 32, (x * 11 + y * 7) % 64, (x + y * 9) % 32];
+    let tiles: Vec<Vec<u8>> = [0, 4]
+        .iter()
+        .map(|&left| {
+            let mut tile = Vec::new();
+            for y in 0..4 {
+                for x in left..left + 4 {
+                    let [r, g, b] = pixel(x, y);
+                    let packed = (r << 11 | g << 5 | b) as u16;
+                    tile.extend_from_slice(&packed.to_be_bytes());
+                }
+            }
+            tile
+        })
+        .collect();
+    let file = mixed_rgb_image(
+        (width, height),
+        &[5, 6, 5],
+        &[1, 1, 1],
+        false,
+        Chunks::Tiles(4),
+        &tiles,
+    );
+
+    let mut decoder = Decoder::new(std::io::Cursor::new(file)).unwrap();
+    assert_eq!(
+        decoder.colortype().unwrap(),
+        ColorType::Mixed { num_samples: 3 }
+    );
+    let channels = decoder.read_image_channels().unwrap();
+    assert_eq!(channels.len(), 3);
+    for (channel, result) in channels.iter().enumerate() {
+        let expected: Vec<u8> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| pixel(x, y)[channel] as u8))
+            .collect();
+        match result {
+            DecodingResult::U8(samples) => assert_eq!(samples, &expected),
+            _ => panic!("Wrong data type of channel {}", channel),
+        }
+    }
+}
+
+#[test]
+fn test_image_channels() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("rgb-3c-16b.tiff");
+    let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+    let image = match decoder.read_image().unwrap() {
+        DecodingResult::U16(image) => image,
+        _ => panic!("Wrong data type"),
+    };
+
+    let mut decoder = Decoder::new(File::open(&path).unwrap()).unwrap();
+    let channels = decoder.read_image_channels().unwrap();
+    assert_eq!(channels.len(), 3);
+    for (channel, result) in channels.iter().enumerate() {
+        let expected: Vec<u16> = image.iter().skip(channel).step_by(3).cloned().collect();
+        match result {
+            DecodingResult::U16(samples) => assert!(samples == &expected),
+            _ => panic!("Wrong data type of channel {}", channel),
+        }
+    }
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("palette-1c-8b.tiff");
+    let mut decoder = Decoder::new(File::open(path).unwrap())
+        .unwrap()
+        .with_palette_output(PaletteOutput::RGB8);
+    let channels = decoder.read_image_channels().unwrap();
+    assert_eq!(channels.len(), 3);
+}
+
 #[test]
 fn test_div_zero() {
     use tiff::{TiffError, TiffFormatError};
@@ -523,3 +1950,108 @@ fn timeout() {
 fn test_no_rows_per_strip() {
     test_image_sum_u8("no_rows_per_strip.tiff", ColorType::RGB(8), 99448840);
 }
+
+#[test]
+fn test_exif_gps() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b-exif-gps.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    let exif = decoder.exif().unwrap().unwrap();
+    assert!((exif.exposure_time().unwrap() - 0.004).abs() < 1e-9);
+    assert!((exif.f_number().unwrap() - 2.8).abs() < 1e-6);
+    assert!((exif.exposure_bias().unwrap() + 0.7).abs() < 1e-6);
+    assert!((exif.focal_length().unwrap() - 8.8).abs() < 1e-6);
+    assert_eq!(exif.iso_speed(), Some(200));
+    assert_eq!(exif.focal_length_in_35mm_film(), Some(24));
+    assert_eq!(exif.date_time_original(), Some("2021:06:01 12:30:00"));
+
+    let gps = decoder.gps().unwrap().unwrap();
+    assert!((gps.latitude().unwrap() + 33.85875).abs() < 1e-9);
+    assert!((gps.longitude().unwrap() - 151.215).abs() < 1e-9);
+    assert_eq!(gps.altitude(), Some(58.5));
+    assert_eq!(
+        gps.get(GpsTag::VersionID),
+        Some(&ifd::Value::List(vec![
+            ifd::Value::Unsigned(2),
+            ifd::Value::Unsigned(3),
+            ifd::Value::Unsigned(0),
+            ifd::Value::Unsigned(0),
+        ]))
+    );
+    assert_eq!(
+        gps.get(GpsTag::LatitudeRef),
+        Some(&ifd::Value::Ascii("S".into()))
+    );
+
+    // The private IFDs are not images.
+    assert_eq!(decoder.images().unwrap().len(), 1);
+    assert!(decoder.read_image().is_ok());
+}
+
+#[test]
+fn test_exif_cycle() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b-exif-gps.tiff");
+    let mut data = std::fs::read(path).expect("Cannot find test image!");
+
+    // Point the ExifIFD tag at the IFD of the image.
+    let ifd = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    let entry = (0..12)
+        .map(|i| ifd as usize + 2 + 12 * i)
+        .find(|&entry| data[entry..entry + 2] == Tag::ExifIFD.to_u16().to_le_bytes())
+        .unwrap();
+    data[entry + 8..entry + 12].copy_from_slice(&ifd.to_le_bytes());
+
+    let mut decoder = Decoder::new(std::io::Cursor::new(data)).expect("Cannot create decoder");
+    assert!(decoder.exif().is_err());
+    assert!(decoder.gps().unwrap().is_some());
+}
+
+#[test]
+fn test_geotiff() {
+    use tiff::geotiff::GeoKeyValue;
+    use tiff::tags::{GeoKey, ModelType, RasterType};
+
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b-geotiff.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+
+    let geotiff = decoder.geotiff().unwrap().unwrap();
+    assert_eq!(geotiff.model_type(), Some(ModelType::Projected));
+    assert_eq!(geotiff.raster_type(), Some(RasterType::PixelIsArea));
+    assert_eq!(geotiff.epsg(), Some(32633));
+    assert_eq!(geotiff.linear_units(), Some(9001));
+    assert_eq!(geotiff.citation(), Some("WGS 84 / UTM zone 33N"));
+    assert_eq!(geotiff.ascii(GeoKey::GeogCitation), Some("WGS 84"));
+    assert_eq!(
+        geotiff.keys[&GeoKey::GeogInvFlattening],
+        GeoKeyValue::Double(vec![298.257223563])
+    );
+    assert_eq!(
+        geotiff.transform(),
+        Some([30.0, 0.0, 500_000.0, 0.0, -30.0, 4_000_000.0])
+    );
+    assert_eq!(
+        geotiff.pixel_to_model(2.0, 2.0),
+        Some((500_060.0, 3_999_940.0))
+    );
+
+    // A rotated grid with a transformation matrix and without a key directory.
+    decoder.next_image().unwrap();
+    let geotiff = decoder.geotiff().unwrap().unwrap();
+    assert!(geotiff.keys.is_empty());
+    assert_eq!(geotiff.epsg(), None);
+    assert_eq!(geotiff.pixel_to_model(1.0, 3.0), Some((106.0, 198.0)));
+
+    decoder.next_image().unwrap();
+    assert_eq!(decoder.geotiff().unwrap(), None);
+}
+
+#[test]
+fn test_no_exif_gps() {
+    let path = PathBuf::from(TEST_IMAGE_DIR).join("minisblack-1c-8b.tiff");
+    let img_file = File::open(path).expect("Cannot find test image!");
+    let mut decoder = Decoder::new(img_file).expect("Cannot create decoder");
+    assert_eq!(decoder.exif().unwrap(), None);
+    assert_eq!(decoder.gps().unwrap(), None);
+}
@@ -2,7 +2,7 @@ extern crate tiff;
 
 use tiff::decoder::{ifd, Decoder, DecodingResult};
 use tiff::encoder::{colortype, Ifd, Ifd8, SRational, TiffEncoder};
-use tiff::tags::Tag;
+use tiff::tags::{Predictor, Tag};
 use tiff::ColorType;
 
 use std::fs::File;
@@ -519,3 +519,952 @@ fn test_rows_per_strip() {
         }
     }
 }
+
+#[test]
+/// verify tiled encoding with tiles written in arbitrary order
+fn test_tiled_image() {
+    let (width, height) = (100u32, 70u32);
+    let image_data: Vec<u16> = (0..width * height * 3).map(|i| i as u16).collect();
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut img_encoder = TiffEncoder::new(&mut file).unwrap();
+        let mut image = img_encoder
+            .new_tile_image::<colortype::RGB16>(width, height)
+            .unwrap();
+        assert!(image.tile_size(40, 32).is_err());
+        image.tile_size(48, 32).unwrap();
+        assert_eq!((image.tiles_across(), image.tiles_down()), (3, 3));
+        assert_eq!(image.tile_data_dimensions(2, 2), (4, 6));
+
+        for row in (0..3).rev() {
+            for column in 0..3 {
+                let (tile_width, tile_height) = image.tile_data_dimensions(column, row);
+                let mut tile = Vec::new();
+                for y in row * 32..row * 32 + tile_height {
+                    let start = ((y * width + column * 48) * 3) as usize;
+                    tile.extend_from_slice(&image_data[start..][..(tile_width * 3) as usize]);
+                    // The top right tile is given with its full size
+                    if (column, row) == (2, 0) {
+                        tile.resize(tile.len() + (48 - tile_width as usize) * 3, 0);
+                    }
+                }
+                image.write_tile(column, row, &tile).unwrap();
+                assert!(image.write_tile(column, row, &tile).is_err());
+            }
+        }
+
+        assert!(image.tile_size(64, 64).is_err());
+        assert!(image.write_tile(3, 0, &[0; 48 * 32 * 3]).is_err());
+        image.finish().unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    {
+        let mut decoder = Decoder::new(&mut file).unwrap();
+        assert_eq!(decoder.colortype().unwrap(), ColorType::RGB(16));
+        assert_eq!(decoder.dimensions().unwrap(), (width, height));
+        assert_eq!(decoder.chunk_dimensions().unwrap(), (48, 32));
+        match decoder.read_image().unwrap() {
+            DecodingResult::U16(data) => assert_eq!(image_data, data),
+            other => panic!("Incorrect image type {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_tiled_image_incomplete() {
+    let mut file = Cursor::new(Vec::new());
+    let mut img_encoder = TiffEncoder::new(&mut file).unwrap();
+    let mut image = img_encoder
+        .new_tile_image::<colortype::Gray8>(20, 20)
+        .unwrap();
+    image.tile_size(16, 16).unwrap();
+    image.write_tile(0, 0, &[0; 16 * 16]).unwrap();
+    assert!(image.write_tile(1, 0, &[0; 5 * 16]).is_err());
+    assert!(image.finish().is_err());
+}
+
+#[test]
+fn test_tiled_image_undersized_buffer() {
+    let mut file = Cursor::new(Vec::new());
+    let mut img_encoder = TiffEncoder::new(&mut file).unwrap();
+    let mut image = img_encoder
+        .new_tile_image::<colortype::RGB8>(20, 20)
+        .unwrap();
+    image.tile_size(16, 16).unwrap();
+    assert!(image.write_data(&[0; 20 * 20]).is_err());
+}
+
+fn test_palette_roundtrip<C: colortype::ColorType<Inner = u8>>(bits: u8, tiled: bool) {
+    let (width, height) = (37u32, 21u32);
+    let colors = 1usize << bits;
+    let image_data: Vec<u8> = (0..width * height)
+        .map(|i| (i as usize * 7 % colors) as u8)
+        .collect();
+    let color_map: Vec<u16> = (0..3 * colors).map(|i| (i * 997) as u16).collect();
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        if tiled {
+            let mut image = tiff.new_tile_image::<C>(width, height).unwrap();
+            image.tile_size(16, 16).unwrap();
+            image.color_map(&color_map).unwrap();
+            image.write_data(&image_data).unwrap();
+        } else {
+            let mut image = tiff.new_image::<C>(width, height).unwrap();
+            image.rows_per_strip(8).unwrap();
+            assert!(image.color_map(&color_map[1..]).is_err());
+            image.color_map(&color_map).unwrap();
+            image.write_data(&image_data).unwrap();
+        }
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert_eq!(decoder.colortype().unwrap(), ColorType::Palette(bits));
+    assert_eq!(decoder.color_map().unwrap(), &color_map[..]);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(data) => assert_eq!(image_data, data),
+        other => panic!("Incorrect image type {:?}", other),
+    }
+}
+
+#[test]
+fn test_palette_image() {
+    for &tiled in &[false, true] {
+        test_palette_roundtrip::<colortype::Palette1>(1, tiled);
+        test_palette_roundtrip::<colortype::Palette4>(4, tiled);
+        test_palette_roundtrip::<colortype::Palette8>(8, tiled);
+    }
+}
+
+#[test]
+fn test_palette_without_color_map() {
+    let mut file = Cursor::new(Vec::new());
+    let mut tiff = TiffEncoder::new(&mut file).unwrap();
+    assert!(tiff.write_image::<colortype::Palette8>(1, 1, &[0]).is_err());
+
+    let mut image = tiff.new_image::<colortype::Gray8>(1, 1).unwrap();
+    assert!(image.color_map(&[0; 768]).is_err());
+}
+
+#[test]
+fn test_palette_index_out_of_range() {
+    let color_map = [0u16; 3 * 16];
+    let mut data = [3u8; 4 * 4];
+    data[5] = 20;
+
+    let mut file = Cursor::new(Vec::new());
+    let mut tiff = TiffEncoder::new(&mut file).unwrap();
+    let mut image = tiff.new_image::<colortype::Palette4>(4, 4).unwrap();
+    image.color_map(&color_map).unwrap();
+    assert!(image.write_data(&data).is_err());
+
+    let mut image = tiff.new_tile_image::<colortype::Palette4>(4, 4).unwrap();
+    image.tile_size(16, 16).unwrap();
+    image.color_map(&color_map).unwrap();
+    assert!(image.write_data(&data).is_err());
+}
+
+#[test]
+fn test_palette_predictor() {
+    let mut file = Cursor::new(Vec::new());
+    let mut tiff = TiffEncoder::new(&mut file).unwrap();
+    let mut image = tiff.new_image::<colortype::Palette4>(1, 1).unwrap();
+    assert!(image.predictor(Predictor::Horizontal).is_err());
+}
+
+#[test]
+fn test_rgba_extra_samples() {
+    use tiff::tags::ExtraSamples;
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        tiff.write_image::<colortype::RGBA8>(1, 1, &[1, 2, 3, 4])
+            .unwrap();
+
+        let mut image = tiff.new_tile_image::<colortype::RGBA16>(1, 1).unwrap();
+        image
+            .extra_samples(&[ExtraSamples::AssociatedAlpha])
+            .unwrap();
+        image.write_data(&[1, 2, 3, 4]).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert_eq!(decoder.colortype().unwrap(), ColorType::RGBA(8));
+    assert_eq!(decoder.extra_samples(), [ExtraSamples::UnassociatedAlpha]);
+    assert_eq!(decoder.alpha(), Some(ExtraSamples::UnassociatedAlpha));
+
+    decoder.next_image().unwrap();
+    assert_eq!(decoder.colortype().unwrap(), ColorType::RGBA(16));
+    assert_eq!(decoder.alpha(), Some(ExtraSamples::AssociatedAlpha));
+}
+
+#[test]
+fn test_extra_samples_without_alpha() {
+    use tiff::tags::ExtraSamples;
+
+    let mut file = Cursor::new(Vec::new());
+    let mut tiff = TiffEncoder::new(&mut file).unwrap();
+    {
+        let mut image = tiff.new_image::<colortype::RGB8>(1, 1).unwrap();
+        assert!(image
+            .extra_samples(&[ExtraSamples::AssociatedAlpha])
+            .is_err());
+    }
+
+    let mut image = tiff.new_image::<colortype::RGBA8>(1, 1).unwrap();
+    assert!(image.extra_samples(&[]).is_err());
+}
+
+#[test]
+fn test_unassociated_alpha_output() {
+    use tiff::tags::ExtraSamples;
+
+    let image_data = [50u8, 100, 0, 128, 7, 8, 9, 0, 255, 255, 255, 255];
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::RGBA8>(3, 1).unwrap();
+        image
+            .extra_samples(&[ExtraSamples::AssociatedAlpha])
+            .unwrap();
+        image.write_data(&image_data).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => assert_eq!(image, image_data),
+        _ => panic!("Wrong data type"),
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file)
+        .unwrap()
+        .with_unassociated_alpha_output(true);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => {
+            assert_eq!(image, [100, 199, 0, 128, 7, 8, 9, 0, 255, 255, 255, 255])
+        }
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_oriented_output() {
+    use tiff::tags::Orientation;
+
+    // The pixels of a stored 3x2 image as displayed with each orientation.
+    let cases: [(Orientation, (u32, u32), [u8; 6]); 8] = [
+        (Orientation::TopLeft, (3, 2), [0, 1, 2, 3, 4, 5]),
+        (Orientation::TopRight, (3, 2), [2, 1, 0, 5, 4, 3]),
+        (Orientation::BottomRight, (3, 2), [5, 4, 3, 2, 1, 0]),
+        (Orientation::BottomLeft, (3, 2), [3, 4, 5, 0, 1, 2]),
+        (Orientation::LeftTop, (2, 3), [0, 3, 1, 4, 2, 5]),
+        (Orientation::RightTop, (2, 3), [3, 0, 4, 1, 5, 2]),
+        (Orientation::RightBottom, (2, 3), [5, 2, 4, 1, 3, 0]),
+        (Orientation::LeftBottom, (2, 3), [2, 5, 1, 4, 0, 3]),
+    ];
+
+    for &(orientation, dimensions, displayed) in &cases {
+        let mut file = Cursor::new(Vec::new());
+        {
+            let mut tiff = TiffEncoder::new(&mut file).unwrap();
+            let mut image = tiff.new_image::<colortype::Gray8>(3, 2).unwrap();
+            image.orientation(orientation).unwrap();
+            image.write_data(&[0, 1, 2, 3, 4, 5]).unwrap();
+        }
+
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut decoder = Decoder::new(&mut file).unwrap();
+        assert_eq!(decoder.orientation(), orientation);
+        assert_eq!(decoder.dimensions().unwrap(), (3, 2));
+        match decoder.read_image().unwrap() {
+            DecodingResult::U8(image) => assert_eq!(image, [0, 1, 2, 3, 4, 5]),
+            _ => panic!("Wrong data type"),
+        }
+
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut decoder = Decoder::new(&mut file).unwrap().with_oriented_output(true);
+        assert_eq!(decoder.oriented_dimensions(), dimensions);
+        match decoder.read_image().unwrap() {
+            DecodingResult::U8(image) => assert_eq!(image, displayed, "{:?}", orientation),
+            _ => panic!("Wrong data type"),
+        }
+
+        // Chunks and regions are addressed within the stored image.
+        assert_eq!(decoder.dimensions().unwrap(), (3, 2));
+        assert_eq!(decoder.chunk_data_dimensions(0).unwrap(), (3, 2));
+        match decoder.read_region(1, 0, 2, 2).unwrap() {
+            DecodingResult::U8(region) => assert_eq!(region, [1, 2, 4, 5]),
+            _ => panic!("Wrong data type"),
+        }
+    }
+}
+
+#[test]
+fn test_oriented_rgb_output() {
+    use tiff::tags::{Orientation, PlanarConfiguration};
+
+    let image_data = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::RGB8>(2, 2).unwrap();
+        image.orientation(Orientation::RightTop).unwrap();
+        image.write_data(&image_data).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap().with_oriented_output(true);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => {
+            assert_eq!(image, [7, 8, 9, 1, 2, 3, 10, 11, 12, 4, 5, 6])
+        }
+        _ => panic!("Wrong data type"),
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file)
+        .unwrap()
+        .with_oriented_output(true)
+        .with_planar_output(PlanarConfiguration::Planar);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(image) => {
+            assert_eq!(image, [7, 1, 10, 4, 8, 2, 11, 5, 9, 3, 12, 6])
+        }
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_overviews() {
+    use tiff::encoder::overview::{OverviewLayout, Overviews, Resampling};
+    use tiff::gdal::NoData;
+    use tiff::geotiff::GeoTiff;
+    use tiff::tags::{ExtraSamples, Orientation, SubfileType};
+
+    let reduced = SubfileType {
+        reduced_resolution: true,
+        ..SubfileType::default()
+    };
+    let data = [0, 2, 4, 6, 2, 4, 6, 8];
+    let cases = [
+        (Resampling::Nearest, [0, 4], 0),
+        (Resampling::Average, [2, 6], 4),
+        (Resampling::Gaussian, [2, 6], 4),
+    ];
+
+    for &layout in &[OverviewLayout::Chained, OverviewLayout::SubIfds] {
+        for &(resampling, first, second) in &cases {
+            let mut file = Cursor::new(Vec::new());
+            {
+                let mut tiff = TiffEncoder::new(&mut file).unwrap();
+                let image = tiff.new_image::<colortype::Gray8>(4, 2).unwrap();
+                let overviews = Overviews {
+                    levels: 2,
+                    resampling,
+                    layout,
+                };
+                image.write_data_with_overviews(&data, &overviews).unwrap();
+                // The next image follows the pyramid in the main chain.
+                tiff.write_image::<colortype::Gray8>(1, 1, &[9]).unwrap();
+            }
+
+            file.seek(SeekFrom::Start(0)).unwrap();
+            let mut decoder = Decoder::new(&mut file).unwrap();
+            let images = decoder.images().unwrap();
+            let depth = match layout {
+                OverviewLayout::Chained => 0,
+                OverviewLayout::SubIfds => 1,
+            };
+            let layout: Vec<_> = images
+                .iter()
+                .map(|image| (image.depth, image.width, image.height, image.subfile_type))
+                .collect();
+            assert_eq!(
+                layout,
+                [
+                    (0, 4, 2, SubfileType::default()),
+                    (depth, 2, 1, reduced),
+                    (depth, 1, 1, reduced),
+                    (0, 1, 1, SubfileType::default()),
+                ]
+            );
+
+            let expected: [&[u8]; 4] = [&data, &first, &[second], &[9]];
+            for (image, expected) in images.iter().zip(&expected) {
+                decoder.seek_to_ifd(image.offset).unwrap();
+                match decoder.read_image().unwrap() {
+                    DecodingResult::U8(samples) => {
+                        assert_eq!(samples, *expected, "{:?}", resampling)
+                    }
+                    _ => panic!("Wrong data type"),
+                }
+            }
+        }
+    }
+
+    // The tags describing the samples and the georeferencing hold for all levels.
+    let geotiff = GeoTiff::default()
+        .with_projected_epsg(32633)
+        .with_transform([30.0, 0.0, 500_000.0, 0.0, -30.0, 4_000_000.0]);
+
+    for &layout in &[OverviewLayout::Chained, OverviewLayout::SubIfds] {
+        let mut file = Cursor::new(Vec::new());
+        {
+            let mut tiff = TiffEncoder::new(&mut file).unwrap();
+            let mut image = tiff.new_image::<colortype::RGBA8>(4, 2).unwrap();
+            image
+                .extra_samples(&[ExtraSamples::AssociatedAlpha])
+                .unwrap();
+            image.orientation(Orientation::BottomLeft).unwrap();
+            image.geotiff(&geotiff).unwrap();
+            image.gdal_nodata(NoData::U8(7)).unwrap();
+            let overviews = Overviews {
+                levels: 2,
+                resampling: Resampling::Average,
+                layout,
+            };
+            image
+                .write_data_with_overviews(&[0; 32], &overviews)
+                .unwrap();
+            tiff.write_image::<colortype::Gray8>(1, 1, &[9]).unwrap();
+        }
+
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut decoder = Decoder::new(&mut file).unwrap();
+        let images = decoder.images().unwrap();
+        assert_eq!(images.len(), 4);
+
+        // The pixels of the 2x1 and 1x1 overviews span 2x2 and 4x2 pixels of the image.
+        let pixel_sizes = [(30.0, 30.0), (60.0, 60.0), (120.0, 60.0)];
+        for (image, &(width, height)) in images.iter().zip(pixel_sizes.iter()) {
+            decoder.seek_to_ifd(image.offset).unwrap();
+            assert_eq!(decoder.alpha(), Some(ExtraSamples::AssociatedAlpha));
+            assert_eq!(decoder.orientation(), Orientation::BottomLeft);
+            assert_eq!(decoder.gdal_nodata().unwrap(), Some(NoData::U8(7)));
+            let overview_geotiff = decoder.geotiff().unwrap().unwrap();
+            assert_eq!(overview_geotiff.epsg(), Some(32633));
+            assert_eq!(
+                overview_geotiff.transform(),
+                Some([width, 0.0, 500_000.0, 0.0, -height, 4_000_000.0])
+            );
+        }
+
+        // The tags are not copied to the following image.
+        decoder.seek_to_ifd(images[3].offset).unwrap();
+        assert_eq!(decoder.orientation(), Orientation::TopLeft);
+        assert_eq!(decoder.geotiff().unwrap(), None);
+    }
+}
+
+#[test]
+fn test_rgb_overviews() {
+    use tiff::encoder::compression::Lzw;
+    use tiff::encoder::overview::{OverviewLayout, Overviews, Resampling};
+
+    // A 3x3 image whose channels are the column, row and a constant.
+    let mut data = Vec::new();
+    for y in 0..3u16 {
+        for x in 0..3u16 {
+            data.extend_from_slice(&[x * 100, y * 100, 1000]);
+        }
+    }
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff
+            .new_image_with_compression::<colortype::RGB16, _>(3, 3, Lzw)
+            .unwrap();
+        image.predictor(Predictor::Horizontal).unwrap();
+        let overviews = Overviews {
+            levels: 1,
+            resampling: Resampling::Average,
+            layout: OverviewLayout::Chained,
+        };
+        image.write_data_with_overviews(&data, &overviews).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    decoder.seek_to_image(1).unwrap();
+    assert_eq!(decoder.dimensions().unwrap(), (2, 2));
+    assert_eq!(
+        decoder.find_tag_unsigned::<u16>(Tag::Predictor).unwrap(),
+        Some(2)
+    );
+    match decoder.read_image().unwrap() {
+        DecodingResult::U16(samples) => assert_eq!(
+            samples,
+            [50, 50, 1000, 200, 50, 1000, 50, 200, 1000, 200, 200, 1000]
+        ),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_nearest_overviews_of_64_bit_samples() {
+    use tiff::encoder::overview::{OverviewLayout, Overviews, Resampling};
+
+    // Samples that can not be represented exactly as a double.
+    let data = [(1 << 53) + 1, 1, u64::MAX, 2];
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let image = tiff.new_image::<colortype::Gray64>(2, 2).unwrap();
+        let overviews = Overviews {
+            levels: 1,
+            resampling: Resampling::Nearest,
+            layout: OverviewLayout::Chained,
+        };
+        image.write_data_with_overviews(&data, &overviews).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    decoder.seek_to_image(1).unwrap();
+    match decoder.read_image().unwrap() {
+        DecodingResult::U64(samples) => assert_eq!(samples, [(1 << 53) + 1]),
+        _ => panic!("Wrong data type"),
+    }
+}
+
+#[test]
+fn test_no_overviews_in_sub_ifds() {
+    use tiff::encoder::overview::{OverviewLayout, Overviews, Resampling};
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let image = tiff.new_image::<colortype::Gray8>(2, 2).unwrap();
+        let overviews = Overviews {
+            levels: 0,
+            resampling: Resampling::Average,
+            layout: OverviewLayout::SubIfds,
+        };
+        image
+            .write_data_with_overviews(&[1, 2, 3, 4], &overviews)
+            .unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert!(decoder.find_tag(Tag::SubIFDs).unwrap().is_none());
+    assert_eq!(decoder.images().unwrap().len(), 1);
+}
+
+fn sub_ifd_overviews<K: tiff::encoder::TiffKind>() -> Vec<ifd::Value> {
+    use tiff::encoder::overview::{OverviewLayout, Overviews, Resampling};
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::<_, K>::new_generic(&mut file).unwrap();
+        let image = tiff.new_image::<colortype::Gray8>(4, 4).unwrap();
+        let overviews = Overviews {
+            levels: 2,
+            resampling: Resampling::Nearest,
+            layout: OverviewLayout::SubIfds,
+        };
+        image
+            .write_data_with_overviews(&[0; 16], &overviews)
+            .unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    match decoder.get_tag(Tag::SubIFDs).unwrap() {
+        ifd::Value::List(pointers) => pointers,
+        value => panic!("Wrong value {:?}", value),
+    }
+}
+
+#[test]
+fn test_overview_sub_ifd_pointers() {
+    use tiff::encoder::{TiffKindBig, TiffKindStandard};
+
+    let pointers = sub_ifd_overviews::<TiffKindStandard>();
+    assert_eq!(pointers.len(), 2);
+    assert!(pointers
+        .iter()
+        .all(|pointer| matches!(pointer, ifd::Value::Ifd(_))));
+
+    let pointers = sub_ifd_overviews::<TiffKindBig>();
+    assert_eq!(pointers.len(), 2);
+    assert!(pointers
+        .iter()
+        .all(|pointer| matches!(pointer, ifd::Value::IfdBig(_))));
+}
+
+#[test]
+fn test_geotiff_roundtrip() {
+    use tiff::geotiff::{GeoKeyValue, GeoTiff};
+    use tiff::tags::{GeoKey, RasterType};
+
+    let geotiff = GeoTiff::default()
+        .with_projected_epsg(32633)
+        .with_raster_type(RasterType::PixelIsPoint)
+        .with_citation("WGS 84 / UTM zone 33N")
+        .with_key(GeoKey::GeogCitation, GeoKeyValue::Ascii("WGS 84".into()))
+        .with_key(
+            GeoKey::GeogInvFlattening,
+            GeoKeyValue::Double(vec![298.257223563]),
+        )
+        .with_key(GeoKey::Unknown(60000), GeoKeyValue::Short(vec![7, 8]))
+        .with_transform([30.0, 0.0, 500_000.0, 0.0, -30.0, 4_000_000.0]);
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::Gray8>(2, 2).unwrap();
+        image.geotiff(&geotiff).unwrap();
+        image.write_data(&[0; 4]).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert_eq!(
+        decoder.get_tag_u16_vec(Tag::GeoKeyDirectoryTag).unwrap(),
+        [
+            1, 1, 0, 7, // header
+            1024, 0, 1, 1, // projected
+            1025, 0, 1, 2, // pixel is point
+            1026, 34737, 22, 0, // citation
+            2049, 34737, 7, 22, // geographic citation
+            2059, 34736, 1, 0, // inverse flattening
+            3072, 0, 1, 32633, // UTM zone 33N
+            60000, 34735, 2, 32, // shorts after the entries
+            7, 8,
+        ]
+    );
+    assert_eq!(
+        decoder
+            .get_tag_ascii_string(Tag::GeoAsciiParamsTag)
+            .unwrap(),
+        "WGS 84 / UTM zone 33N|WGS 84|"
+    );
+    assert_eq!(
+        decoder.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap(),
+        [0.0, 0.0, 0.0, 500_000.0, 4_000_000.0, 0.0]
+    );
+    assert_eq!(decoder.geotiff().unwrap(), Some(geotiff));
+}
+
+#[test]
+fn test_geotiff_rotated_roundtrip() {
+    use tiff::geotiff::GeoTiff;
+
+    let transform = [0.0, 0.5, 10.0, -0.5, 0.0, 50.0];
+    let geotiff = GeoTiff::default()
+        .with_geographic_epsg(4326)
+        .with_transform(transform);
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_tile_image::<colortype::Gray8>(2, 2).unwrap();
+        image.geotiff(&geotiff).unwrap();
+        image.write_data(&[0; 4]).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert!(decoder.find_tag(Tag::ModelTiepointTag).unwrap().is_none());
+    let decoded = decoder.geotiff().unwrap().unwrap();
+    assert_eq!(decoded.epsg(), Some(4326));
+    assert_eq!(decoded.transform(), Some(transform));
+    assert_eq!(decoded, geotiff);
+}
+
+#[test]
+fn test_geotiff_south_up_roundtrip() {
+    use tiff::geotiff::GeoTiff;
+
+    // The y axis points down in model space, which a pixel scale can not describe.
+    let transform = [30.0, 0.0, 500_000.0, 0.0, 30.0, 4_000_000.0];
+    let geotiff = GeoTiff::default().with_transform(transform);
+    assert!(geotiff.pixel_scale.is_none());
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::Gray8>(2, 2).unwrap();
+        image.geotiff(&geotiff).unwrap();
+        image.write_data(&[0; 4]).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert!(decoder.find_tag(Tag::ModelPixelScaleTag).unwrap().is_none());
+    let decoded = decoder.geotiff().unwrap().unwrap();
+    assert_eq!(decoded.transform(), Some(transform));
+    assert_eq!(
+        decoded.pixel_to_model(1.0, 1.0),
+        Some((500_030.0, 4_000_030.0))
+    );
+}
+
+fn gdal_nodata<C: colortype::ColorType>(
+    value: &str,
+    data: &[C::Inner],
+) -> tiff::TiffResult<Option<tiff::gdal::NoData>>
+where
+    [C::Inner]: tiff::encoder::TiffValue,
+{
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<C>(1, 1).unwrap();
+        image.encoder().write_tag(Tag::GdalNodata, value).unwrap();
+        image.write_data(data).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    Decoder::new(&mut file).unwrap().gdal_nodata()
+}
+
+#[test]
+fn test_gdal_nodata() {
+    use tiff::gdal::NoData;
+
+    let gray = gdal_nodata::<colortype::Gray8>;
+    assert_eq!(gray("255", &[0]).unwrap(), Some(NoData::U8(255)));
+    assert_eq!(gray(" 0.0 ", &[0]).unwrap(), Some(NoData::U8(0)));
+    assert_eq!(gray("-1", &[0]).unwrap(), None);
+    assert!(gray("none", &[0]).is_err());
+
+    let nodata = gdal_nodata::<colortype::GrayI16>("-9999", &[0]).unwrap();
+    assert_eq!(nodata, Some(NoData::I16(-9999)));
+    let nodata = gdal_nodata::<colortype::Gray64Float>("-3.5e2", &[0.0]).unwrap();
+    assert_eq!(nodata, Some(NoData::F64(-350.0)));
+    match gdal_nodata::<colortype::Gray32Float>("nan", &[0.0]).unwrap() {
+        Some(NoData::F32(value)) => assert!(value.is_nan()),
+        nodata => panic!("Wrong nodata {:?}", nodata),
+    }
+}
+
+#[test]
+fn test_gdal_nodata_without_sample_format() {
+    use tiff::gdal::NoData;
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::Gray8>(1, 1).unwrap();
+        let encoder = image.encoder();
+        encoder.write_tag(Tag::GdalNodata, "255").unwrap();
+        encoder
+            .write_tag(Tag::SampleFormat, &[][..] as &[u16])
+            .unwrap();
+        image.write_data(&[0]).unwrap();
+    }
+
+    // Without any sample format the samples are unsigned integers.
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert_eq!(decoder.gdal_nodata().unwrap(), Some(NoData::U8(255)));
+}
+
+#[test]
+fn test_gdal_nodata_mask() {
+    use tiff::gdal::NoData;
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::Gray32Float>(2, 2).unwrap();
+        image.gdal_nodata(NoData::F32(f32::NAN)).unwrap();
+        image.write_data(&[1.0, f32::NAN, 3.0, f32::NAN]).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    assert_eq!(
+        decoder.get_tag_ascii_string(Tag::GdalNodata).unwrap(),
+        "nan"
+    );
+    let nodata = decoder.gdal_nodata().unwrap().unwrap();
+    let image = decoder.read_image().unwrap();
+    assert_eq!(nodata.mask(&image), [false, true, false, true]);
+    assert_eq!(NoData::U8(1).mask(&image), [false; 4]);
+}
+
+#[test]
+fn test_gdal_metadata() {
+    use tiff::gdal::{GdalMetadata, Statistics};
+
+    let statistics = Statistics {
+        minimum: Some(0.0),
+        maximum: Some(255.0),
+        mean: Some(127.5),
+        std_dev: None,
+    };
+    let metadata = GdalMetadata::default()
+        .with_item("AREA_OR_POINT", None, "Area")
+        .with_item("NOTE", None, "a < b & \"c\"")
+        .with_scale(0, 0.01)
+        .with_offset(0, -10.0)
+        .with_description(0, "Red")
+        .with_statistics(0, statistics)
+        .with_description(1, "Green");
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::new(&mut file).unwrap();
+        let mut image = tiff.new_tile_image::<colortype::Gray8>(1, 1).unwrap();
+        image.gdal_metadata(&metadata).unwrap();
+        image.write_data(&[0]).unwrap();
+    }
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut decoder = Decoder::new(&mut file).unwrap();
+    let decoded = decoder.gdal_metadata().unwrap().unwrap();
+    assert_eq!(decoded, metadata);
+    assert_eq!(decoded.item("AREA_OR_POINT", None), Some("Area"));
+    assert_eq!(decoded.item("NOTE", None), Some("a < b & \"c\""));
+    assert_eq!(decoded.scale(0), Some(0.01));
+    assert_eq!(decoded.offset(0), Some(-10.0));
+    assert_eq!(decoded.scale(1), None);
+    assert_eq!(decoded.description(1), Some("Green"));
+    assert_eq!(decoded.statistics(0), statistics);
+}
+
+#[test]
+fn test_gdal_metadata_xml() {
+    use tiff::gdal::GdalMetadata;
+
+    let xml = "<GDALMetadata>\n  \
+        <Item name=\"SCALE\" sample=\"0\" role=\"scale\">2</Item>\n  \
+        <Item name='OFFSET' role='offset' sample='0'>0.5</Item>\n  \
+        <Item name=\"EMPTY\" />\n  \
+        <Item name=\"SCALE\" sample=\"0\" domain=\"OTHER\">3</Item>\n\
+        </GDALMetadata>\n";
+    let metadata = GdalMetadata::from_xml(xml).unwrap();
+    assert_eq!(metadata.items.len(), 4);
+    assert_eq!(metadata.scale(0), Some(2.0));
+    assert_eq!(metadata.offset(0), Some(0.5));
+    assert_eq!(metadata.item("EMPTY", None), Some(""));
+    assert_eq!(metadata.items[3].domain.as_deref(), Some("OTHER"));
+
+    assert!(GdalMetadata::from_xml("<GDALMetadata><Item name=\"A\">1</GDALMetadata>").is_err());
+}
+
+fn write_exif_gps<K: tiff::encoder::TiffKind>() -> Cursor<Vec<u8>> {
+    use tiff::encoder::Rational;
+    use tiff::tags::GpsTag;
+
+    let mut file = Cursor::new(Vec::new());
+    {
+        let mut tiff = TiffEncoder::<_, K>::new_generic(&mut file).unwrap();
+        let mut image = tiff.new_image::<colortype::Gray8>(2, 2).unwrap();
+
+        let mut exif = image.encoder().sub_directory(Tag::ExifIFD).unwrap();
+        exif.write_tag(Tag::ExposureTime, Rational { n: 1, d: 250 })
+            .unwrap();
+        exif.write_tag(Tag::FocalLength, Rational { n: 88, d: 10 })
+            .unwrap();
+        exif.write_tag(Tag::ISOSpeedRatings, &[400u16][..]).unwrap();
+        exif.write_tag(Tag::DateTimeOriginal, "2021:06:01 12:30:00")
+            .unwrap();
+        let mut interoperability = exif.sub_directory(Tag::InteroperabilityIFD).unwrap();
+        interoperability.write_tag(Tag::Unknown(1), "R98").unwrap();
+        interoperability.finish().unwrap();
+        exif.finish().unwrap();
+
+        let mut gps = image.encoder().sub_directory(Tag::GPSInfo).unwrap();
+        gps.write_gps_tag(GpsTag::VersionID, &[2u8, 3, 0, 0][..])
+            .unwrap();
+        gps.write_gps_tag(GpsTag::LatitudeRef, "N").unwrap();
+        let latitude = [
+            Rational { n: 48, d: 1 },
+            Rational { n: 51, d: 1 },
+            Rational { n: 295, d: 10 },
+        ];
+        gps.write_gps_tag(GpsTag::Latitude, &latitude[..]).unwrap();
+        gps.write_gps_tag(GpsTag::LongitudeRef, "W").unwrap();
+        let longitude = [
+            Rational { n: 2, d: 1 },
+            Rational { n: 17, d: 1 },
+            Rational { n: 402, d: 10 },
+        ];
+        gps.write_gps_tag(GpsTag::Longitude, &longitude[..])
+            .unwrap();
+        gps.write_gps_tag(GpsTag::AltitudeRef, 1u8).unwrap();
+        gps.write_gps_tag(GpsTag::Altitude, Rational { n: 35, d: 1 })
+            .unwrap();
+        gps.finish().unwrap();
+
+        image.write_data(&[1, 2, 3, 4]).unwrap();
+    }
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file
+}
+
+fn check_exif_gps(file: Cursor<Vec<u8>>) -> Decoder<Cursor<Vec<u8>>> {
+    let mut decoder = Decoder::new(file).unwrap();
+    let exif = decoder.exif().unwrap().unwrap();
+    assert_eq!(exif.exposure_time(), Some(0.004));
+    assert_eq!(exif.focal_length(), Some(8.8));
+    assert_eq!(exif.iso_speed(), Some(400));
+    assert_eq!(exif.date_time_original(), Some("2021:06:01 12:30:00"));
+
+    let gps = decoder.gps().unwrap().unwrap();
+    assert!((gps.latitude().unwrap() - 48.858194).abs() < 1e-6);
+    assert!((gps.longitude().unwrap() + 2.2945).abs() < 1e-6);
+    assert_eq!(gps.altitude(), Some(-35.0));
+
+    // The sub directories do not change the image.
+    assert_eq!(decoder.images().unwrap().len(), 1);
+    match decoder.read_image().unwrap() {
+        DecodingResult::U8(data) => assert_eq!(data, [1, 2, 3, 4]),
+        _ => panic!("Wrong data type"),
+    }
+    decoder
+}
+
+#[test]
+fn test_exif_gps_sub_directories() {
+    use tiff::encoder::TiffKindStandard;
+
+    let mut decoder = check_exif_gps(write_exif_gps::<TiffKindStandard>());
+    assert!(matches!(
+        decoder.get_tag(Tag::ExifIFD).unwrap(),
+        ifd::Value::Ifd(_)
+    ));
+    assert!(matches!(
+        decoder.get_tag(Tag::GPSInfo).unwrap(),
+        ifd::Value::Ifd(_)
+    ));
+    let exif = decoder.exif().unwrap().unwrap();
+    assert!(matches!(
+        exif.get(Tag::InteroperabilityIFD),
+        Some(ifd::Value::Ifd(_))
+    ));
+}
+
+#[test]
+fn test_exif_gps_sub_directories_bigtiff() {
+    use tiff::encoder::TiffKindBig;
+
+    let mut decoder = check_exif_gps(write_exif_gps::<TiffKindBig>());
+    assert!(matches!(
+        decoder.get_tag(Tag::ExifIFD).unwrap(),
+        ifd::Value::IfdBig(_)
+    ));
+    assert!(matches!(
+        decoder.get_tag(Tag::GPSInfo).unwrap(),
+        ifd::Value::IfdBig(_)
+    ));
+    let exif = decoder.exif().unwrap().unwrap();
+    assert!(matches!(
+        exif.get(Tag::InteroperabilityIFD),
+        Some(ifd::Value::IfdBig(_))
+    ));
+}
@@ -8,6 +8,7 @@ use tiff::{
         compression::*,
         TiffEncoder, TiffValue,
     },
+    tags::{Predictor, Tag},
 };
 
 trait TestImage<const NUM_CHANNELS: usize>: From<Vec<<Self::Color as ColorType>::Inner>> {
@@ -134,14 +135,47 @@ fn encode_decode_with_compression<C: Compression + Clone>(compression: C) {
     }
 }
 
+fn encode_decode_tiled_with_compression<C: Compression>(compression: C) {
+    let mut data = Cursor::new(Vec::new());
+    let image = TestImageGrayscale::generate();
+
+    {
+        let mut encoder = TiffEncoder::new(&mut data).unwrap();
+        let mut tiled = encoder
+            .new_tile_image_with_compression::<colortype::Gray8, C>(
+                TestImageGrayscale::WIDTH,
+                TestImageGrayscale::HEIGHT,
+                compression,
+            )
+            .unwrap();
+        tiled.tile_size(16, 16).unwrap();
+        tiled.write_data(image.reference_data()).unwrap();
+    }
+
+    data.set_position(0);
+    {
+        let mut decoder = Decoder::new(data).unwrap();
+        assert_eq!(decoder.tile_count().unwrap(), 2);
+        assert_eq!(
+            match decoder.read_image() {
+                Ok(DecodingResult::U8(image_data)) => image_data,
+                unexpected => panic!("Decoding tiled image failed: {:?}", unexpected),
+            },
+            image.reference_data()
+        );
+    }
+}
+
 #[test]
 fn encode_decode_without_compression() {
     encode_decode_with_compression(Uncompressed::default());
+    encode_decode_tiled_with_compression(Uncompressed::default());
 }
 
 #[test]
 fn encode_decode_with_lzw() {
     encode_decode_with_compression(Lzw::default());
+    encode_decode_tiled_with_compression(Lzw::default());
 }
 
 #[test]
@@ -149,9 +183,127 @@ fn encode_decode_with_deflate() {
     encode_decode_with_compression(Deflate::with_level(DeflateLevel::Fast));
     encode_decode_with_compression(Deflate::with_level(DeflateLevel::Balanced));
     encode_decode_with_compression(Deflate::with_level(DeflateLevel::Best));
+    encode_decode_tiled_with_compression(Deflate::with_level(DeflateLevel::Best));
 }
 
 #[test]
 fn encode_decode_with_packbits() {
     encode_decode_with_compression(Packbits::default());
+    encode_decode_tiled_with_compression(Packbits::default());
+}
+
+macro_rules! test_predictor_roundtrip {
+    ($name:ident, $color:ident, $buffer:ident, $inner:ty) => {
+        test_predictor_roundtrip!($name, Horizontal, $color, $buffer, $inner, |i: u32| {
+            (i.wrapping_mul(2_654_435_761) >> 7) as $inner
+        });
+    };
+    ($name:ident, $predictor:ident, $color:ident, $buffer:ident, $inner:ty, $sample:expr) => {
+        #[test]
+        fn $name() {
+            let (width, height) = (37, 23);
+            let samples = colortype::$color::BITS_PER_SAMPLE.len() as u32;
+            let image: Vec<$inner> = (0..width * height * samples).map($sample).collect();
+
+            let mut data = Cursor::new(Vec::new());
+            {
+                let mut encoder = TiffEncoder::new(&mut data).unwrap();
+
+                let mut stripped = encoder
+                    .new_image_with_compression::<colortype::$color, _>(width, height, Lzw)
+                    .unwrap();
+                stripped.rows_per_strip(5).unwrap();
+                stripped.predictor(Predictor::$predictor).unwrap();
+                stripped.write_data(&image).unwrap();
+
+                let mut tiled = encoder
+                    .new_tile_image_with_compression::<colortype::$color, _>(
+                        width,
+                        height,
+                        Deflate::default(),
+                    )
+                    .unwrap();
+                tiled.tile_size(16, 16).unwrap();
+                tiled.predictor(Predictor::$predictor).unwrap();
+                tiled.write_data(&image).unwrap();
+            }
+
+            data.set_position(0);
+            let mut decoder = Decoder::new(data).unwrap();
+            for _ in 0..2 {
+                assert_eq!(
+                    decoder.get_tag_u32(Tag::Predictor).unwrap(),
+                    Predictor::$predictor.to_u16().into()
+                );
+                match decoder.read_image() {
+                    Ok(DecodingResult::$buffer(image_data)) => assert_eq!(image_data, image),
+                    unexpected => panic!("Decoding failed: {:?}", unexpected),
+                }
+                if decoder.more_images() {
+                    decoder.next_image().unwrap();
+                }
+            }
+        }
+    };
+}
+
+test_predictor_roundtrip!(predictor_gray_u8, Gray8, U8, u8);
+test_predictor_roundtrip!(predictor_gray_i8, GrayI8, I8, i8);
+test_predictor_roundtrip!(predictor_gray_u16, Gray16, U16, u16);
+test_predictor_roundtrip!(predictor_gray_i16, GrayI16, I16, i16);
+test_predictor_roundtrip!(predictor_gray_u32, Gray32, U32, u32);
+test_predictor_roundtrip!(predictor_gray_i32, GrayI32, I32, i32);
+test_predictor_roundtrip!(predictor_gray_u64, Gray64, U64, u64);
+test_predictor_roundtrip!(predictor_gray_i64, GrayI64, I64, i64);
+test_predictor_roundtrip!(predictor_rgb_u8, RGB8, U8, u8);
+test_predictor_roundtrip!(predictor_rgb_u16, RGB16, U16, u16);
+test_predictor_roundtrip!(predictor_rgb_u32, RGB32, U32, u32);
+test_predictor_roundtrip!(predictor_rgb_u64, RGB64, U64, u64);
+test_predictor_roundtrip!(predictor_rgba_u8, RGBA8, U8, u8);
+test_predictor_roundtrip!(predictor_rgba_u16, RGBA16, U16, u16);
+test_predictor_roundtrip!(predictor_rgba_u32, RGBA32, U32, u32);
+test_predictor_roundtrip!(predictor_rgba_u64, RGBA64, U64, u64);
+test_predictor_roundtrip!(predictor_cmyk_u8, CMYK8, U8, u8);
+test_predictor_roundtrip!(predictor_cmyk_u16, CMYK16, U16, u16);
+test_predictor_roundtrip!(predictor_cmyk_u32, CMYK32, U32, u32);
+test_predictor_roundtrip!(predictor_cmyk_u64, CMYK64, U64, u64);
+
+macro_rules! test_float_predictor_roundtrip {
+    ($name:ident, $color:ident, $buffer:ident, $inner:ty) => {
+        test_predictor_roundtrip!($name, FloatingPoint, $color, $buffer, $inner, |i: u32| {
+            let x = i as $inner;
+            (x * 0.37).sin() * x - 1.0 / (x + 0.5)
+        });
+    };
+}
+
+test_float_predictor_roundtrip!(predictor_gray_f32, Gray32Float, F32, f32);
+test_float_predictor_roundtrip!(predictor_gray_f64, Gray64Float, F64, f64);
+test_float_predictor_roundtrip!(predictor_rgb_f32, RGB32Float, F32, f32);
+test_float_predictor_roundtrip!(predictor_rgb_f64, RGB64Float, F64, f64);
+test_float_predictor_roundtrip!(predictor_rgba_f32, RGBA32Float, F32, f32);
+test_float_predictor_roundtrip!(predictor_rgba_f64, RGBA64Float, F64, f64);
+test_float_predictor_roundtrip!(predictor_cmyk_f32, CMYK32Float, F32, f32);
+test_float_predictor_roundtrip!(predictor_cmyk_f64, CMYK64Float, F64, f64);
+
+#[test]
+fn predictor_rejects_float() {
+    let mut data = Cursor::new(Vec::new());
+    let mut encoder = TiffEncoder::new(&mut data).unwrap();
+    let mut image = encoder
+        .new_image_with_compression::<colortype::RGB32Float, _>(1, 1, Lzw)
+        .unwrap();
+    assert!(image.predictor(Predictor::Horizontal).is_err());
+    image.predictor(Predictor::None).unwrap();
+}
+
+#[test]
+fn floating_point_predictor_rejects_integers() {
+    let mut data = Cursor::new(Vec::new());
+    let mut encoder = TiffEncoder::new(&mut data).unwrap();
+    let mut image = encoder
+        .new_tile_image_with_compression::<colortype::RGB16, _>(1, 1, Lzw)
+        .unwrap();
+    assert!(image.predictor(Predictor::FloatingPoint).is_err());
+    image.predictor(Predictor::None).unwrap();
 }